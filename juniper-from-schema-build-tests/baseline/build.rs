@@ -0,0 +1,6 @@
+fn main() {
+    juniper_from_schema_build::configure_for_file("schema.graphql")
+        .baseline("baseline.graphql")
+        .compile()
+        .unwrap();
+}
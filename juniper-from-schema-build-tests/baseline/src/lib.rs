@@ -0,0 +1,41 @@
+#![allow(unused_braces)]
+
+use juniper::{Executor, FieldResult, ID};
+use juniper_from_schema::Connection;
+
+juniper_from_schema::include_schema!();
+
+#[derive(Debug)]
+pub struct Context;
+
+impl juniper::Context for Context {}
+
+#[derive(Debug)]
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users(
+        &self,
+        _: &Executor<Context>,
+        _: &QueryTrail<UserConnection, Walked>,
+        _: Option<i32>,
+        _: Option<String>,
+        _: Option<i32>,
+        _: Option<String>,
+    ) -> FieldResult<Connection<User>> {
+        todo!()
+    }
+}
+
+#[derive(Debug)]
+pub struct User;
+
+impl UserFields for User {
+    fn field_id(&self, _: &Executor<Context>) -> FieldResult<&ID> {
+        todo!()
+    }
+
+    fn field_name(&self, _: &Executor<Context>) -> FieldResult<&Option<String>> {
+        todo!()
+    }
+}
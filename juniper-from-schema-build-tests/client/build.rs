@@ -0,0 +1,11 @@
+fn main() {
+    juniper_from_schema_build::configure_for_file("schema.graphql")
+        .persisted_queries("persisted")
+        .compile()
+        .unwrap();
+
+    juniper_from_schema_build::configure_for_file("schema.graphql")
+        .output_name("client")
+        .compile_client("operations.graphql")
+        .unwrap();
+}
@@ -0,0 +1,100 @@
+use juniper::{Executor, FieldResult, ID};
+
+juniper_from_schema::include_schema!();
+
+pub mod operations {
+    juniper_from_schema::include_schema!("client");
+}
+
+pub struct Context;
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_user(
+        &self,
+        _: &Executor<Context>,
+        _: &QueryTrail<User, Walked>,
+        id: ID,
+    ) -> FieldResult<Option<User>> {
+        Ok(Some(User {
+            id,
+            name: "Bob".to_string(),
+            status: Status::Active,
+        }))
+    }
+
+    fn field_search(
+        &self,
+        _: &Executor<Context>,
+        _: &QueryTrail<SearchResult, Walked>,
+        _query: String,
+    ) -> FieldResult<Vec<SearchResult>> {
+        Ok(vec![
+            SearchResult::from(User {
+                id: ID::new("1"),
+                name: "Bob".to_string(),
+                status: Status::Active,
+            }),
+            SearchResult::from(Post {
+                id: ID::new("2"),
+                title: "Hello".to_string(),
+            }),
+        ])
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_set_status(
+        &self,
+        _: &Executor<Context>,
+        _: &QueryTrail<User, Walked>,
+        id: ID,
+        status: Status,
+    ) -> FieldResult<User> {
+        Ok(User {
+            id,
+            name: "Bob".to_string(),
+            status,
+        })
+    }
+}
+
+pub struct User {
+    id: ID,
+    name: String,
+    status: Status,
+}
+
+impl UserFields for User {
+    fn field_id(&self, _: &Executor<Context>) -> FieldResult<&ID> {
+        Ok(&self.id)
+    }
+
+    fn field_name(&self, _: &Executor<Context>) -> FieldResult<&String> {
+        Ok(&self.name)
+    }
+
+    fn field_status(&self, _: &Executor<Context>) -> FieldResult<&Status> {
+        Ok(&self.status)
+    }
+}
+
+pub struct Post {
+    id: ID,
+    title: String,
+}
+
+impl PostFields for Post {
+    fn field_id(&self, _: &Executor<Context>) -> FieldResult<&ID> {
+        Ok(&self.id)
+    }
+
+    fn field_title(&self, _: &Executor<Context>) -> FieldResult<&String> {
+        Ok(&self.title)
+    }
+}
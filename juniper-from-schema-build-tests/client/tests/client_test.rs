@@ -0,0 +1,60 @@
+use client::{operations, Context, Mutation, Query, Schema};
+use juniper::{EmptySubscription, Variables, ID};
+use serde::{de::DeserializeOwned, Serialize};
+
+fn execute<B: Serialize, T: DeserializeOwned>(body: &B) -> T {
+    let body = serde_json::to_value(body).unwrap();
+    let query = body["query"].as_str().unwrap();
+    let variables: Variables = serde_json::from_value(body["variables"].clone()).unwrap();
+
+    let schema = Schema::new(Query, Mutation, EmptySubscription::new());
+    let (res, errors) =
+        futures::executor::block_on(juniper::execute(query, None, &schema, &variables, &Context))
+            .unwrap();
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    serde_json::from_value(serde_json::to_value(&res).unwrap()).unwrap()
+}
+
+#[test]
+fn query_with_fragment() {
+    let data: operations::get_user::ResponseData = execute(&operations::get_user::request_body(
+        operations::get_user::Variables { id: ID::new("1") },
+    ));
+
+    let user = data.user.unwrap();
+    assert_eq!(ID::new("1"), user.id);
+    assert_eq!("Bob", user.display_name);
+    assert_eq!(operations::Status::Active, user.status);
+}
+
+#[test]
+fn query_with_type_conditions() {
+    use operations::search::ResponseDataSearch;
+
+    let data: operations::search::ResponseData = execute(&operations::search::request_body(
+        operations::search::Variables {
+            query: "foo".to_string(),
+        },
+    ));
+
+    match &data.search[..] {
+        [ResponseDataSearch::User(user), ResponseDataSearch::Post(post)] => {
+            assert_eq!("Bob", user.name);
+            assert_eq!("Hello", post.title);
+        }
+        other => panic!("unexpected response {:?}", other),
+    }
+}
+
+#[test]
+fn mutation_with_enum_variable() {
+    let data: operations::disable::ResponseData = execute(&operations::disable::request_body(
+        operations::disable::Variables {
+            id: ID::new("1"),
+            status: operations::Status::Disabled,
+        },
+    ));
+
+    assert_eq!(operations::Status::Disabled, data.set_status.status);
+}
@@ -0,0 +1,35 @@
+use client::{Context, Mutation, Query, Schema, PERSISTED_QUERIES};
+use juniper::{EmptySubscription, Variables};
+
+#[test]
+fn persisted_queries_are_keyed_by_hash() {
+    let hashes = PERSISTED_QUERIES
+        .iter()
+        .map(|(hash, _)| *hash)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        vec![
+            "4692c8dff9137985c246b7b8b0063ff95461c7933657d578b090718f4964e4e3",
+            "6a5a05c2f88da0515e843fa0982885e3851b7aec8a21b8c39e6148244011406a",
+        ],
+        hashes
+    );
+}
+
+#[test]
+fn persisted_queries_execute() {
+    let schema = Schema::new(Query, Mutation, EmptySubscription::new());
+
+    for (_, document) in PERSISTED_QUERIES {
+        let (_, errors) = futures::executor::block_on(juniper::execute(
+            document,
+            None,
+            &schema,
+            &Variables::new(),
+            &Context,
+        ))
+        .unwrap();
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+}
@@ -0,0 +1,12 @@
+fn main() {
+    juniper_from_schema_build::configure_for_file("schemas/public.graphql")
+        .output_name("public")
+        .compile()
+        .unwrap();
+
+    juniper_from_schema_build::configure_for_file("schemas/admin.graphql")
+        .output_name("admin")
+        .module("admin")
+        .compile()
+        .unwrap();
+}
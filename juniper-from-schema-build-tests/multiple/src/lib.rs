@@ -0,0 +1,48 @@
+use juniper::{EmptyMutation, EmptySubscription, Executor, FieldResult};
+
+juniper_from_schema::include_schema!("admin");
+
+pub mod public {
+    use juniper::{Executor, FieldResult};
+
+    juniper_from_schema::include_schema!("public");
+
+    pub struct Context;
+
+    impl juniper::Context for Context {}
+
+    pub struct Query;
+
+    impl QueryFields for Query {
+        fn field_ping(&self, _: &Executor<Context>) -> FieldResult<bool> {
+            Ok(true)
+        }
+    }
+}
+
+pub struct Context;
+
+impl juniper::Context for Context {}
+
+pub struct AdminQuery;
+
+impl admin::AdminQueryFields for AdminQuery {
+    fn field_ping(&self, _: &Executor<Context>) -> FieldResult<bool> {
+        Ok(true)
+    }
+
+    fn field_user_count(&self, _: &Executor<Context>) -> FieldResult<i32> {
+        Ok(0)
+    }
+}
+
+pub fn schemas() -> (public::Schema, admin::Schema) {
+    (
+        public::Schema::new(
+            public::Query,
+            EmptyMutation::new(),
+            EmptySubscription::new(),
+        ),
+        admin::Schema::new(AdminQuery, EmptyMutation::new(), EmptySubscription::new()),
+    )
+}
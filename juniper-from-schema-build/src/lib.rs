@@ -37,6 +37,47 @@
 //!
 //! // the rest of your code...
 //! ```
+//!
+//! # Multiple schemas
+//!
+//! Each schema needs its own output name, set with [`CodeGen::output_name`].
+//!
+//! ```no_run
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     juniper_from_schema_build::configure_for_file("schemas/public.graphql")
+//!         .output_name("public")
+//!         .compile()?;
+//!
+//!     juniper_from_schema_build::configure_for_file("schemas/admin.graphql")
+//!         .output_name("admin")
+//!         .compile()?;
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Include each schema in its own module so the generated items don't clash:
+//!
+//! ```ignore
+//! mod public {
+//!     juniper_from_schema::include_schema!("public");
+//!
+//!     // the types implementing the `*Fields` traits...
+//! }
+//!
+//! mod admin {
+//!     juniper_from_schema::include_schema!("admin");
+//!
+//!     // the types implementing the `*Fields` traits...
+//! }
+//! ```
+//!
+//! Alternatively [`CodeGen::module`] wraps the generated code in a module for you. The types
+//! implementing the `*Fields` traits then live outside the generated module, so the schemas must
+//! not share type names.
+//!
+//! [`CodeGen::output_name`]: struct.CodeGen.html#method.output_name
+//! [`CodeGen::module`]: struct.CodeGen.html#method.module
 
 #![deny(
     dead_code,
@@ -48,9 +89,11 @@
 #![recursion_limit = "256"]
 #![doc(html_root_url = "https://docs.rs/juniper-from-schema-build/0.5.2")]
 
+use juniper_from_schema_code_gen::{Lint, LintLevel};
 use std::{
     env,
     error::Error,
+    ffi::OsStr,
     fs,
     path::{Path, PathBuf},
 };
@@ -68,6 +111,12 @@ pub fn configure_for_schema_literal(schema: &str) -> CodeGen {
         schema: SchemaLocation::Literal(schema.to_string()),
         context_type: None,
         error_type: None,
+        output_name: DEFAULT_OUTPUT_NAME.to_string(),
+        module: None,
+        persisted_queries: None,
+        baseline: None,
+        lints: Vec::new(),
+        max_list_nesting_depth: None,
     }
 }
 
@@ -87,6 +136,12 @@ pub fn configure_for_file<P: AsRef<Path>>(path: P) -> CodeGen {
         schema: SchemaLocation::File(path),
         context_type: None,
         error_type: None,
+        output_name: DEFAULT_OUTPUT_NAME.to_string(),
+        module: None,
+        persisted_queries: None,
+        baseline: None,
+        lints: Vec::new(),
+        max_list_nesting_depth: None,
     }
 }
 
@@ -96,8 +151,16 @@ pub struct CodeGen {
     schema: SchemaLocation,
     context_type: Option<Result<syn::Type, Box<dyn Error>>>,
     error_type: Option<Result<syn::Type, Box<dyn Error>>>,
+    output_name: String,
+    module: Option<Result<syn::Ident, Box<dyn Error>>>,
+    persisted_queries: Option<PathBuf>,
+    baseline: Option<PathBuf>,
+    lints: Vec<Result<(Lint, LintLevel), Box<dyn Error>>>,
+    max_list_nesting_depth: Option<usize>,
 }
 
+const DEFAULT_OUTPUT_NAME: &str = "graphql_schema";
+
 #[derive(Debug)]
 enum SchemaLocation {
     File(PathBuf),
@@ -125,11 +188,212 @@ impl CodeGen {
         self
     }
 
+    /// Set the name of the generated file.
+    ///
+    /// Required when compiling several schemas in the same crate. The code is included with
+    /// `juniper_from_schema::include_schema!("<name>")`. The name must only contain characters
+    /// that are valid in a file name.
+    pub fn output_name(mut self, name: &str) -> Self {
+        self.output_name = name.to_string();
+        self
+    }
+
+    /// Wrap the generated code in `pub mod <module> { ... }`.
+    ///
+    /// Use this when including several schemas in the same module, so the generated items, such as
+    /// the `Schema` type alias, don't clash. Items from the module the code is included in, such
+    /// as the context type and your query type, are in scope inside the generated module.
+    pub fn module(mut self, module: &str) -> Self {
+        self.module = Some(syn::parse_str(module).map_err(From::from));
+        self
+    }
+
+    /// Validate the persisted operations in `dir` against the schema.
+    ///
+    /// Every `.graphql` file in the directory is checked when compiling and the build fails if an
+    /// operation selects unknown fields, passes arguments of the wrong type, or leaves out
+    /// required arguments. The generated code then includes a static allowlist of the documents
+    /// keyed by the hex encoded SHA-256 hash of the file contents:
+    ///
+    /// ```ignore
+    /// pub static PERSISTED_QUERIES: &[(&str, &str)] = &[(hash, document), ...];
+    /// ```
+    ///
+    /// The path is relative to the crate root, like the schema path.
+    pub fn persisted_queries<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        let root = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+        self.persisted_queries = Some(root.join(dir));
+        self
+    }
+
+    /// Fail the build if the schema has breaking changes compared to the schema at `path`.
+    ///
+    /// Useful for committing the schema that is currently deployed and making sure it stays
+    /// compatible with existing clients. Removed fields, new required arguments, and so on are
+    /// reported as errors. Dangerous changes, such as new enum values, are allowed. The schemas
+    /// are compared with their `@connection` fields expanded, so changes to the generated
+    /// connection types are checked too.
+    ///
+    /// The path is relative to the crate root, like the schema path.
+    pub fn baseline<P: AsRef<Path>>(mut self, path: P) -> Self {
+        let root = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+        self.baseline = Some(root.join(path));
+        self
+    }
+
+    /// Report problems found by `lint` as errors, failing the build.
+    ///
+    /// See the juniper-from-schema docs for the supported lints. Takes precedence over the
+    /// `@lints` directive in the schema.
+    pub fn deny(self, lint: &str) -> Self {
+        self.lint(lint, LintLevel::Deny)
+    }
+
+    /// Report problems found by `lint` as warnings. They are printed as `cargo:warning`.
+    pub fn warn(self, lint: &str) -> Self {
+        self.lint(lint, LintLevel::Warn)
+    }
+
+    /// Turn off `lint`.
+    pub fn allow(self, lint: &str) -> Self {
+        self.lint(lint, LintLevel::Allow)
+    }
+
+    /// Set how deeply lists can be nested before the `list_nesting_depth` lint triggers.
+    pub fn max_list_nesting_depth(mut self, depth: usize) -> Self {
+        self.max_list_nesting_depth = Some(depth);
+        self
+    }
+
+    fn lint(mut self, lint: &str, level: LintLevel) -> Self {
+        self.lints
+            .push(lint.parse().map(|lint| (lint, level)).map_err(From::from));
+        self
+    }
+
     /// Compile the GraphQL schema.
+    ///
+    /// The generated code is formatted so it can be read and debugged like any other Rust file.
+    /// The file is only written if its contents changed, so crates including it are not rebuilt
+    /// needlessly.
     pub fn compile(self) -> Result<(), Box<dyn Error>> {
         let out_dir = env::var_os("OUT_DIR").unwrap();
-        let dest_path = Path::new(&out_dir).join("juniper_from_schema_graphql_schema.rs");
+        let dest_path =
+            Path::new(&out_dir).join(format!("juniper_from_schema_{}.rs", self.output_name));
+
+        self.check_baseline()?;
+
+        let (code, warnings) = self
+            .into_code_gen()?
+            .generate_formatted_code_with_warnings()?;
+
+        for line in warnings.to_string().lines() {
+            println!("cargo:warning={}", line);
+        }
+
+        write_if_changed(&dest_path, &code)?;
+
+        println!("cargo:rerun-if-changed=build.rs");
 
+        Ok(())
+    }
+
+    /// Generate typed client code for the GraphQL operations in `operations_path`.
+    ///
+    /// The operations are validated against the schema and the build fails if they select fields
+    /// that don't exist, use undefined variables, and so on. The code is written the same way as
+    /// [`compile`] so give it its own [`output_name`] if the schema is also compiled for the
+    /// server.
+    ///
+    /// The path is relative to the crate root, like the schema path.
+    ///
+    /// [`compile`]: #method.compile
+    /// [`output_name`]: #method.output_name
+    pub fn compile_client<P: AsRef<Path>>(self, operations_path: P) -> Result<(), Box<dyn Error>> {
+        let out_dir = env::var_os("OUT_DIR").unwrap();
+        let dest_path =
+            Path::new(&out_dir).join(format!("juniper_from_schema_{}.rs", self.output_name));
+
+        let root = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+        let operations_path = root.join(operations_path);
+        let operations = fs::read_to_string(&operations_path)?;
+
+        // The client code doesn't include the schema so tell cargo about it explicitly
+        if let SchemaLocation::File(schema_path) = &self.schema {
+            println!("cargo:rerun-if-changed={}", schema_path.display());
+        }
+        println!("cargo:rerun-if-changed={}", operations_path.display());
+
+        let code = self
+            .into_code_gen()?
+            .generate_formatted_client_code(&operations)?;
+
+        write_if_changed(&dest_path, &code)?;
+
+        println!("cargo:rerun-if-changed=build.rs");
+
+        Ok(())
+    }
+
+    /// Generate a formatted Rust file with `todo!()` implementations of every `*Fields` trait.
+    ///
+    /// The `*Resolvers`, `*ReferenceResolver`, and `NodeResolver` traits are included as well.
+    /// The traits of batched fields aren't, since their `Key` type has to be chosen by you.
+    ///
+    /// Useful as a starting point when adding new types to the schema. The returned code is
+    /// meant to be saved as a regular source file in your crate, not to be included from
+    /// `OUT_DIR`.
+    pub fn scaffold(self) -> Result<String, Box<dyn Error>> {
+        Ok(self.into_code_gen()?.generate_scaffold()?)
+    }
+
+    /// Like [`scaffold`] but only includes the methods that are missing from `existing_code`.
+    ///
+    /// Traits that aren't implemented at all get a complete `impl` block. For traits that are
+    /// partially implemented only the missing methods are included, after a comment naming the
+    /// `impl` block they belong in. That part of the output isn't valid Rust on its own, the
+    /// methods must be copied into the existing implementation.
+    ///
+    /// [`scaffold`]: #method.scaffold
+    pub fn scaffold_missing(self, existing_code: &str) -> Result<String, Box<dyn Error>> {
+        Ok(self
+            .into_code_gen()?
+            .generate_missing_scaffold(existing_code)?)
+    }
+
+    fn check_baseline(&self) -> Result<(), Box<dyn Error>> {
+        let baseline_path = match &self.baseline {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        println!("cargo:rerun-if-changed={}", baseline_path.display());
+
+        let baseline = fs::read_to_string(baseline_path)?;
+        let schema = match &self.schema {
+            SchemaLocation::File(path) => {
+                // The check runs in the build script so the schema must trigger a rerun as well
+                println!("cargo:rerun-if-changed={}", path.display());
+                fs::read_to_string(path)?
+            }
+            SchemaLocation::Literal(schema) => schema.clone(),
+        };
+
+        let diff = juniper_from_schema_code_gen::diff_schemas(&baseline, &schema)?;
+
+        if diff.is_breaking() {
+            return Err(format!(
+                "the schema has breaking changes compared to {}\n\n{}",
+                baseline_path.display(),
+                diff
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    fn into_code_gen(self) -> Result<juniper_from_schema_code_gen::CodeGen, Box<dyn Error>> {
         let mut code_gen = match self.schema {
             SchemaLocation::File(path) => {
                 juniper_from_schema_code_gen::CodeGen::build_from_schema_file(path)
@@ -147,12 +411,61 @@ impl CodeGen {
             code_gen = code_gen.error_type(error_type?);
         }
 
-        let code = code_gen.finish().generate_code()?;
+        if let Some(module) = self.module {
+            code_gen = code_gen.module(module?);
+        }
 
-        fs::write(&dest_path, code.to_string())?;
+        for lint in self.lints {
+            let (lint, level) = lint?;
+            code_gen = code_gen.lint(lint, level);
+        }
 
-        println!("cargo:rerun-if-changed=build.rs");
+        if let Some(depth) = self.max_list_nesting_depth {
+            code_gen = code_gen.max_list_nesting_depth(depth);
+        }
 
-        Ok(())
+        if let Some(dir) = self.persisted_queries {
+            for path in persisted_query_paths(&dir)? {
+                let document = fs::read_to_string(&path)?;
+                println!("cargo:rerun-if-changed={}", path.display());
+                code_gen = code_gen.persisted_query(path.display().to_string(), document);
+            }
+
+            // Also rerun when files are added or removed
+            println!("cargo:rerun-if-changed={}", dir.display());
+        }
+
+        Ok(code_gen.finish())
+    }
+}
+
+// The `.graphql` files in `dir`, sorted so the generated code doesn't depend on the order the file
+// system lists them in.
+fn persisted_query_paths(dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut paths = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension() == Some(OsStr::new("graphql")) {
+            paths.push(path);
+        }
     }
+
+    paths.sort();
+
+    Ok(paths)
+}
+
+// Only touch the file if the code actually changed. Otherwise the new modification time would
+// cause everything that includes it to be rebuilt.
+fn write_if_changed(path: &Path, contents: &str) -> Result<(), Box<dyn Error>> {
+    if let Ok(existing) = fs::read_to_string(path) {
+        if existing == contents {
+            return Ok(());
+        }
+    }
+
+    fs::write(path, contents)?;
+
+    Ok(())
 }
@@ -0,0 +1,264 @@
+//! Command line tool for [juniper-from-schema](https://crates.io/crates/juniper-from-schema).
+//!
+//! Run `juniper-from-schema --help` for the list of subcommands.
+
+#![deny(unused_imports, dead_code, unused_variables, unused_must_use)]
+
+use juniper_from_schema_code_gen::{
+    diagnostics_to_json, diagnostics_to_sarif, diff_schemas, explain, CodeGen, Warnings,
+    CONNECTION_DIRECTIVE_DEFINITION, COST_DIRECTIVE_DEFINITION, JUNIPER_DIRECTIVE_DEFINITION,
+};
+use std::{error::Error, fs, path::PathBuf, process, str::FromStr};
+use structopt::StructOpt;
+
+/// Generate Juniper code from a GraphQL schema.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "juniper-from-schema")]
+enum Opt {
+    /// Validate a schema and print any errors or warnings.
+    Check {
+        #[structopt(flatten)]
+        schema: SchemaOpt,
+
+        /// How to print errors and warnings: `human`, `json`, or `sarif`.
+        #[structopt(long, default_value = "human")]
+        message_format: MessageFormat,
+    },
+
+    /// Generate the formatted Rust code for a schema.
+    Generate {
+        #[structopt(flatten)]
+        schema: SchemaOpt,
+
+        #[structopt(flatten)]
+        output: OutputOpt,
+    },
+
+    /// Generate `todo!()` implementations of every `*Fields` trait, and the resolver traits.
+    Scaffold {
+        #[structopt(flatten)]
+        schema: SchemaOpt,
+
+        #[structopt(flatten)]
+        output: OutputOpt,
+
+        /// Only include methods that are missing from this Rust file. Methods of traits the file
+        /// already implements are printed without their `impl` block, to be merged into it.
+        #[structopt(long, parse(from_os_str))]
+        missing_from: Option<PathBuf>,
+    },
+
+    /// Generate typed client code for the operations in a `.graphql` file.
+    Client {
+        #[structopt(flatten)]
+        schema: SchemaOpt,
+
+        /// Path to the file containing the GraphQL operations.
+        #[structopt(long, parse(from_os_str))]
+        operations: PathBuf,
+
+        #[structopt(flatten)]
+        output: OutputOpt,
+    },
+
+    /// Compare a schema to a baseline and classify each change as breaking, dangerous, or safe.
+    ///
+    /// Exits with an error if any of the changes are breaking.
+    Diff {
+        /// Path to the baseline schema, for example the one currently deployed.
+        #[structopt(parse(from_os_str))]
+        baseline: PathBuf,
+
+        /// Path to the new schema.
+        #[structopt(parse(from_os_str))]
+        schema: PathBuf,
+    },
+
+    /// Print the long-form explanation of an error code, such as `JFS0015`.
+    Explain {
+        /// The error code.
+        code: String,
+    },
+
+    /// Print the definitions of the `@juniper`, `@cost` and `@connection` directives.
+    PrintDirective,
+}
+
+#[derive(Debug, StructOpt)]
+struct SchemaOpt {
+    /// Path to the GraphQL schema file.
+    #[structopt(parse(from_os_str))]
+    schema: PathBuf,
+
+    /// The context type. Defaults to `Context`.
+    #[structopt(long, parse(try_from_str = parse_type))]
+    context_type: Option<syn::Type>,
+
+    /// The error type. Defaults to `juniper::FieldError`.
+    #[structopt(long, parse(try_from_str = parse_type))]
+    error_type: Option<syn::Type>,
+}
+
+impl SchemaOpt {
+    fn into_code_gen(self) -> CodeGen {
+        let mut builder = CodeGen::build_from_schema_file(self.schema);
+        if let Some(context_type) = self.context_type {
+            builder = builder.context_type(context_type);
+        }
+        if let Some(error_type) = self.error_type {
+            builder = builder.error_type(error_type);
+        }
+        builder.finish()
+    }
+}
+
+#[derive(Debug, StructOpt)]
+struct OutputOpt {
+    /// Write the code to this file instead of stdout.
+    #[structopt(short, long, parse(from_os_str))]
+    output: Option<PathBuf>,
+}
+
+impl OutputOpt {
+    fn write(self, code: String) -> Result<(), Box<dyn Error>> {
+        match self.output {
+            Some(path) => fs::write(path, code)?,
+            None => print!("{}", code),
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+enum MessageFormat {
+    Human,
+    Json,
+    Sarif,
+}
+
+impl FromStr for MessageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(MessageFormat::Human),
+            "json" => Ok(MessageFormat::Json),
+            "sarif" => Ok(MessageFormat::Sarif),
+            other => Err(format!(
+                "Unknown message format `{}`. Supported formats are `human`, `json`, and `sarif`",
+                other
+            )),
+        }
+    }
+}
+
+fn print_warnings(warnings: &Warnings) {
+    if !warnings.is_empty() {
+        eprintln!("{}\n", warnings);
+    }
+}
+
+fn parse_type(s: &str) -> syn::Result<syn::Type> {
+    syn::parse_str(s)
+}
+
+fn main() {
+    if let Err(err) = run(Opt::from_args()) {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
+
+fn run(opt: Opt) -> Result<(), Box<dyn Error>> {
+    match opt {
+        Opt::Check {
+            schema,
+            message_format,
+        } => {
+            let path = schema.schema.display().to_string();
+            let result = schema.into_code_gen().generate_code_with_warnings();
+
+            let mut diagnostics = match (&message_format, &result) {
+                (MessageFormat::Human, _) => {
+                    let (_, warnings) = result?;
+                    print_warnings(&warnings);
+                    return Ok(());
+                }
+                (_, Ok((_, warnings))) => warnings.diagnostics(),
+                (_, Err(err)) => err.diagnostics(),
+            };
+
+            for diagnostic in &mut diagnostics {
+                if diagnostic.file.as_deref() == Some("schema") {
+                    diagnostic.file = Some(path.clone());
+                }
+            }
+
+            match message_format {
+                MessageFormat::Sarif => println!("{}", diagnostics_to_sarif(&diagnostics)),
+                _ => println!("{}", diagnostics_to_json(&diagnostics)),
+            }
+
+            if result.is_err() {
+                return Err("the schema has errors".into());
+            }
+        }
+        Opt::Generate { schema, output } => {
+            let (code, warnings) = schema
+                .into_code_gen()
+                .generate_formatted_code_with_warnings()?;
+            print_warnings(&warnings);
+            output.write(code)?;
+        }
+        Opt::Scaffold {
+            schema,
+            output,
+            missing_from,
+        } => {
+            let code_gen = schema.into_code_gen();
+            let code = match missing_from {
+                Some(path) => {
+                    let existing_code = fs::read_to_string(path)?;
+                    code_gen.generate_missing_scaffold(&existing_code)?
+                }
+                None => code_gen.generate_scaffold()?,
+            };
+            output.write(code)?;
+        }
+        Opt::Client {
+            schema,
+            operations,
+            output,
+        } => {
+            let operations = fs::read_to_string(operations)?;
+            let code = schema
+                .into_code_gen()
+                .generate_formatted_client_code(&operations)?;
+            output.write(code)?;
+        }
+        Opt::Diff { baseline, schema } => {
+            let baseline = fs::read_to_string(baseline)?;
+            let schema = fs::read_to_string(schema)?;
+            let diff = diff_schemas(&baseline, &schema)?;
+
+            print!("{}", diff);
+
+            if diff.is_breaking() {
+                return Err("the schema has breaking changes".into());
+            }
+        }
+        Opt::Explain { code } => match explain(&code) {
+            Some(explanation) => print!("{}", explanation),
+            None => return Err(format!("unknown error code `{}`", code).into()),
+        },
+        Opt::PrintDirective => {
+            println!("{}", JUNIPER_DIRECTIVE_DEFINITION);
+            println!();
+            println!("{}", COST_DIRECTIVE_DEFINITION);
+            println!();
+            println!("{}", CONNECTION_DIRECTIVE_DEFINITION);
+        }
+    }
+
+    Ok(())
+}
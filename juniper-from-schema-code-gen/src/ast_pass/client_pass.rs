@@ -0,0 +1,1416 @@
+//! Generates typed client code for GraphQL operations.
+//!
+//! The operations are first validated against the schema. Code is only generated if that
+//! succeeds, so the generation itself can assume the operations are valid.
+
+use super::{
+    error::Error, schema_visitor::*, type_name, AstData, DateTimeScalarDefinition, EmitError,
+    ErrorKind, NullableType,
+};
+use graphql_parser::{
+    query::{
+        Definition, Directive, Document, FragmentDefinition, OperationDefinition, Selection,
+        SelectionSet, TypeCondition, Value, VariableDefinition,
+    },
+    schema, Pos,
+};
+use heck::{CamelCase, SnakeCase};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use syn::Ident;
+
+#[derive(Debug)]
+pub struct ClientPass<'doc, 'op> {
+    ast_data: AstData<'doc>,
+    errors: BTreeSet<Error>,
+    fragments: HashMap<&'op str, &'op FragmentDefinition<'op, &'op str>>,
+    schema_types: TokenStream,
+    // Only operations we generate code for need names
+    require_operation_names: bool,
+    // The variables of the operation being validated. `None` while validating fragments
+    current_variables: Option<&'op [VariableDefinition<'op, &'op str>]>,
+}
+
+/// An operation that passed validation.
+struct Operation<'op> {
+    name: &'op str,
+    root_type: String,
+    variable_definitions: &'op [VariableDefinition<'op, &'op str>],
+    selection_set: &'op SelectionSet<'op, &'op str>,
+    definition: &'op Definition<'op, &'op str>,
+}
+
+/// The fields selected on a type, with fragments applying to the type merged in.
+struct Collected<'op> {
+    fields: Vec<CollectedField<'op>>,
+    // Concrete types selected with type conditions, when selecting on an interface or union
+    conditional_types: Vec<String>,
+}
+
+struct CollectedField<'op> {
+    response_key: &'op str,
+    name: &'op str,
+    selection_sets: Vec<&'op SelectionSet<'op, &'op str>>,
+    // The field has `@skip` or `@include`, so it might be missing from the response
+    optional: bool,
+}
+
+impl<'doc, 'op> ClientPass<'doc, 'op> {
+    pub fn new(ast_data: AstData<'doc>) -> Self {
+        Self {
+            ast_data,
+            errors: BTreeSet::new(),
+            fragments: HashMap::new(),
+            schema_types: TokenStream::new(),
+            require_operation_names: true,
+            current_variables: None,
+        }
+    }
+
+    /// Validate the operations in `doc` without generating any code.
+    pub fn validate(mut self, doc: &'op Document<'op, &'op str>) -> Result<(), BTreeSet<Error>> {
+        self.require_operation_names = false;
+        self.collect_fragments(doc);
+        self.validate_doc(doc);
+        self.check_for_errors()
+    }
+
+    pub fn gen_client_code(
+        mut self,
+        schema_doc: &'doc schema::Document<'doc, &'doc str>,
+        doc: &'op Document<'op, &'op str>,
+    ) -> Result<TokenStream, BTreeSet<Error>> {
+        self.collect_fragments(doc);
+
+        let operations = self.validate_doc(doc);
+        self.check_for_errors()?;
+
+        visit_document(&mut self, schema_doc);
+
+        let mut tokens = std::mem::replace(&mut self.schema_types, TokenStream::new());
+        for operation in operations {
+            tokens.extend(self.gen_operation(doc, operation));
+        }
+
+        Ok(tokens)
+    }
+
+    fn collect_fragments(&mut self, doc: &'op Document<'op, &'op str>) {
+        for definition in &doc.definitions {
+            if let Definition::Fragment(fragment) = definition {
+                self.fragments.insert(fragment.name, fragment);
+            }
+        }
+    }
+
+    fn check_for_errors(&self) -> Result<(), BTreeSet<Error>> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors.clone())
+        }
+    }
+}
+
+// Validation
+impl<'doc, 'op> ClientPass<'doc, 'op> {
+    fn validate_doc(&mut self, doc: &'op Document<'op, &'op str>) -> Vec<Operation<'op>> {
+        let mut operations = Vec::new();
+        let mut operation_names = HashSet::new();
+
+        for definition in &doc.definitions {
+            match definition {
+                Definition::Operation(operation) => {
+                    let (pos, name, variable_definitions, selection_set, root_type) =
+                        match operation {
+                            OperationDefinition::SelectionSet(selection_set) => (
+                                selection_set.span.0,
+                                None,
+                                &[][..],
+                                selection_set,
+                                self.ast_data.query_type_name(),
+                            ),
+                            OperationDefinition::Subscription(subscription) => {
+                                self.emit_error(
+                                    subscription.position,
+                                    ErrorKind::SubscriptionOperationNotSupported,
+                                );
+                                continue;
+                            }
+                            OperationDefinition::Query(query) => (
+                                query.position,
+                                query.name,
+                                &query.variable_definitions[..],
+                                &query.selection_set,
+                                self.ast_data.query_type_name(),
+                            ),
+                            OperationDefinition::Mutation(mutation) => {
+                                let root_type = self.ast_data.mutation_type_name();
+                                if root_type.is_none() {
+                                    self.emit_error(mutation.position, ErrorKind::NoMutationType);
+                                    continue;
+                                }
+                                (
+                                    mutation.position,
+                                    mutation.name,
+                                    &mutation.variable_definitions[..],
+                                    &mutation.selection_set,
+                                    root_type,
+                                )
+                            }
+                        };
+
+                    if name.is_none() && self.require_operation_names {
+                        self.emit_error(pos, ErrorKind::AnonymousOperation);
+                        continue;
+                    }
+
+                    if let Some(name) = name {
+                        if !operation_names.insert(name.to_snake_case()) {
+                            self.emit_error(
+                                pos,
+                                ErrorKind::DuplicateOperationName(name.to_string()),
+                            );
+                            continue;
+                        }
+                    }
+
+                    let root_type = match root_type {
+                        Some(root_type) => root_type.to_string(),
+                        None => {
+                            self.emit_error(pos, ErrorKind::NoQueryType);
+                            continue;
+                        }
+                    };
+
+                    self.current_variables = Some(variable_definitions);
+                    self.validate_variable_definitions(variable_definitions);
+                    self.validate_selection_set(&root_type, selection_set, pos);
+                    self.validate_variable_usages(variable_definitions, selection_set);
+                    self.current_variables = None;
+
+                    let name = match name {
+                        Some(name) => name,
+                        None => continue,
+                    };
+
+                    operations.push(Operation {
+                        name,
+                        root_type,
+                        variable_definitions,
+                        selection_set,
+                        definition,
+                    });
+                }
+                Definition::Fragment(fragment) => {
+                    let TypeCondition::On(type_condition) = &fragment.type_condition;
+
+                    if !self.is_output_type(type_condition) {
+                        self.emit_error(
+                            fragment.position,
+                            ErrorKind::UnknownType(type_condition.to_string()),
+                        );
+                        continue;
+                    }
+
+                    if self.fragment_spreads_itself(fragment) {
+                        self.emit_error(
+                            fragment.position,
+                            ErrorKind::FragmentCycle(fragment.name.to_string()),
+                        );
+                        continue;
+                    }
+
+                    self.validate_selection_set(
+                        type_condition,
+                        &fragment.selection_set,
+                        fragment.position,
+                    );
+                }
+            }
+        }
+
+        operations
+    }
+
+    fn validate_variable_definitions(
+        &mut self,
+        variable_definitions: &'op [VariableDefinition<'op, &'op str>],
+    ) {
+        for variable in variable_definitions {
+            let name = type_name(&variable.var_type);
+
+            if self.is_input_type(name) {
+                continue;
+            }
+
+            if self.is_output_type(name) {
+                self.emit_error(
+                    variable.position,
+                    ErrorKind::NonInputVariableType(name.to_string()),
+                );
+            } else {
+                self.emit_error(variable.position, ErrorKind::UnknownType(name.to_string()));
+            }
+        }
+    }
+
+    fn validate_selection_set(
+        &mut self,
+        parent_type: &str,
+        selection_set: &'op SelectionSet<'op, &'op str>,
+        pos: Pos,
+    ) {
+        let mut selects_typename = false;
+        let mut has_type_conditions = false;
+
+        for selection in &selection_set.items {
+            match selection {
+                Selection::Field(field) => {
+                    if field.name == "__typename" {
+                        selects_typename |= field.alias.is_none();
+                        if !field.selection_set.items.is_empty() {
+                            self.emit_error(
+                                field.position,
+                                ErrorKind::SelectionOnLeafField {
+                                    field_name: field.name.to_string(),
+                                    type_name: "String".to_string(),
+                                },
+                            );
+                        }
+                        continue;
+                    }
+
+                    let field_definition = match self.field_definition(parent_type, field.name) {
+                        Some(field_definition) => field_definition,
+                        None => {
+                            let available = self
+                                .ast_data
+                                .output_fields(parent_type)
+                                .map(|fields| {
+                                    fields.iter().map(|field| field.name.to_string()).collect()
+                                })
+                                .unwrap_or_default();
+
+                            self.emit_error(
+                                field.position,
+                                ErrorKind::UnknownField {
+                                    type_name: parent_type.to_string(),
+                                    field_name: field.name.to_string(),
+                                    available,
+                                },
+                            );
+                            continue;
+                        }
+                    };
+
+                    for (argument_name, value) in &field.arguments {
+                        match field_definition
+                            .arguments
+                            .iter()
+                            .find(|arg| arg.name == *argument_name)
+                        {
+                            Some(argument) => self.validate_value(
+                                argument_name,
+                                value,
+                                &argument.value_type,
+                                argument.default_value.is_some(),
+                                field.position,
+                            ),
+                            None => self.emit_error(
+                                field.position,
+                                ErrorKind::UnknownArgument {
+                                    field_name: field.name.to_string(),
+                                    argument_name: argument_name.to_string(),
+                                },
+                            ),
+                        }
+                    }
+
+                    for argument in &field_definition.arguments {
+                        let required = matches!(argument.value_type, schema::Type::NonNullType(_))
+                            && argument.default_value.is_none();
+
+                        if required
+                            && !field
+                                .arguments
+                                .iter()
+                                .any(|(name, _)| *name == argument.name)
+                        {
+                            self.emit_error(
+                                field.position,
+                                ErrorKind::MissingArgument {
+                                    field_name: field.name.to_string(),
+                                    argument_name: argument.name.to_string(),
+                                },
+                            );
+                        }
+                    }
+
+                    let field_type = type_name(&field_definition.field_type);
+
+                    if self.is_leaf_type(field_type) {
+                        if !field.selection_set.items.is_empty() {
+                            self.emit_error(
+                                field.position,
+                                ErrorKind::SelectionOnLeafField {
+                                    field_name: field.name.to_string(),
+                                    type_name: field_type.to_string(),
+                                },
+                            );
+                        }
+                    } else if field.selection_set.items.is_empty() {
+                        self.emit_error(
+                            field.position,
+                            ErrorKind::MissingSelection {
+                                field_name: field.name.to_string(),
+                                type_name: field_type.to_string(),
+                            },
+                        );
+                    } else {
+                        self.validate_selection_set(
+                            field_type,
+                            &field.selection_set,
+                            field.position,
+                        );
+                    }
+                }
+                Selection::FragmentSpread(spread) => match self.fragments.get(spread.fragment_name)
+                {
+                    Some(fragment) => {
+                        let TypeCondition::On(type_condition) = &fragment.type_condition;
+                        has_type_conditions |= *type_condition != parent_type;
+                    }
+                    None => {
+                        self.emit_error(
+                            spread.position,
+                            ErrorKind::UnknownFragment(spread.fragment_name.to_string()),
+                        );
+                    }
+                },
+                Selection::InlineFragment(inline_fragment) => {
+                    let type_condition = match &inline_fragment.type_condition {
+                        Some(TypeCondition::On(type_condition)) => {
+                            if !self.is_output_type(type_condition) {
+                                self.emit_error(
+                                    inline_fragment.position,
+                                    ErrorKind::UnknownType(type_condition.to_string()),
+                                );
+                                continue;
+                            }
+                            has_type_conditions |= *type_condition != parent_type;
+                            *type_condition
+                        }
+                        None => parent_type,
+                    };
+
+                    self.validate_selection_set(
+                        type_condition,
+                        &inline_fragment.selection_set,
+                        inline_fragment.position,
+                    );
+                }
+            }
+        }
+
+        if self.is_abstract_type(parent_type) && has_type_conditions && !selects_typename {
+            self.emit_error(pos, ErrorKind::MissingTypename(parent_type.to_string()));
+        }
+    }
+
+    fn validate_value(
+        &mut self,
+        argument_name: &str,
+        value: &'op Value<'op, &'op str>,
+        expected_type: &schema::Type<'doc, &'doc str>,
+        has_default: bool,
+        pos: Pos,
+    ) {
+        if let Value::Variable(variable_name) = value {
+            let variable = self
+                .current_variables
+                .and_then(|variables| variables.iter().find(|var| var.name == *variable_name));
+
+            // Undefined variables are reported by `validate_variable_usages`
+            if let Some(variable) = variable {
+                let has_default = has_default || variable.default_value.is_some();
+
+                if !variable_type_allowed(&variable.var_type, expected_type, has_default) {
+                    self.emit_error(
+                        pos,
+                        ErrorKind::VariableTypeMismatch {
+                            variable_name: variable_name.to_string(),
+                            variable_type: variable.var_type.to_string(),
+                            argument_name: argument_name.to_string(),
+                            expected_type: expected_type.to_string(),
+                        },
+                    );
+                }
+            }
+
+            return;
+        }
+
+        if !self.value_has_type(value, expected_type) {
+            self.emit_error(
+                pos,
+                ErrorKind::InvalidArgumentValue {
+                    argument_name: argument_name.to_string(),
+                    expected_type: expected_type.to_string(),
+                    value: value.to_string(),
+                },
+            );
+        }
+    }
+
+    // Whether the literal `value` can be coerced into `expected_type`. Nested variables are
+    // accepted as their definitions are checked by `validate_variable_definitions`.
+    fn value_has_type<'a>(
+        &self,
+        value: &Value<'op, &'op str>,
+        expected_type: &schema::Type<'a, &'a str>,
+    ) -> bool {
+        match (value, expected_type) {
+            (Value::Variable(_), _) => true,
+            (Value::Null, schema::Type::NonNullType(_)) => false,
+            (Value::Null, _) => true,
+            (value, schema::Type::NonNullType(inner)) => self.value_has_type(value, inner),
+            (Value::List(values), schema::Type::ListType(inner)) => {
+                values.iter().all(|value| self.value_has_type(value, inner))
+            }
+            // A single value is coerced into a list containing it
+            (value, schema::Type::ListType(inner)) => self.value_has_type(value, inner),
+            (value, schema::Type::NamedType(name)) => self.value_has_named_type(value, name),
+        }
+    }
+
+    fn value_has_named_type(&self, value: &Value<'op, &'op str>, name: &str) -> bool {
+        match (name, value) {
+            ("Int", Value::Int(_)) => true,
+            ("Float", Value::Int(_)) | ("Float", Value::Float(_)) => true,
+            ("String", Value::String(_)) => true,
+            ("Boolean", Value::Boolean(_)) => true,
+            ("ID", Value::String(_)) | ("ID", Value::Int(_)) => true,
+            ("Int", _) | ("Float", _) | ("String", _) | ("Boolean", _) | ("ID", _) => false,
+            (name, value) => {
+                if let Some(values) = self.ast_data.enum_values(name) {
+                    match value {
+                        Value::Enum(variant) => values.contains(variant),
+                        _ => false,
+                    }
+                } else if let Some(fields) = self.ast_data.input_object_fields(name) {
+                    match value {
+                        Value::Object(object) => {
+                            let known_keys = object.keys().all(|key| fields.contains_key(key));
+                            // Fields with a default value can be omitted even if they're
+                            // non-null
+                            let valid_fields =
+                                fields.iter().all(|(field_name, field)| {
+                                    match object.get(field_name) {
+                                        Some(value) => {
+                                            self.value_has_type(value, &field.value_type)
+                                        }
+                                        None => {
+                                            field.default_value.is_some()
+                                                || !matches!(
+                                                    field.value_type,
+                                                    schema::Type::NonNullType(_)
+                                                )
+                                        }
+                                    }
+                                });
+                            known_keys && valid_fields
+                        }
+                        _ => false,
+                    }
+                } else {
+                    // Custom scalars can be represented as anything
+                    true
+                }
+            }
+        }
+    }
+
+    fn validate_variable_usages(
+        &mut self,
+        variable_definitions: &'op [VariableDefinition<'op, &'op str>],
+        selection_set: &'op SelectionSet<'op, &'op str>,
+    ) {
+        let mut usages = Vec::new();
+        self.variable_usages(selection_set, &mut HashSet::new(), &mut usages);
+
+        for (name, pos) in usages {
+            if !variable_definitions
+                .iter()
+                .any(|variable| variable.name == name)
+            {
+                self.emit_error(pos, ErrorKind::UndefinedVariable(name.to_string()));
+            }
+        }
+    }
+
+    fn variable_usages(
+        &self,
+        selection_set: &'op SelectionSet<'op, &'op str>,
+        visited_fragments: &mut HashSet<&'op str>,
+        acc: &mut Vec<(&'op str, Pos)>,
+    ) {
+        for selection in &selection_set.items {
+            match selection {
+                Selection::Field(field) => {
+                    for (_, value) in &field.arguments {
+                        value_variables(value, field.position, acc);
+                    }
+                    directive_variables(&field.directives, field.position, acc);
+                    self.variable_usages(&field.selection_set, visited_fragments, acc);
+                }
+                Selection::FragmentSpread(spread) => {
+                    directive_variables(&spread.directives, spread.position, acc);
+                    if !visited_fragments.insert(spread.fragment_name) {
+                        continue;
+                    }
+                    if let Some(fragment) = self.fragments.get(spread.fragment_name) {
+                        self.variable_usages(&fragment.selection_set, visited_fragments, acc);
+                    }
+                }
+                Selection::InlineFragment(inline_fragment) => {
+                    directive_variables(&inline_fragment.directives, inline_fragment.position, acc);
+                    self.variable_usages(&inline_fragment.selection_set, visited_fragments, acc);
+                }
+            }
+        }
+    }
+
+    fn fragment_spreads_itself(&self, fragment: &'op FragmentDefinition<'op, &'op str>) -> bool {
+        fn visit<'op>(
+            pass: &ClientPass<'_, 'op>,
+            target: &str,
+            selection_set: &'op SelectionSet<'op, &'op str>,
+            visited: &mut HashSet<&'op str>,
+        ) -> bool {
+            selection_set.items.iter().any(|selection| match selection {
+                Selection::Field(field) => visit(pass, target, &field.selection_set, visited),
+                Selection::InlineFragment(inline_fragment) => {
+                    visit(pass, target, &inline_fragment.selection_set, visited)
+                }
+                Selection::FragmentSpread(spread) => {
+                    if spread.fragment_name == target {
+                        return true;
+                    }
+                    if !visited.insert(spread.fragment_name) {
+                        return false;
+                    }
+                    pass.fragments
+                        .get(spread.fragment_name)
+                        .map(|fragment| visit(pass, target, &fragment.selection_set, visited))
+                        .unwrap_or(false)
+                }
+            })
+        }
+
+        visit(
+            self,
+            fragment.name,
+            &fragment.selection_set,
+            &mut HashSet::new(),
+        )
+    }
+}
+
+// Whether a variable of type `variable_type` can be used where `expected_type` is expected. A
+// nullable variable can be used for a non-null argument if either of them has a default value.
+fn variable_type_allowed<'a, 'b>(
+    variable_type: &schema::Type<'a, &'a str>,
+    expected_type: &schema::Type<'b, &'b str>,
+    has_default: bool,
+) -> bool {
+    use schema::Type::*;
+
+    match (variable_type, expected_type) {
+        (NonNullType(variable_inner), NonNullType(expected_inner)) => {
+            variable_type_allowed(variable_inner, expected_inner, false)
+        }
+        (_, NonNullType(expected_inner)) => {
+            has_default && variable_type_allowed(variable_type, expected_inner, false)
+        }
+        (NonNullType(variable_inner), _) => {
+            variable_type_allowed(variable_inner, expected_type, false)
+        }
+        (ListType(variable_inner), ListType(expected_inner)) => {
+            variable_type_allowed(variable_inner, expected_inner, false)
+        }
+        (NamedType(variable_name), NamedType(expected_name)) => variable_name == expected_name,
+        _ => false,
+    }
+}
+
+fn value_variables<'op>(value: &Value<'op, &'op str>, pos: Pos, acc: &mut Vec<(&'op str, Pos)>) {
+    match value {
+        Value::Variable(name) => acc.push((name, pos)),
+        Value::List(values) => {
+            for value in values {
+                value_variables(value, pos, acc);
+            }
+        }
+        Value::Object(fields) => {
+            for value in fields.values() {
+                value_variables(value, pos, acc);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn directive_variables<'op>(
+    directives: &[Directive<'op, &'op str>],
+    pos: Pos,
+    acc: &mut Vec<(&'op str, Pos)>,
+) {
+    for directive in directives {
+        for (_, value) in &directive.arguments {
+            value_variables(value, pos, acc);
+        }
+    }
+}
+
+// Type lookups
+impl<'doc, 'op> ClientPass<'doc, 'op> {
+    fn field_definition(
+        &self,
+        type_name: &str,
+        field_name: &str,
+    ) -> Option<&'doc schema::Field<'doc, &'doc str>> {
+        self.ast_data
+            .output_fields(type_name)?
+            .iter()
+            .find(|field| field.name == field_name)
+            .copied()
+    }
+
+    fn is_builtin_scalar(&self, name: &str) -> bool {
+        matches!(name, "String" | "Float" | "Int" | "Boolean" | "ID")
+    }
+
+    fn is_leaf_type(&self, name: &str) -> bool {
+        self.is_builtin_scalar(name)
+            || self.ast_data.is_scalar(name)
+            || self.ast_data.is_enum_type(name)
+    }
+
+    fn is_input_type(&self, name: &str) -> bool {
+        self.is_leaf_type(name) || self.ast_data.is_input_object_type(name)
+    }
+
+    fn is_abstract_type(&self, name: &str) -> bool {
+        self.ast_data.is_interface_type(name) || self.ast_data.is_union_type(name)
+    }
+
+    fn is_output_type(&self, name: &str) -> bool {
+        self.ast_data.output_fields(name).is_some() || self.ast_data.is_union_type(name)
+    }
+
+    fn possible_types(&self, name: &str) -> Vec<String> {
+        self.ast_data
+            .possible_types(name)
+            .into_iter()
+            .map(|name| name.to_string())
+            .collect()
+    }
+
+    fn leaf_type(&self, name: &str) -> TokenStream {
+        match name {
+            "String" => quote! { std::string::String },
+            "Float" => quote! { f64 },
+            "Int" => quote! { i32 },
+            "Boolean" => quote! { bool },
+            "ID" => quote! { juniper_from_schema::juniper::ID },
+            name if name == crate::URL_SCALAR_NAME => quote! { url::Url },
+            name if name == crate::UUID_SCALAR_NAME => quote! { uuid::Uuid },
+            name if name == crate::DATE_SCALAR_NAME => quote! { chrono::naive::NaiveDate },
+            name if name == crate::DATE_TIME_SCALAR_NAME => {
+                match self.ast_data.date_time_scalar_definition() {
+                    Some(DateTimeScalarDefinition::WithoutTimeZone) => {
+                        quote! { chrono::naive::NaiveDateTime }
+                    }
+                    _ => quote! { chrono::DateTime<chrono::offset::Utc> },
+                }
+            }
+            name => {
+                let name = format_ident!("{}", name);
+                quote! { #name }
+            }
+        }
+    }
+}
+
+// Code generation
+impl<'doc, 'op> ClientPass<'doc, 'op> {
+    fn gen_operation(
+        &mut self,
+        doc: &'op Document<'op, &'op str>,
+        operation: Operation<'op>,
+    ) -> TokenStream {
+        let Operation {
+            name,
+            root_type,
+            variable_definitions,
+            selection_set,
+            definition,
+        } = operation;
+
+        let module_name = format_ident!("{}", name.to_snake_case());
+        let serde_crate = serde_crate();
+
+        let query = operation_document(doc, definition, selection_set, &self.fragments);
+
+        let variables = variable_definitions
+            .iter()
+            .map(|variable| {
+                let field_name = format_ident!("r#{}", variable.name.to_snake_case());
+                let graphql_name = variable.name;
+                let rust_type = self.input_type(&variable.var_type);
+                quote! {
+                    #[serde(rename = #graphql_name)]
+                    pub #field_name: #rust_type,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut response_types = TokenStream::new();
+        self.gen_selection(
+            &format_ident!("ResponseData"),
+            &root_type,
+            &[selection_set],
+            false,
+            &mut response_types,
+        );
+
+        quote! {
+            /// Generated by `juniper-from-schema`.
+            pub mod #module_name {
+                #![allow(unused_imports)]
+                use super::*;
+
+                /// The name of the operation.
+                pub const OPERATION_NAME: &str = #name;
+
+                /// The operation, and the fragments it uses, to send to the server.
+                pub const QUERY: &str = #query;
+
+                /// The variables of the operation.
+                #[derive(Debug, Clone, PartialEq, #serde_crate::Serialize)]
+                #[serde(crate = "juniper_from_schema::serde")]
+                pub struct Variables {
+                    #(#variables)*
+                }
+
+                /// The JSON body of a request for the operation.
+                #[derive(Debug, Clone, PartialEq, #serde_crate::Serialize)]
+                #[serde(crate = "juniper_from_schema::serde")]
+                pub struct RequestBody {
+                    pub query: &'static str,
+                    #[serde(rename = "operationName")]
+                    pub operation_name: &'static str,
+                    pub variables: Variables,
+                }
+
+                /// Build the request body for the operation.
+                pub fn request_body(variables: Variables) -> RequestBody {
+                    RequestBody {
+                        query: QUERY,
+                        operation_name: OPERATION_NAME,
+                        variables,
+                    }
+                }
+
+                #response_types
+            }
+        }
+    }
+
+    fn gen_selection(
+        &mut self,
+        name: &Ident,
+        parent_type: &str,
+        selection_sets: &[&'op SelectionSet<'op, &'op str>],
+        skip_typename: bool,
+        out: &mut TokenStream,
+    ) {
+        let serde_crate = serde_crate();
+        let collected = self.collect(parent_type, selection_sets);
+
+        if !collected.conditional_types.is_empty() {
+            let variants = collected
+                .conditional_types
+                .iter()
+                .map(|type_name| {
+                    let variant_name = format_ident!("{}", type_name);
+                    let struct_name = format_ident!("{}On{}", name, type_name);
+                    self.gen_selection(&struct_name, type_name, selection_sets, true, out);
+                    quote! { #variant_name(#struct_name), }
+                })
+                .collect::<Vec<_>>();
+
+            out.extend(quote! {
+                #[derive(Debug, Clone, PartialEq, #serde_crate::Deserialize)]
+                #[serde(crate = "juniper_from_schema::serde", tag = "__typename")]
+                pub enum #name {
+                    #(#variants)*
+                    /// A type that wasn't selected with a type condition.
+                    #[serde(other)]
+                    Other,
+                }
+            });
+
+            return;
+        }
+
+        let fields = collected
+            .fields
+            .iter()
+            .filter(|field| !(skip_typename && field.response_key == "__typename"))
+            .map(|field| {
+                let field_name = format_ident!("r#{}", field.response_key.to_snake_case());
+                let response_key = field.response_key;
+
+                let rust_type = match self.field_definition(parent_type, field.name) {
+                    Some(field_definition) => {
+                        let nested_name =
+                            format_ident!("{}{}", name, field.response_key.to_camel_case());
+                        let nullable_type =
+                            NullableType::from_schema_type(&field_definition.field_type);
+                        self.output_type(&nullable_type, &nested_name, &field.selection_sets, out)
+                    }
+                    // Only `__typename` isn't defined in the schema
+                    None => quote! { std::string::String },
+                };
+
+                if field.optional {
+                    quote! {
+                        #[serde(rename = #response_key, default)]
+                        pub #field_name: std::option::Option<#rust_type>,
+                    }
+                } else {
+                    quote! {
+                        #[serde(rename = #response_key)]
+                        pub #field_name: #rust_type,
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        out.extend(quote! {
+            #[derive(Debug, Clone, PartialEq, #serde_crate::Deserialize)]
+            #[serde(crate = "juniper_from_schema::serde")]
+            pub struct #name {
+                #(#fields)*
+            }
+        });
+    }
+
+    fn output_type(
+        &mut self,
+        nullable_type: &NullableType<'doc>,
+        nested_name: &Ident,
+        selection_sets: &[&'op SelectionSet<'op, &'op str>],
+        out: &mut TokenStream,
+    ) -> TokenStream {
+        match nullable_type {
+            NullableType::NamedType(name) => {
+                if self.is_leaf_type(name) {
+                    self.leaf_type(name)
+                } else {
+                    self.gen_selection(nested_name, name, selection_sets, false, out);
+                    quote! { #nested_name }
+                }
+            }
+            NullableType::ListType(inner) => {
+                let inner = self.output_type(inner, nested_name, selection_sets, out);
+                quote! { std::vec::Vec<#inner> }
+            }
+            NullableType::NullableType(inner) => {
+                let inner = self.output_type(inner, nested_name, selection_sets, out);
+                quote! { std::option::Option<#inner> }
+            }
+        }
+    }
+
+    fn input_type<'a>(&self, graphql_type: &schema::Type<'a, &'a str>) -> TokenStream {
+        fn gen(pass: &ClientPass<'_, '_>, nullable_type: &NullableType<'_>) -> TokenStream {
+            match nullable_type {
+                NullableType::NamedType(name) => pass.leaf_type(name),
+                NullableType::ListType(inner) => {
+                    let inner = gen(pass, inner);
+                    quote! { std::vec::Vec<#inner> }
+                }
+                NullableType::NullableType(inner) => {
+                    let inner = gen(pass, inner);
+                    quote! { std::option::Option<#inner> }
+                }
+            }
+        }
+
+        gen(self, &NullableType::from_schema_type(graphql_type))
+    }
+
+    /// Collect the fields selected on `parent_type`, merging in fragments that apply to it.
+    fn collect(
+        &self,
+        parent_type: &str,
+        selection_sets: &[&'op SelectionSet<'op, &'op str>],
+    ) -> Collected<'op> {
+        let mut collected = Collected {
+            fields: Vec::new(),
+            conditional_types: Vec::new(),
+        };
+
+        for selection_set in selection_sets {
+            self.collect_selection_set(parent_type, selection_set, &mut collected, &mut Vec::new());
+        }
+
+        collected
+    }
+
+    fn collect_selection_set(
+        &self,
+        parent_type: &str,
+        selection_set: &'op SelectionSet<'op, &'op str>,
+        acc: &mut Collected<'op>,
+        spread_stack: &mut Vec<&'op str>,
+    ) {
+        for selection in &selection_set.items {
+            match selection {
+                Selection::Field(field) => {
+                    let response_key = field.alias.unwrap_or(field.name);
+                    let optional = field
+                        .directives
+                        .iter()
+                        .any(|directive| directive.name == "skip" || directive.name == "include");
+
+                    match acc
+                        .fields
+                        .iter_mut()
+                        .find(|existing| existing.response_key == response_key)
+                    {
+                        Some(existing) => {
+                            existing.selection_sets.push(&field.selection_set);
+                            existing.optional &= optional;
+                        }
+                        None => acc.fields.push(CollectedField {
+                            response_key,
+                            name: field.name,
+                            selection_sets: vec![&field.selection_set],
+                            optional,
+                        }),
+                    }
+                }
+                Selection::FragmentSpread(spread) => {
+                    if spread_stack.contains(&spread.fragment_name) {
+                        continue;
+                    }
+
+                    if let Some(fragment) = self.fragments.get(spread.fragment_name) {
+                        let TypeCondition::On(type_condition) = &fragment.type_condition;
+                        spread_stack.push(spread.fragment_name);
+                        self.collect_fragment(
+                            parent_type,
+                            type_condition,
+                            &fragment.selection_set,
+                            acc,
+                            spread_stack,
+                        );
+                        spread_stack.pop();
+                    }
+                }
+                Selection::InlineFragment(inline_fragment) => {
+                    let type_condition = match &inline_fragment.type_condition {
+                        Some(TypeCondition::On(type_condition)) => type_condition,
+                        None => parent_type,
+                    };
+
+                    self.collect_fragment(
+                        parent_type,
+                        type_condition,
+                        &inline_fragment.selection_set,
+                        acc,
+                        spread_stack,
+                    );
+                }
+            }
+        }
+    }
+
+    fn collect_fragment(
+        &self,
+        parent_type: &str,
+        type_condition: &str,
+        selection_set: &'op SelectionSet<'op, &'op str>,
+        acc: &mut Collected<'op>,
+        spread_stack: &mut Vec<&'op str>,
+    ) {
+        if type_condition == parent_type {
+            self.collect_selection_set(parent_type, selection_set, acc, spread_stack);
+        } else if self.is_abstract_type(parent_type) {
+            let parent_possible_types = self.possible_types(parent_type);
+
+            for possible_type in self.possible_types(type_condition) {
+                if parent_possible_types.contains(&possible_type)
+                    && !acc.conditional_types.contains(&possible_type)
+                {
+                    acc.conditional_types.push(possible_type);
+                }
+            }
+        } else if self
+            .possible_types(type_condition)
+            .iter()
+            .any(|possible_type| possible_type == parent_type)
+        {
+            self.collect_selection_set(parent_type, selection_set, acc, spread_stack);
+        }
+    }
+}
+
+impl<'doc, 'op> SchemaVisitor<'doc> for ClientPass<'doc, 'op> {
+    fn visit_scalar_type(&mut self, node: &'doc schema::ScalarType<'doc, &'doc str>) {
+        match node.name {
+            crate::DATE_TIME_SCALAR_NAME
+            | crate::DATE_SCALAR_NAME
+            | crate::URL_SCALAR_NAME
+            | crate::UUID_SCALAR_NAME => {}
+            name => {
+                let name = format_ident!("{}", name);
+                let serde_crate = serde_crate();
+
+                self.schema_types.extend(quote! {
+                    #[derive(
+                        Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Hash,
+                        #serde_crate::Serialize, #serde_crate::Deserialize,
+                    )]
+                    #[serde(crate = "juniper_from_schema::serde", transparent)]
+                    pub struct #name(pub std::string::String);
+                });
+            }
+        }
+    }
+
+    fn visit_enum_type(&mut self, node: &'doc schema::EnumType<'doc, &'doc str>) {
+        let name = format_ident!("{}", node.name);
+        let serde_crate = serde_crate();
+
+        let variants = node.values.iter().map(|value| {
+            let graphql_name = value.name;
+            let variant_name = format_ident!("{}", value.name.to_camel_case());
+            quote! {
+                #[serde(rename = #graphql_name)]
+                #variant_name,
+            }
+        });
+
+        self.schema_types.extend(quote! {
+            #[derive(
+                Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash,
+                #serde_crate::Serialize, #serde_crate::Deserialize,
+            )]
+            #[serde(crate = "juniper_from_schema::serde")]
+            pub enum #name {
+                #(#variants)*
+            }
+        });
+    }
+
+    fn visit_input_object_type(&mut self, node: &'doc schema::InputObjectType<'doc, &'doc str>) {
+        let name = format_ident!("{}", node.name);
+        let serde_crate = serde_crate();
+
+        let fields = node
+            .fields
+            .iter()
+            .map(|field| {
+                let graphql_name = field.name;
+                let field_name = format_ident!("r#{}", field.name.to_snake_case());
+                let rust_type = self.input_type(&field.value_type);
+                quote! {
+                    #[serde(rename = #graphql_name)]
+                    pub #field_name: #rust_type,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        self.schema_types.extend(quote! {
+            #[derive(Debug, Clone, PartialEq, #serde_crate::Serialize)]
+            #[serde(crate = "juniper_from_schema::serde")]
+            pub struct #name {
+                #(#fields)*
+            }
+        });
+    }
+}
+
+impl<'doc, 'op> EmitError for ClientPass<'doc, 'op> {
+    fn emit_error(&mut self, pos: Pos, kind: ErrorKind) {
+        let error = Error { pos, kind };
+        self.errors.insert(error);
+    }
+}
+
+fn serde_crate() -> TokenStream {
+    quote! { juniper_from_schema::serde }
+}
+
+/// The text of `definition` along with all the fragments it uses.
+fn operation_document<'op>(
+    doc: &'op Document<'op, &'op str>,
+    definition: &'op Definition<'op, &'op str>,
+    selection_set: &'op SelectionSet<'op, &'op str>,
+    fragments: &HashMap<&'op str, &'op FragmentDefinition<'op, &'op str>>,
+) -> String {
+    fn used_fragments<'op>(
+        selection_set: &'op SelectionSet<'op, &'op str>,
+        fragments: &HashMap<&'op str, &'op FragmentDefinition<'op, &'op str>>,
+        acc: &mut HashSet<&'op str>,
+    ) {
+        for selection in &selection_set.items {
+            match selection {
+                Selection::Field(field) => used_fragments(&field.selection_set, fragments, acc),
+                Selection::InlineFragment(inline_fragment) => {
+                    used_fragments(&inline_fragment.selection_set, fragments, acc)
+                }
+                Selection::FragmentSpread(spread) => {
+                    if acc.insert(spread.fragment_name) {
+                        if let Some(fragment) = fragments.get(spread.fragment_name) {
+                            used_fragments(&fragment.selection_set, fragments, acc);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut used = HashSet::new();
+    used_fragments(selection_set, fragments, &mut used);
+
+    // Keep the order from the original document so the output is deterministic
+    let definitions = doc
+        .definitions
+        .iter()
+        .filter(|other| match other {
+            Definition::Operation(_) => std::ptr::eq(*other, definition),
+            Definition::Fragment(fragment) => used.contains(fragment.name),
+        })
+        .cloned()
+        .collect();
+
+    Document { definitions }.to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::CodeGen;
+
+    const SCHEMA: &str = r#"
+        schema {
+          query: Query
+        }
+
+        type Query {
+          user(id: ID!): User
+          search(query: String!): [SearchResult!]!
+        }
+
+        type User {
+          id: ID!
+          name: String!
+          status: Status!
+        }
+
+        type Post {
+          id: ID!
+          title: String!
+        }
+
+        union SearchResult = User | Post
+
+        enum Status {
+          ACTIVE
+          DISABLED
+        }
+    "#;
+
+    fn generate(operations: &str) -> Result<String, String> {
+        CodeGen::build_from_schema_literal(SCHEMA.to_string())
+            .finish()
+            .generate_formatted_client_code(operations)
+            .map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn test_generates_structs_for_operations() {
+        let code = generate(
+            r#"
+            query GetUser($id: ID!) {
+              user(id: $id) {
+                ...UserFields
+                status
+              }
+            }
+
+            fragment UserFields on User {
+              id
+              displayName: name
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert!(code.contains("pub mod get_user {"));
+        assert!(code.contains("pub const OPERATION_NAME: &str = \"GetUser\";"));
+        assert!(code.contains("fragment UserFields on User"));
+        assert!(code.contains("pub r#id: juniper_from_schema::juniper::ID,"));
+        assert!(code.contains("pub r#user: std::option::Option<ResponseDataUser>,"));
+        assert!(code.contains("pub r#display_name: std::string::String,"));
+        assert!(code.contains("pub r#status: Status,"));
+        assert!(code.contains("pub enum Status {"));
+    }
+
+    #[test]
+    fn test_generates_enums_for_type_conditions() {
+        let code = generate(
+            r#"
+            query Search {
+              search(query: "foo") {
+                __typename
+                ... on User { name }
+                ... on Post { title }
+              }
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert!(code.contains("pub enum ResponseDataSearch {"));
+        assert!(code.contains("User(ResponseDataSearchOnUser),"));
+        assert!(code.contains("Post(ResponseDataSearchOnPost),"));
+    }
+
+    #[test]
+    fn test_reports_invalid_operations() {
+        let error = generate(
+            r#"
+            query GetUser {
+              user(id: $id) {
+                email
+              }
+              search(query: "foo") {
+                ... on User { name }
+              }
+            }
+            "#,
+        )
+        .unwrap_err();
+
+        assert!(error.contains("Unknown field `email` on type `User`"));
+        assert!(error.contains("--> operations:4:17"));
+        assert!(error.contains("Variable `$id` is not defined"));
+        assert!(error.contains("must include `__typename`"));
+    }
+
+    #[test]
+    fn test_reports_missing_arguments_and_selections() {
+        let error = generate(
+            r#"
+            query GetUser {
+              user {
+                name { first }
+              }
+            }
+            "#,
+        )
+        .unwrap_err();
+
+        assert!(error.contains("Field `user` is missing the required argument `id`"));
+        assert!(error.contains("Field `name` of type `String` cannot have a selection set"));
+    }
+
+    #[test]
+    fn test_reports_invalid_argument_values() {
+        let error = generate(
+            r#"
+            query GetUser($query: Int) {
+              user(id: true) { name }
+              search(query: $query) {
+                __typename
+              }
+            }
+            "#,
+        )
+        .unwrap_err();
+
+        assert!(error.contains("Argument `id` expects type `ID!`, got `true`"));
+        assert!(error.contains(
+            "Variable `$query` of type `Int` cannot be used for argument `query` of type `String!`"
+        ));
+    }
+
+    #[test]
+    fn test_input_object_fields_with_defaults_can_be_omitted() {
+        let schema = r#"
+            schema {
+              query: Query
+            }
+
+            type Query {
+              users(filter: UserFilter!): [String!]!
+            }
+
+            input UserFilter {
+              limit: Int! = 10
+              name: String!
+            }
+        "#;
+
+        let generate = |operations: &str| {
+            CodeGen::build_from_schema_literal(schema.to_string())
+                .finish()
+                .generate_formatted_client_code(operations)
+                .map_err(|err| err.to_string())
+        };
+
+        generate(r#"query Users { users(filter: { name: "Alice" }) }"#).unwrap();
+
+        let error = generate(r#"query Users { users(filter: { limit: 5 }) }"#).unwrap_err();
+        assert!(error.contains("Argument `filter` expects type `UserFilter!`"));
+    }
+
+    #[test]
+    fn test_validates_persisted_queries() {
+        let result = CodeGen::build_from_schema_literal(SCHEMA.to_string())
+            .persisted_query(
+                "get_user.graphql".to_string(),
+                r#"{ user(id: 1) { name } }"#.to_string(),
+            )
+            .persisted_query(
+                "search.graphql".to_string(),
+                r#"{ search(query: null) { __typename } }"#.to_string(),
+            )
+            .finish()
+            .generate_code();
+
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("--> search.graphql:1:3"));
+        assert!(error.contains("Argument `query` expects type `String!`, got `null`"));
+        assert!(!error.contains("get_user.graphql"));
+
+        let code = CodeGen::build_from_schema_literal(SCHEMA.to_string())
+            .persisted_query(
+                "get_user.graphql".to_string(),
+                r#"{ user(id: 1) { name } }"#.to_string(),
+            )
+            .finish()
+            .generate_code()
+            .unwrap()
+            .to_string();
+
+        assert!(code.contains("pub static PERSISTED_QUERIES"));
+    }
+}
@@ -1,8 +1,8 @@
-use super::CodeGenPass;
+use super::{is_int_type, CodeGenPass};
 use crate::ast_pass::{
-    error::ErrorKind,
+    directive_parsing::cost_directive,
     schema_visitor::{visit_document, SchemaVisitor},
-    type_name, EmitError, TypeKind,
+    type_name, TypeKind,
 };
 use graphql_parser::schema::*;
 use heck::{CamelCase, MixedCase, SnakeCase};
@@ -17,24 +17,31 @@ use syn::Ident;
 struct QueryTrailCodeGenPass<'pass, 'doc> {
     pass: &'pass mut CodeGenPass<'doc>,
     tokens: TokenStream,
+    field_enums: TokenStream,
     fields_map: HashMap<&'doc str, Vec<&'doc Field<'doc, &'doc str>>>,
+    type_complexities: HashMap<&'doc str, u64>,
 }
 
 impl<'doc> CodeGenPass<'doc> {
     pub fn gen_query_trails(&mut self, doc: &'doc Document<'doc, &'doc str>) -> TokenStream {
         let fields_map = build_fields_map(doc);
+        let type_complexities = build_type_complexities(doc);
 
         let mut query_trail_pass = QueryTrailCodeGenPass {
             pass: self,
             tokens: TokenStream::new(),
+            field_enums: TokenStream::new(),
             fields_map,
+            type_complexities,
         };
         query_trail_pass.gen_query_trail();
         query_trail_pass.gen_from_default_scalar_value();
         query_trail_pass.gen_from_look_ahead_value();
+        query_trail_pass.gen_query_cost();
         visit_document(&mut query_trail_pass, doc);
 
         let tokens = query_trail_pass.tokens;
+        let field_enums = query_trail_pass.field_enums;
 
         quote! {
             pub use juniper_from_schema::{Walked, NotWalked, QueryTrail};
@@ -49,6 +56,16 @@ impl<'doc> CodeGenPass<'doc> {
                 use super::*;
 
                 #tokens
+
+                /// Enums with the fields of each type, returned by `selected_fields`.
+                ///
+                /// They live in their own module so they can be named after their type without
+                /// clashing with the types of the schema.
+                ///
+                /// Generated by `juniper-from-schema`.
+                pub mod fields {
+                    #field_enums
+                }
             }
         }
     }
@@ -73,6 +90,17 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
                 /// Generated by juniper-from-schema.
                 fn downcast(self) -> QueryTrail<'r, T, Walked>;
             }
+
+            /// Convert from one type of `SelectionTree` to another. Used for converting interface
+            /// and union trees into concrete subtypes.
+            ///
+            /// Generated by `juniper-from-schema`.
+            pub trait DowncastSelectionTree<T> {
+                /// Perform the downcast.
+                ///
+                /// Generated by juniper-from-schema.
+                fn downcast(self) -> juniper_from_schema::SelectionTree<T>;
+            }
         })
     }
 
@@ -85,56 +113,61 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
             /// Generated by `juniper-from-schema`.
             pub(super) trait FromDefaultScalarValue<T> {
                 /// Perform the conversion.
-                fn from(self) -> T;
+                fn from(self) -> Result<T, juniper_from_schema::QueryTrailError>;
             }
         });
 
-        let gen_impl = |to: &str, variant: &str| {
+        let gen_impl = |to: &str, variant: &str, coercions: TokenStream| {
             let to = format_ident!("{}", to);
             let variant = format_ident!("{}", variant);
             quote! {
                 impl<'a, 'b> FromDefaultScalarValue<#to> for &'a &'b juniper_from_schema::juniper::DefaultScalarValue {
-                    fn from(self) -> #to {
-                        match self {
-                            juniper_from_schema::juniper::DefaultScalarValue::#variant(x) => x.to_owned(),
-                            other => {
-                                match other {
-                                    juniper_from_schema::juniper::DefaultScalarValue::Int(_) => panic!(
-                                        "Failed converting scalar value. Expected `{}` got `Int`",
-                                        stringify!(#to),
-                                    ),
-                                    juniper_from_schema::juniper::DefaultScalarValue::String(_) => panic!(
-                                        "Failed converting scalar value. Expected `{}` got `String`",
-                                        stringify!(#to),
-                                    ),
-                                    juniper_from_schema::juniper::DefaultScalarValue::Float(_) => panic!(
-                                        "Failed converting scalar value. Expected `{}` got `Float`",
-                                        stringify!(#to),
-                                    ),
-                                    juniper_from_schema::juniper::DefaultScalarValue::Boolean(_) => panic!(
-                                        "Failed converting scalar value. Expected `{}` got `Boolean`",
-                                        stringify!(#to),
-                                    ),
-                                }
+                    fn from(self) -> Result<#to, juniper_from_schema::QueryTrailError> {
+                        let other = match self {
+                            juniper_from_schema::juniper::DefaultScalarValue::#variant(x) => {
+                                return Ok(x.to_owned())
                             }
-                        }
+                            #coercions
+                            other => other,
+                        };
+
+                        let found = match other {
+                            juniper_from_schema::juniper::DefaultScalarValue::Int(_) => "Int",
+                            juniper_from_schema::juniper::DefaultScalarValue::String(_) => "String",
+                            juniper_from_schema::juniper::DefaultScalarValue::Float(_) => "Float",
+                            juniper_from_schema::juniper::DefaultScalarValue::Boolean(_) => "Boolean",
+                        };
+
+                        Err(juniper_from_schema::QueryTrailError::UnexpectedValue {
+                            expected: format!("`{}`", stringify!(#to)),
+                            found: found.to_string(),
+                        })
                     }
                 }
             }
         };
 
-        self.tokens.extend(gen_impl("i32", "Int"));
-        self.tokens.extend(gen_impl("String", "String"));
-        self.tokens.extend(gen_impl("f64", "Float"));
-        self.tokens.extend(gen_impl("bool", "Boolean"));
+        self.tokens.extend(gen_impl("i32", "Int", quote! {}));
+        self.tokens.extend(gen_impl("String", "String", quote! {}));
+        // Ints are coerced to floats, as required by the spec
+        self.tokens.extend(gen_impl(
+            "f64",
+            "Float",
+            quote! {
+                juniper_from_schema::juniper::DefaultScalarValue::Int(x) => {
+                    return Ok(f64::from(*x))
+                }
+            },
+        ));
+        self.tokens.extend(gen_impl("bool", "Boolean", quote! {}));
 
         self.tokens.extend(quote! {
             impl<'a, 'b, T> FromDefaultScalarValue<Option<T>> for &'a &'b juniper_from_schema::juniper::DefaultScalarValue
             where
                 &'a &'b juniper_from_schema::juniper::DefaultScalarValue: FromDefaultScalarValue<T>,
             {
-                fn from(self) -> Option<T> {
-                    Some(self.from())
+                fn from(self) -> Result<Option<T>, juniper_from_schema::QueryTrailError> {
+                    self.from().map(Some)
                 }
             }
         });
@@ -149,7 +182,7 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
             /// Generated by `juniper-from-schema`.
             pub(super) trait FromLookAheadValue<T> {
                 /// Perform the conversion.
-                fn from(self) -> T;
+                fn from(self) -> Result<T, juniper_from_schema::QueryTrailError>;
             }
         });
 
@@ -159,23 +192,15 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
                 impl<'a, 'b> FromLookAheadValue<#to>
                     for &'a juniper_from_schema::juniper::LookAheadValue<'b, juniper_from_schema::juniper::DefaultScalarValue>
                 {
-                    fn from(self) -> #to {
+                    fn from(self) -> Result<#to, juniper_from_schema::QueryTrailError> {
                         match self {
                             juniper_from_schema::juniper::LookAheadValue::Scalar(scalar) => {
                                 FromDefaultScalarValue::from(scalar)
                             },
-                            juniper_from_schema::juniper::LookAheadValue::Null => panic!(
-                                "Failed converting look ahead value. Expected scalar type got `null`",
-                            ),
-                            juniper_from_schema::juniper::LookAheadValue::Enum(_) => panic!(
-                                "Failed converting look ahead value. Expected scalar type got `enum`",
-                            ),
-                            juniper_from_schema::juniper::LookAheadValue::List(_) => panic!(
-                                "Failed converting look ahead value. Expected scalar type got `list`",
-                            ),
-                            juniper_from_schema::juniper::LookAheadValue::Object(_) => panic!(
-                                "Failed converting look ahead value. Expected scalar type got `object`",
-                            ),
+                            other => Err(juniper_from_schema::QueryTrailError::unexpected_value(
+                                "scalar type",
+                                other,
+                            )),
                         }
                     }
                 }
@@ -193,10 +218,10 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
             where
                 &'a juniper_from_schema::juniper::LookAheadValue<'b, juniper_from_schema::juniper::DefaultScalarValue>: FromLookAheadValue<T>,
             {
-                fn from(self) -> Option<T> {
+                fn from(self) -> Result<Option<T>, juniper_from_schema::QueryTrailError> {
                     match self {
-                        juniper_from_schema::juniper::LookAheadValue::Null => None,
-                        other => Some(other.from()),
+                        juniper_from_schema::juniper::LookAheadValue::Null => Ok(None),
+                        other => other.from().map(Some),
                     }
                 }
             }
@@ -206,23 +231,20 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
             where
                 &'a juniper_from_schema::juniper::LookAheadValue<'b, juniper_from_schema::juniper::DefaultScalarValue>: FromLookAheadValue<T>,
             {
-                fn from(self) -> Vec<T> {
+                fn from(self) -> Result<Vec<T>, juniper_from_schema::QueryTrailError> {
                     match self {
                         juniper_from_schema::juniper::LookAheadValue::List(values) => {
-                            values.iter().map(|value| value.from()).collect::<Vec<_>>()
+                            values.iter().map(|value| value.from()).collect()
                         },
-                        juniper_from_schema::juniper::LookAheadValue::Scalar(_) => panic!(
-                            "Failed converting look ahead value. Expected list type got `scalar`",
-                        ),
-                        juniper_from_schema::juniper::LookAheadValue::Null => panic!(
-                            "Failed converting look ahead value. Expected list type got `null`",
-                        ),
-                        juniper_from_schema::juniper::LookAheadValue::Enum(_) => panic!(
-                            "Failed converting look ahead value. Expected list type got `enum`",
-                        ),
-                        juniper_from_schema::juniper::LookAheadValue::Object(_) => panic!(
-                            "Failed converting look ahead value. Expected list type got `object`",
+                        juniper_from_schema::juniper::LookAheadValue::Null => Err(
+                            juniper_from_schema::QueryTrailError::unexpected_value(
+                                "list type",
+                                self,
+                            ),
                         ),
+                        // A single value is coerced to a list with one item, as required by the
+                        // spec
+                        other => other.from().map(|value| vec![value]),
                     }
                 }
             }
@@ -230,9 +252,14 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
             impl<'a, 'b> FromLookAheadValue<juniper_from_schema::juniper::ID>
                 for &'a juniper_from_schema::juniper::LookAheadValue<'b, juniper_from_schema::juniper::DefaultScalarValue>
             {
-                fn from(self) -> juniper_from_schema::juniper::ID {
-                    let s = FromLookAheadValue::<String>::from(self);
-                    juniper_from_schema::juniper::ID::new(s)
+                fn from(self) -> Result<juniper_from_schema::juniper::ID, juniper_from_schema::QueryTrailError> {
+                    match self {
+                        juniper_from_schema::juniper::LookAheadValue::Scalar(
+                            juniper_from_schema::juniper::DefaultScalarValue::Int(x),
+                        ) => Ok(juniper_from_schema::juniper::ID::new(x.to_string())),
+                        other => FromLookAheadValue::<String>::from(other)
+                            .map(juniper_from_schema::juniper::ID::new),
+                    }
                 }
             }
         });
@@ -242,12 +269,13 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
                 impl<'a, 'b> FromLookAheadValue<url::Url>
                     for &'a juniper_from_schema::juniper::LookAheadValue<'b, juniper_from_schema::juniper::DefaultScalarValue>
                 {
-                    fn from(self) -> url::Url {
-                        let s = FromLookAheadValue::<String>::from(self);
-                        match url::Url::parse(&s) {
-                            Ok(url) => url,
-                            Err(e) => panic!("Error parsing URL: {}", e),
-                        }
+                    fn from(self) -> Result<url::Url, juniper_from_schema::QueryTrailError> {
+                        let s = FromLookAheadValue::<String>::from(self)?;
+                        url::Url::parse(&s).map_err(|e| {
+                            juniper_from_schema::QueryTrailError::InvalidValue(
+                                format!("Error parsing URL: {}", e),
+                            )
+                        })
                     }
                 }
             });
@@ -258,12 +286,13 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
                 impl<'a, 'b> FromLookAheadValue<uuid::Uuid>
                     for &'a juniper_from_schema::juniper::LookAheadValue<'b, juniper_from_schema::juniper::DefaultScalarValue>
                 {
-                    fn from(self) -> uuid::Uuid {
-                        let s = FromLookAheadValue::<String>::from(self);
-                        match uuid::Uuid::parse_str(&s) {
-                            Ok(url) => url,
-                            Err(e) => panic!("Error parsing UUID: {}", e),
-                        }
+                    fn from(self) -> Result<uuid::Uuid, juniper_from_schema::QueryTrailError> {
+                        let s = FromLookAheadValue::<String>::from(self)?;
+                        uuid::Uuid::parse_str(&s).map_err(|e| {
+                            juniper_from_schema::QueryTrailError::InvalidValue(
+                                format!("Error parsing UUID: {}", e),
+                            )
+                        })
                     }
                 }
             });
@@ -274,17 +303,14 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
                 impl<'a, 'b> FromLookAheadValue<chrono::NaiveDate>
                     for &'a juniper_from_schema::juniper::LookAheadValue<'b, juniper_from_schema::juniper::DefaultScalarValue>
                 {
-                    fn from(self) -> chrono::NaiveDate {
-                        let s = FromLookAheadValue::<String>::from(self);
-                        match chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d") {
-                            Ok(date) => date,
-                            Err(e) => {
-                                panic!(
-                                    "Error parsing NaiveDate. Format used is `%Y-%m-%d`\n{}",
-                                    e,
-                                )
-                            },
-                        }
+                    fn from(self) -> Result<chrono::NaiveDate, juniper_from_schema::QueryTrailError> {
+                        let s = FromLookAheadValue::<String>::from(self)?;
+                        chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d").map_err(|e| {
+                            juniper_from_schema::QueryTrailError::InvalidValue(format!(
+                                "Error parsing NaiveDate. Format used is `%Y-%m-%d`\n{}",
+                                e,
+                            ))
+                        })
                     }
                 }
             });
@@ -295,17 +321,14 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
                 impl<'a, 'b> FromLookAheadValue<chrono::DateTime<chrono::Utc>>
                     for &'a juniper_from_schema::juniper::LookAheadValue<'b, juniper_from_schema::juniper::DefaultScalarValue>
                 {
-                    fn from(self) -> chrono::DateTime<chrono::Utc> {
-                        let s = FromLookAheadValue::<String>::from(self);
-                        let parsed = chrono::DateTime::parse_from_rfc3339(&s);
-                        match parsed {
-                            Ok(date_time) => date_time.into(),
-                            Err(e) => {
-                                panic!(
-                                    "Error parsing DateTime. Format used is RFC 3339 (aka ISO 8601)\n{}",
-                                    e,
-                                )
-                            },
+                    fn from(self) -> Result<chrono::DateTime<chrono::Utc>, juniper_from_schema::QueryTrailError> {
+                        let s = FromLookAheadValue::<String>::from(self)?;
+                        match chrono::DateTime::parse_from_rfc3339(&s) {
+                            Ok(date_time) => Ok(date_time.into()),
+                            Err(e) => Err(juniper_from_schema::QueryTrailError::InvalidValue(format!(
+                                "Error parsing DateTime. Format used is RFC 3339 (aka ISO 8601)\n{}",
+                                e,
+                            ))),
                         }
                     }
                 }
@@ -313,29 +336,279 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
                 impl<'a, 'b> FromLookAheadValue<chrono::NaiveDateTime>
                     for &'a juniper_from_schema::juniper::LookAheadValue<'b, juniper_from_schema::juniper::DefaultScalarValue>
                 {
-                    fn from(self) -> chrono::NaiveDateTime {
-                        let s = FromLookAheadValue::<String>::from(self);
-                        let parsed = chrono::NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S");
-                        match parsed {
-                            Ok(date_time) => date_time.into(),
-                            Err(e) => {
-                                panic!(
-                                    "Error parsing NaiveDateTime. Format used is `%Y-%m-%d %H:%M:%S`\n{}",
-                                    e,
-                                )
-                            },
-                        }
+                    fn from(self) -> Result<chrono::NaiveDateTime, juniper_from_schema::QueryTrailError> {
+                        let s = FromLookAheadValue::<String>::from(self)?;
+                        chrono::NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S").map_err(|e| {
+                            juniper_from_schema::QueryTrailError::InvalidValue(format!(
+                                "Error parsing NaiveDateTime. Format used is `%Y-%m-%d %H:%M:%S`\n{}",
+                                e,
+                            ))
+                        })
                     }
                 }
             });
         }
     }
 
+    fn gen_query_cost(&mut self) {
+        self.tokens.extend(quote! {
+            /// Extension trait for `QueryTrail` to compute the cost of incoming queries from the
+            /// `@cost` directives in the schema.
+            ///
+            /// Generated by `juniper-from-schema`.
+            pub trait QueryTrailCostExtensions {
+                /// The cost and depth of the fields selected on this trail.
+                ///
+                /// Generated by `juniper-from-schema`.
+                fn query_cost(&self) -> juniper_from_schema::QueryCost;
+            }
+        });
+
+        let query_type_name = match self.pass.ast_data.query_type_name() {
+            Some(name) => name,
+            None => return,
+        };
+        let query_field_cost_fn = field_cost_fn_name(query_type_name);
+        let query_fields_cost_fn = fields_cost_fn_name(query_type_name);
+
+        // Operations on root types the schema doesn't have select nothing that can be costed
+        let root_fields_cost = |name: Option<&str>| match name {
+            Some(name) => {
+                let fields_cost_fn = fields_cost_fn_name(name);
+                quote! { #fields_cost_fn(&fields) }
+            }
+            None => quote! { juniper_from_schema::QueryCost::default() },
+        };
+        let mutation_fields_cost = root_fields_cost(self.pass.ast_data.mutation_type_name());
+        let subscription_fields_cost =
+            root_fields_cost(self.pass.ast_data.subscription_type_name());
+
+        self.tokens.extend(quote! {
+            /// Compute the cost and depth of a field of the query type, from the `@cost`
+            /// directives in the schema.
+            ///
+            /// `look_ahead` is the selection of the field, such as the one returned by
+            /// `executor.look_ahead()` in the resolvers of the query type. The cost includes the
+            /// field itself and every field selected on it.
+            ///
+            /// Generated by `juniper-from-schema`.
+            pub fn query_cost<'r>(
+                look_ahead: &'r juniper_from_schema::juniper::LookAheadSelection<
+                    'r,
+                    juniper_from_schema::juniper::DefaultScalarValue,
+                >,
+            ) -> juniper_from_schema::QueryCost {
+                #query_field_cost_fn(&juniper_from_schema::look_ahead_selected_field(look_ahead))
+                    .unwrap_or_default()
+            }
+
+            /// Extension trait for `Schema` to compute the cost of whole queries from the `@cost`
+            /// directives in the schema.
+            ///
+            /// Generated by `juniper-from-schema`.
+            pub trait SchemaCostExtensions {
+                /// The cost and depth of every root field selected by an operation in a parsed
+                /// query document.
+                ///
+                /// `operation_name` and `variables` are the ones the query will be executed
+                /// with. Fails like executing the query would if the operation can't be found.
+                ///
+                /// Generated by `juniper-from-schema`.
+                fn query_cost(
+                    &self,
+                    document: &juniper_from_schema::juniper::Document<
+                        juniper_from_schema::juniper::DefaultScalarValue,
+                    >,
+                    operation_name: Option<&str>,
+                    variables: &juniper_from_schema::juniper::Variables,
+                ) -> Result<
+                    juniper_from_schema::QueryCost,
+                    juniper_from_schema::juniper::GraphQLError<'static>,
+                >;
+            }
+
+            impl SchemaCostExtensions for Schema {
+                fn query_cost(
+                    &self,
+                    document: &juniper_from_schema::juniper::Document<
+                        juniper_from_schema::juniper::DefaultScalarValue,
+                    >,
+                    operation_name: Option<&str>,
+                    variables: &juniper_from_schema::juniper::Variables,
+                ) -> Result<
+                    juniper_from_schema::QueryCost,
+                    juniper_from_schema::juniper::GraphQLError<'static>,
+                > {
+                    let (operation_type, fields) = juniper_from_schema::operation_selected_fields(
+                        document,
+                        operation_name,
+                        variables,
+                    )?;
+
+                    Ok(match operation_type {
+                        juniper_from_schema::juniper::OperationType::Query => {
+                            #query_fields_cost_fn(&fields)
+                        }
+                        juniper_from_schema::juniper::OperationType::Mutation => {
+                            #mutation_fields_cost
+                        }
+                        juniper_from_schema::juniper::OperationType::Subscription => {
+                            #subscription_fields_cost
+                        }
+                    })
+                }
+            }
+        });
+    }
+
+    fn gen_query_cost_impl(&mut self, obj: &InternalQueryTrailNode<'doc>) {
+        let name = obj.name();
+        let field_cost_fn = field_cost_fn_name(&name.to_string());
+
+        // Unions have no fields of their own, and the fields of interfaces and unions that are
+        // selected with fragments are costed by the possible types that have them
+        let (arms, possible_types) = match obj {
+            InternalQueryTrailNode::Object(_) => (self.gen_field_cost_arms(obj), vec![]),
+            InternalQueryTrailNode::Interface(i) => (
+                self.gen_field_cost_arms(obj),
+                self.pass
+                    .ast_data
+                    .get_implementors_of_interface(i.name)
+                    .cloned()
+                    .unwrap_or_default(),
+            ),
+            InternalQueryTrailNode::Union(u, _) => (vec![], u.types.clone()),
+        };
+        let possible_type_field_cost_fns = possible_types.into_iter().map(field_cost_fn_name);
+        let fallback = quote! {
+            None #(.or_else(|| #possible_type_field_cost_fns(field)))*
+        };
+
+        let body = if arms.is_empty() {
+            fallback
+        } else {
+            quote! {
+                match field.name() {
+                    #(#arms,)*
+                    _ => #fallback,
+                }
+            }
+        };
+        let fields_cost_fn = fields_cost_fn_name(&name.to_string());
+
+        self.tokens.extend(quote! {
+            #[allow(unused_variables)]
+            fn #field_cost_fn(
+                field: &juniper_from_schema::SelectedField,
+            ) -> Option<juniper_from_schema::QueryCost> {
+                #body
+            }
+
+            fn #fields_cost_fn(
+                fields: &[juniper_from_schema::SelectedField],
+            ) -> juniper_from_schema::QueryCost {
+                fields.iter().filter_map(#field_cost_fn).sum()
+            }
+
+            impl<'r, K> QueryTrailCostExtensions for QueryTrail<'r, #name, K> {
+                fn query_cost(&self) -> juniper_from_schema::QueryCost {
+                    #fields_cost_fn(&juniper_from_schema::look_ahead_selected_fields(
+                        self.look_ahead,
+                    ))
+                }
+            }
+        });
+    }
+
+    fn gen_field_cost_arms(&mut self, obj: &InternalQueryTrailNode<'doc>) -> Vec<TokenStream> {
+        obj.fields()
+            .into_iter()
+            .map(|field| self.gen_field_cost_arm(field, obj))
+            .collect()
+    }
+
+    fn gen_field_cost_arm(
+        &mut self,
+        field: &'doc Field<'doc, &'doc str>,
+        obj: &InternalQueryTrailNode<'doc>,
+    ) -> TokenStream {
+        let string_name = &field.name.to_mixed_case();
+        let field_type_name = type_name(&field.field_type);
+        let cost = cost_directive(&field.directives).unwrap_or_default();
+
+        // Fields cost 1 unless they, or the type they return, say otherwise
+        let complexity = cost
+            .complexity
+            .or_else(|| self.type_complexities.get(field_type_name).copied())
+            .unwrap_or(1);
+
+        let multipliers = cost
+            .multipliers
+            .iter()
+            .filter_map(|multiplier| {
+                field
+                    .arguments
+                    .iter()
+                    .find(|arg| arg.name == multiplier && is_int_type(&arg.value_type))
+            })
+            .map(|arg| {
+                let try_ident = format_ident!("try_{}", arg.name.to_snake_case());
+                let nullable =
+                    arg.default_value.is_none() && !matches!(arg.value_type, Type::NonNullType(_));
+
+                if nullable {
+                    quote! { args.#try_ident().ok().flatten() }
+                } else {
+                    quote! { args.#try_ident().ok() }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let args = if multipliers.is_empty() {
+            quote! {}
+        } else {
+            let args_type_name = format_ident!(
+                "{}{}Args",
+                obj.name(),
+                field.name.to_snake_case().to_camel_case()
+            );
+            quote! {
+                let args = #args_type_name(Some(
+                    juniper_from_schema::ArgumentSource::SelectionTree(field),
+                ));
+            }
+        };
+
+        let ty = self
+            .pass
+            .graphql_type_to_rust_type(&field.field_type, false, field.position);
+        let children = match ty.kind() {
+            TypeKind::Scalar => quote! { juniper_from_schema::QueryCost::default() },
+            TypeKind::Type => {
+                let fields_cost_fn = fields_cost_fn_name(field_type_name);
+                quote! { #fields_cost_fn(field.fields()) }
+            }
+        };
+
+        quote! {
+            #string_name => {
+                #args
+                Some(juniper_from_schema::QueryCost::field(
+                    #complexity,
+                    &[#(#multipliers),*],
+                    #children,
+                ))
+            }
+        }
+    }
+
     fn gen_field_walk_methods(&mut self, obj: InternalQueryTrailNode<'doc>) {
         let name = obj.name();
         let trait_name = format_ident!("QueryTrail{}Extensions", obj.name());
         let args_trait_name = format_ident!("QueryTrail{}ArgumentsExtensions", obj.name());
+        let tree_trait_name = format_ident!("SelectionTree{}Extensions", obj.name());
         let fields = obj.fields();
+        let field_enum_name = format_ident!("{}", obj.name());
 
         let mut method_signatures = vec![];
         let mut method_implementations = vec![];
@@ -344,31 +617,100 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
         let mut argument_implementations = vec![];
         let mut argument_types = vec![];
 
+        let mut field_variants = vec![];
+        let mut field_variant_matches = vec![];
+
+        let mut tree_signatures = vec![];
+        let mut tree_implementations = vec![];
+
         for field in fields {
             let FieldWalkMethod {
                 method_signature,
                 method_implementation,
+                selections_signature,
+                selections_implementation,
                 argument_signature,
                 argument_implementation,
                 argument_type,
+                trail_type,
             } = self.gen_field_walk_method(field, &obj);
 
+            let (tree_signature, tree_implementation) =
+                self.gen_selection_tree_methods(field, trail_type.as_ref(), &obj);
+            tree_signatures.push(tree_signature);
+            tree_implementations.push(tree_implementation);
+
+            let (field_variant, field_variant_match) =
+                gen_field_variant(field, &field_enum_name, trail_type.as_ref());
+            field_variants.push(field_variant);
+            field_variant_matches.push(field_variant_match);
+
             method_signatures.push(method_signature);
+            method_signatures.push(selections_signature);
             method_implementations.push(method_implementation);
+            method_implementations.push(selections_implementation);
 
             argument_signatures.push(argument_signature);
             argument_implementations.push(argument_implementation);
             argument_types.push(argument_type);
         }
 
+        // The enum only needs a lifetime if some of its variants hold a `QueryTrail`
+        let (field_enum_definition, child) =
+            if field_variants.iter().any(|(_, has_trail)| *has_trail) {
+                (quote! { #field_enum_name<'r> }, quote! { child })
+            } else {
+                (quote! { #field_enum_name }, quote! { _ })
+            };
+        let field_enum_type = quote! { fields::#field_enum_definition };
+        let field_variants = field_variants.into_iter().map(|(variant, _)| variant);
+        let field_enum_doc = format!("The fields of `{}` that can be selected in a query.", name);
+
+        self.field_enums.extend(quote! {
+            #[doc = #field_enum_doc]
+            ///
+            /// Returned by `selected_fields`. Fields that return objects, interfaces, or unions
+            /// hold the walked `QueryTrail` for the fields selected on them.
+            ///
+            /// Generated by `juniper-from-schema`.
+            pub enum #field_enum_definition {
+                #(#field_variants,)*
+            }
+        });
+
         self.tokens.extend(quote! {
             /// Extension trait for `QueryTrail` to inspect incoming queries.
             pub trait #trait_name<'r, K> {
                 #(#method_signatures)*
+
+                /// The fields selected in the incoming query, in the order they are selected.
+                ///
+                /// Fields selected several times under different aliases are only included once.
+                ///
+                /// Generated by `juniper-from-schema`.
+                fn selected_fields(&self) -> std::vec::IntoIter<#field_enum_type>;
             }
 
             impl<'r, K> #trait_name<'r, K> for QueryTrail<'r, #name, K> {
                 #(#method_implementations)*
+
+                fn selected_fields(&self) -> std::vec::IntoIter<#field_enum_type> {
+                    let mut seen = std::collections::HashSet::new();
+                    let mut fields = Vec::new();
+
+                    for (name, #child) in juniper_from_schema::look_ahead_children(self.look_ahead) {
+                        let field = match name.as_str() {
+                            #(#field_variant_matches,)*
+                            _ => continue,
+                        };
+
+                        if seen.insert(name) {
+                            fields.push(field);
+                        }
+                    }
+
+                    fields.into_iter()
+                }
             }
 
             /// Extension trait for `QueryTrail` to inspect incoming query arguments.
@@ -381,8 +723,21 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
             }
 
             #(#argument_types)*
+
+            /// Extension trait for `SelectionTree` to inspect selections taken with
+            /// `to_selection_tree`.
+            ///
+            /// Generated by `juniper-from-schema`.
+            pub trait #tree_trait_name {
+                #(#tree_signatures)*
+            }
+
+            impl #tree_trait_name for juniper_from_schema::SelectionTree<#name> {
+                #(#tree_implementations)*
+            }
         });
 
+        self.gen_query_cost_impl(&obj);
         self.gen_conversion_methods(name, obj);
     }
 
@@ -422,38 +777,15 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
                         }
                     }
                 }
-            });
-        }
-    }
 
-    fn error_msg_if_field_types_dont_overlap(&mut self, union: &'doc UnionType<'doc, &'doc str>) {
-        let fields_map = &self.fields_map;
-        let mut prev: HashMap<&'doc str, (&'doc str, &'doc str)> = HashMap::new();
-
-        for type_b in &union.types {
-            if let Some(fields) = fields_map.get(type_b) {
-                for field in fields {
-                    let field_type_b = type_name(&field.field_type);
-
-                    if let Some((type_a, field_type_a)) = prev.get(&field.name) {
-                        if field_type_b != *field_type_a {
-                            self.pass.emit_error(
-                                union.position,
-                                ErrorKind::UnionFieldTypeMismatch {
-                                    union_name: union.name.to_string(),
-                                    field_name: field.name.to_string(),
-                                    type_a: type_a.to_string(),
-                                    type_b: type_b.to_string(),
-                                    field_type_a: field_type_a.to_string(),
-                                    field_type_b: field_type_b.to_string(),
-                                },
-                            );
-                        }
+                impl DowncastSelectionTree<#type_>
+                    for &juniper_from_schema::SelectionTree<#original_type_name>
+                {
+                    fn downcast(self) -> juniper_from_schema::SelectionTree<#type_> {
+                        juniper_from_schema::SelectionTree::new(self.iter().cloned().collect())
                     }
-
-                    prev.insert(&field.name, (type_b, field_type_b));
                 }
-            }
+            });
         }
     }
 
@@ -482,27 +814,26 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
 
                 let method_implementation = quote! {
                     fn #name(&self) -> bool {
-                        use juniper_from_schema::juniper::LookAheadMethods;
-
-                        self.look_ahead
-                            .map(|la| {
-                                la.children()
-                                    .iter()
-                                    .any(|child| child.field_name() == #string_name)
-                            })
-                            .unwrap_or(false)
+                        !juniper_from_schema::look_ahead_children_named(self.look_ahead, #string_name)
+                            .is_empty()
                     }
                 };
 
+                let (selections_signature, selections_implementation) =
+                    self.gen_selections_method(field, &name, None, obj);
+
                 let (argument_signature, argument_implementation, argument_type) =
                     self.gen_args_query_trail(field, &name, obj);
 
                 FieldWalkMethod {
                     method_signature,
                     method_implementation,
+                    selections_signature,
+                    selections_implementation,
                     argument_signature,
                     argument_implementation,
                     argument_type,
+                    trail_type: None,
                 }
             }
             TypeKind::Type => {
@@ -518,13 +849,12 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
 
                 let method_implementation = quote! {
                     fn #name(&self) -> QueryTrail<'r, #field_type, juniper_from_schema::NotWalked> {
-                        use juniper_from_schema::juniper::LookAheadMethods;
-
-                        let child = self.look_ahead.and_then(|la| {
-                            la.children().into_iter().find(|child| {
-                                child.field_name() == #string_name
-                            })
-                        });
+                        let child = juniper_from_schema::look_ahead_children_named(
+                            self.look_ahead,
+                            #string_name,
+                        )
+                        .into_iter()
+                        .next();
 
                         QueryTrail {
                             look_ahead: child,
@@ -534,20 +864,178 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
                     }
                 };
 
+                let (selections_signature, selections_implementation) =
+                    self.gen_selections_method(field, &name, Some(&field_type), obj);
+
                 let (argument_signature, argument_implementation, argument_type) =
                     self.gen_args_query_trail(field, &name, obj);
 
                 FieldWalkMethod {
                     method_signature,
                     method_implementation,
+                    selections_signature,
+                    selections_implementation,
                     argument_signature,
                     argument_implementation,
                     argument_type,
+                    trail_type: Some(field_type),
                 }
             }
         }
     }
 
+    fn gen_selections_method(
+        &mut self,
+        field: &'doc Field<'doc, &'doc str>,
+        name: &Ident,
+        field_type: Option<&Ident>,
+        obj: &InternalQueryTrailNode,
+    ) -> (TokenStream, TokenStream) {
+        let selections_method_name = format_ident!("{}_selections", name);
+        let string_name = &field.name.to_mixed_case();
+
+        let (node_type, node) = if let Some(field_type) = field_type {
+            (
+                quote! { QueryTrail<'r, #field_type, juniper_from_schema::Walked> },
+                quote! {
+                    QueryTrail {
+                        look_ahead: Some(child),
+                        node_type: std::marker::PhantomData,
+                        walked: juniper_from_schema::Walked,
+                    }
+                },
+            )
+        } else {
+            (quote! { () }, quote! { () })
+        };
+
+        let (args_type, args) = if field.arguments.is_empty() {
+            (quote! { () }, quote! { () })
+        } else {
+            let args_type_name =
+                format_ident!("{}{}Args", obj.name(), name.to_string().to_camel_case());
+            (
+                quote! { #args_type_name<'r> },
+                quote! {
+                    #args_type_name(Some(juniper_from_schema::ArgumentSource::LookAhead(child)))
+                },
+            )
+        };
+
+        let selections_signature = quote! {
+            /// Every occurrence of the field in the incoming query, one for each alias it is
+            /// selected under.
+            ///
+            /// Generated by `juniper-from-schema`.
+            fn #selections_method_name(
+                &self,
+            ) -> juniper_from_schema::Selections<'r, #node_type, #args_type>;
+        };
+
+        let selections_implementation = quote! {
+            fn #selections_method_name(
+                &self,
+            ) -> juniper_from_schema::Selections<'r, #node_type, #args_type> {
+                let selections = juniper_from_schema::look_ahead_children_named(
+                    self.look_ahead,
+                    #string_name,
+                )
+                .into_iter()
+                .map(|child| {
+                    juniper_from_schema::Selection::new(child, #string_name, #node, #args)
+                })
+                .collect();
+
+                juniper_from_schema::Selections::new(selections)
+            }
+        };
+
+        (selections_signature, selections_implementation)
+    }
+
+    fn gen_selection_tree_methods(
+        &mut self,
+        field: &'doc Field<'doc, &'doc str>,
+        field_type: Option<&Ident>,
+        obj: &InternalQueryTrailNode,
+    ) -> (TokenStream, TokenStream) {
+        let name = format_ident!("{}", field.name.to_snake_case());
+        let args_method_name = format_ident!("{}_args", name);
+        let string_name = &field.name.to_mixed_case();
+
+        let (walk_signature, walk_implementation) = if let Some(field_type) = field_type {
+            (
+                quote! {
+                    /// The fields selected on a field, if the field is selected.
+                    ///
+                    /// Generated by `juniper-from-schema`.
+                    fn #name(&self) -> Option<juniper_from_schema::SelectionTree<#field_type>>;
+                },
+                quote! {
+                    fn #name(&self) -> Option<juniper_from_schema::SelectionTree<#field_type>> {
+                        self.field_named(#string_name)
+                            .map(|field| field.to_selection_tree())
+                    }
+                },
+            )
+        } else {
+            (
+                quote! {
+                    /// Check if a scalar leaf node is selected.
+                    ///
+                    /// Generated by `juniper-from-schema`.
+                    fn #name(&self) -> bool;
+                },
+                quote! {
+                    fn #name(&self) -> bool {
+                        self.field_named(#string_name).is_some()
+                    }
+                },
+            )
+        };
+
+        let (args_signature, args_implementation) = if field.arguments.is_empty() {
+            (
+                quote! {
+                    /// Inspect argument in the selection.
+                    ///
+                    /// This field takes no arguments, so therefore it returns `()`.
+                    #[allow(clippy::unused_unit)]
+                    fn #args_method_name(&self) -> ();
+                },
+                quote! {
+                    #[allow(clippy::unused_unit)]
+                    #[inline]
+                    fn #args_method_name(&self) -> () {
+                        ()
+                    }
+                },
+            )
+        } else {
+            let args_type_name =
+                format_ident!("{}{}Args", obj.name(), name.to_string().to_camel_case());
+            (
+                quote! {
+                    /// Inspect argument in the selection.
+                    fn #args_method_name(&self) -> #args_type_name<'_>;
+                },
+                quote! {
+                    fn #args_method_name(&self) -> #args_type_name<'_> {
+                        #args_type_name(
+                            self.field_named(#string_name)
+                                .map(juniper_from_schema::ArgumentSource::SelectionTree),
+                        )
+                    }
+                },
+            )
+        };
+
+        (
+            quote! { #walk_signature #args_signature },
+            quote! { #walk_implementation #args_implementation },
+        )
+    }
+
     fn gen_args_query_trail(
         &mut self,
         field: &'doc Field<'doc, &'doc str>,
@@ -558,8 +1046,6 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
         let mut argument_implementation = quote! {};
         let mut argument_type = quote! {};
 
-        let obj_type = obj.name();
-
         let args_method_name = format_ident!("{}_args", name);
 
         if field.arguments.is_empty() {
@@ -588,25 +1074,32 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
                 fn #args_method_name(&'r self) -> #args_type_name<'r>;
             });
 
+            let string_name = &field.name.to_mixed_case();
+
             argument_implementation.extend(quote! {
                 #[allow(missing_docs)]
                 fn #args_method_name(&'r self) -> #args_type_name<'r> {
-                    #args_type_name(self)
+                    let child = juniper_from_schema::look_ahead_children_named(
+                        self.look_ahead,
+                        #string_name,
+                    )
+                    .into_iter()
+                    .next();
+
+                    #args_type_name(child.map(juniper_from_schema::ArgumentSource::LookAhead))
                 }
             });
 
             let arguments_methods = field
                 .arguments
                 .iter()
-                .map(|input_value| self.gen_argument_look_ahead_methods(input_value, &field.name));
+                .map(|input_value| self.gen_argument_look_ahead_methods(input_value, field.name));
 
             argument_type.extend(quote! {
                 /// This is used for inspecting arguments to a field.
                 ///
                 /// Generated by `juniper-from-schema`.
-                pub struct #args_type_name<'r>(
-                    &'r QueryTrail<'r, #obj_type, juniper_from_schema::Walked>
-                );
+                pub struct #args_type_name<'r>(Option<juniper_from_schema::ArgumentSource<'r>>);
 
                 impl<'r> #args_type_name<'r> {
                     #(#arguments_methods)*
@@ -642,56 +1135,52 @@ impl<'pass, 'doc> QueryTrailCodeGenPass<'pass, 'doc> {
 
         let name = &input_value.name;
         let ident = format_ident!("{}", name.to_snake_case());
+        let try_ident = format_ident!("try_{}", name.to_snake_case());
+
+        // Arguments that aren't passed, or are passed as `null` when there is a default value, are
+        // coerced the same way juniper does before calling the resolver.
+        let (skip_null, missing_argument) = if let Some(default_value) = default_value {
+            (
+                quote! {
+                    .filter(|value| {
+                        !matches!(value, juniper_from_schema::juniper::LookAheadValue::Null)
+                    })
+                },
+                quote! { Ok(#default_value) },
+            )
+        } else if let Type::NonNullType(_) = &input_value.value_type {
+            (
+                quote! {},
+                quote! {
+                    Err(juniper_from_schema::QueryTrailError::MissingArgument(#name.to_string()))
+                },
+            )
+        } else {
+            (quote! {}, quote! { Ok(None) })
+        };
 
-        if let Some(default_value) = default_value {
-            quote! {
-                #[allow(missing_docs)]
-                pub fn #ident(&self) -> #field_type {
-                    use juniper_from_schema::juniper::LookAheadMethods;
-
-                    // these `expect`s are fine since these methods you can only obtain
-                    // arguments from walked query trails
-                    let lh = &self
-                        .0
-                        .look_ahead
-                        .expect("look_ahead")
-                        .children()
-                        .into_iter()
-                        .find(|child| child.field_name() == #field_name)
-                        .expect("select child");
-
-                    let arg = lh.arguments().iter().find(|arg| {
-                        arg.name() == #name
-                    });
-
-                    if let Some(arg) = arg {
-                        let value = arg.value();
-                        FromLookAheadValue::<#field_type>::from(value)
-                    } else {
-                        #default_value
-                    }
+        quote! {
+            /// Like the method without `try_`, but returns an error instead of panicking if the
+            /// argument can't be converted.
+            pub fn #try_ident(&self) -> Result<#field_type, juniper_from_schema::QueryTrailError> {
+                let args = self.0.ok_or_else(|| {
+                    juniper_from_schema::QueryTrailError::FieldNotSelected(#field_name.to_string())
+                })?;
+
+                let value = args.get(#name)#skip_null;
+
+                if let Some(value) = value {
+                    FromLookAheadValue::<#field_type>::from(&value)
+                } else {
+                    #missing_argument
                 }
             }
-        } else {
-            quote! {
-                #[allow(missing_docs)]
-                pub fn #ident(&self) -> #field_type {
-                    use juniper_from_schema::juniper::LookAheadMethods;
-
-                    // these `expect`s are fine since these methods you can only obtain
-                    // arguments from walked query trails
-                    let lh = &self
-                        .0
-                        .look_ahead
-                        .expect("look_ahead")
-                        .children()
-                        .into_iter()
-                        .find(|child| child.field_name() == #field_name)
-                        .expect("select child");
 
-                    let arg = lh.arguments().iter().find(|arg| { arg.name() == #name }).expect("no argument with name");
-                    let value = arg.value();
-                    FromLookAheadValue::<#field_type>::from(value)
+            #[allow(missing_docs)]
+            pub fn #ident(&self) -> #field_type {
+                match self.#try_ident() {
+                    Ok(value) => value,
+                    Err(err) => panic!("{}", err),
                 }
             }
         }
@@ -708,8 +1197,6 @@ impl<'pass, 'doc> SchemaVisitor<'doc> for QueryTrailCodeGenPass<'pass, 'doc> {
     }
 
     fn visit_union_type(&mut self, union: &'doc UnionType<'doc, &'doc str>) {
-        self.error_msg_if_field_types_dont_overlap(union);
-
         self.gen_field_walk_methods(InternalQueryTrailNode::Union(
             union,
             build_union_fields_set(union, &self.fields_map),
@@ -717,12 +1204,49 @@ impl<'pass, 'doc> SchemaVisitor<'doc> for QueryTrailCodeGenPass<'pass, 'doc> {
     }
 }
 
+fn gen_field_variant<'doc>(
+    field: &Field<'doc, &'doc str>,
+    field_enum_name: &Ident,
+    trail_type: Option<&Ident>,
+) -> ((TokenStream, bool), TokenStream) {
+    let variant = format_ident!("{}", field.name.to_camel_case());
+    let string_name = &field.name.to_mixed_case();
+
+    if let Some(trail_type) = trail_type {
+        (
+            (
+                quote! {
+                    #variant(
+                        juniper_from_schema::QueryTrail<'r, super::#trail_type, juniper_from_schema::Walked>
+                    )
+                },
+                true,
+            ),
+            quote! {
+                #string_name => fields::#field_enum_name::#variant(QueryTrail {
+                    look_ahead: Some(child),
+                    node_type: std::marker::PhantomData,
+                    walked: juniper_from_schema::Walked,
+                })
+            },
+        )
+    } else {
+        (
+            (quote! { #variant }, false),
+            quote! { #string_name => fields::#field_enum_name::#variant },
+        )
+    }
+}
+
 struct FieldWalkMethod {
     method_signature: TokenStream,
     method_implementation: TokenStream,
+    selections_signature: TokenStream,
+    selections_implementation: TokenStream,
     argument_signature: TokenStream,
     argument_implementation: TokenStream,
     argument_type: TokenStream,
+    trail_type: Option<Ident>,
 }
 
 #[derive(Clone, Debug)]
@@ -770,21 +1294,61 @@ impl<'a> InternalQueryTrailNode<'a> {
     }
 }
 
+// The fields of the union members that get `QueryTrail` methods. Fields that the members declare
+// with different types or arguments are left out, since the method would have to return a
+// different type depending on the member. Those are only reachable after downcasting.
 fn build_union_fields_set<'d>(
     union: &UnionType<'d, &'d str>,
     fields_map: &HashMap<&'d str, Vec<&'d Field<'d, &'d str>>>,
 ) -> HashSet<HashFieldByName<'d>> {
-    let mut union_fields_set = HashSet::new();
+    let mut fields_by_name: HashMap<&'d str, Vec<&'d Field<'d, &'d str>>> = HashMap::new();
 
     for type_ in &union.types {
         if let Some(fields) = fields_map.get(type_) {
             for field in fields {
-                union_fields_set.insert(HashFieldByName(&field));
+                fields_by_name.entry(field.name).or_default().push(field);
             }
         }
     }
 
-    union_fields_set
+    fields_by_name
+        .into_values()
+        .filter(|fields| fields.windows(2).all(|pair| fields_agree(pair[0], pair[1])))
+        .map(|fields| HashFieldByName(fields[0]))
+        .collect()
+}
+
+fn fields_agree<'d>(a: &Field<'d, &'d str>, b: &Field<'d, &'d str>) -> bool {
+    type_name(&a.field_type) == type_name(&b.field_type)
+        && a.arguments.len() == b.arguments.len()
+        && a.arguments.iter().all(|arg_a| {
+            b.arguments.iter().any(|arg_b| {
+                arg_a.name == arg_b.name
+                    && arg_a.value_type == arg_b.value_type
+                    && arg_a.default_value == arg_b.default_value
+            })
+        })
+}
+
+fn build_type_complexities<'a>(doc: &'a Document<'a, &'a str>) -> HashMap<&'a str, u64> {
+    doc.definitions
+        .iter()
+        .filter_map(|def| match def {
+            Definition::TypeDefinition(TypeDefinition::Object(obj)) => {
+                let complexity = cost_directive(&obj.directives)?.complexity?;
+                Some((obj.name, complexity))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn field_cost_fn_name(type_name: &str) -> Ident {
+    format_ident!("{}_field_cost", type_name.to_snake_case())
+}
+
+fn fields_cost_fn_name(type_name: &str) -> Ident {
+    format_ident!("{}_fields_cost", type_name.to_snake_case())
 }
 
 fn build_fields_map<'a>(
@@ -809,19 +1373,24 @@ fn build_fields_map<'a>(
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::ast_pass::AstData;
+    use crate::ast_pass::{lints::LintConfig, AstData};
 
     #[test]
-    fn test_fails_to_generate_query_trail_for_unions_where_fields_dont_overlap() {
+    fn test_skips_query_trail_methods_for_union_fields_that_dont_overlap() {
         let schema = r#"
             union Entity = User | Company
 
             type User {
               country: Country!
+              id: Int!
+              avatar(size: Int!): String!
             }
 
             type Company {
               country: OtherCountry!
+              id: Int!
+              avatar(size: Int): String!
+              name: String!
             }
 
             type Country {
@@ -837,10 +1406,72 @@ mod test {
         let ast_data = AstData::new_from_doc(&doc).unwrap();
         let context_type = crate::default_error_type();
         let error_type = crate::default_context_type();
-        let mut out = CodeGenPass::new(&context_type, &error_type, ast_data);
+        let lints = LintConfig::default();
+        let mut out = CodeGenPass::new(&context_type, &error_type, &lints, ast_data);
+
+        let tokens = out.gen_query_trails(&doc);
+        assert!(out.errors.is_empty());
+
+        let file = syn::parse2::<syn::File>(tokens).unwrap();
+        let methods = trait_methods(&file, "QueryTrailEntityExtensions");
+        assert_eq!(
+            vec![
+                "id",
+                "id_selections",
+                "name",
+                "name_selections",
+                "selected_fields"
+            ],
+            methods
+        );
 
-        out.gen_query_trails(&doc);
+        // The conflicting fields are still available after downcasting
+        let methods = trait_methods(&file, "QueryTrailCompanyExtensions");
+        assert_eq!(
+            vec![
+                "avatar",
+                "avatar_selections",
+                "country",
+                "country_selections",
+                "id",
+                "id_selections",
+                "name",
+                "name_selections",
+                "selected_fields",
+            ],
+            methods
+        );
+    }
 
-        assert_eq!(1, out.errors.len());
+    fn trait_methods(file: &syn::File, trait_name: &str) -> Vec<String> {
+        let query_trails = file
+            .items
+            .iter()
+            .find_map(|item| match item {
+                syn::Item::Mod(item_mod) if item_mod.ident == "query_trails" => {
+                    Some(&item_mod.content.as_ref().unwrap().1)
+                }
+                _ => None,
+            })
+            .unwrap();
+
+        let mut methods = query_trails
+            .iter()
+            .find_map(|item| match item {
+                syn::Item::Trait(item_trait) if item_trait.ident == trait_name => Some(
+                    item_trait
+                        .items
+                        .iter()
+                        .filter_map(|item| match item {
+                            syn::TraitItem::Method(method) => Some(method.sig.ident.to_string()),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+                _ => None,
+            })
+            .unwrap();
+        methods.sort();
+        methods
     }
 }
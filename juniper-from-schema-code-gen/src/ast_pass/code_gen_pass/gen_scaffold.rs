@@ -0,0 +1,398 @@
+use super::{
+    fields_trait_name, node_resolver_method, node_resolver_method_name,
+    reference_resolver_trait_name, resolvers_trait_name, CodeGenPass, Field,
+};
+use crate::ast_pass::{error::Error, schema_visitor::visit_document};
+use graphql_parser::schema::Document;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use syn::{ext::IdentExt, Ident};
+
+/// A stub implementation of one of the generated `*Fields`, `*Resolvers`, `*ReferenceResolver`,
+/// or `NodeResolver` traits.
+#[derive(Debug)]
+pub struct ScaffoldImpl {
+    trait_name: Ident,
+    self_ty: Ident,
+    is_async: bool,
+    methods: Vec<ScaffoldMethod>,
+}
+
+#[derive(Debug)]
+struct ScaffoldMethod {
+    name: Ident,
+    tokens: TokenStream,
+}
+
+impl<'doc> CodeGenPass<'doc> {
+    pub fn gen_scaffold(
+        mut self,
+        doc: &'doc Document<'doc, &'doc str>,
+    ) -> Result<Vec<ScaffoldImpl>, BTreeSet<Error>> {
+        self.validate_doc(doc);
+        self.check_for_errors()?;
+
+        visit_document(&mut self, doc);
+
+        self.check_for_errors()?;
+
+        let mut impls = Vec::new();
+
+        for object in &self.objects {
+            let (trait_name, fields) = if object.generate_struct {
+                let fields = object
+                    .fields
+                    .iter()
+                    .filter(|field| {
+                        !field.args.is_empty() && !field.is_batched() && !field.is_node_lookup()
+                    })
+                    .collect::<Vec<_>>();
+                (resolvers_trait_name(&object.name), fields)
+            } else {
+                let fields = object
+                    .fields
+                    .iter()
+                    .filter(|field| !field.is_batched() && !field.is_node_lookup())
+                    .collect::<Vec<_>>();
+                (fields_trait_name(&object.name), fields)
+            };
+
+            if !fields.is_empty() {
+                let methods = fields
+                    .iter()
+                    .map(|field| ScaffoldMethod {
+                        name: field.trait_field_name(),
+                        tokens: field
+                            .to_tokens_for_trait_impl(todo_body())
+                            .into_token_stream(),
+                    })
+                    .collect();
+
+                impls.push(ScaffoldImpl {
+                    trait_name,
+                    self_ty: object.name.clone(),
+                    is_async: any_async(&fields),
+                    methods,
+                });
+            }
+
+            if let Some(reference_resolver) = &object.reference_resolver {
+                impls.push(ScaffoldImpl {
+                    trait_name: reference_resolver_trait_name(&object.name),
+                    self_ty: object.name.clone(),
+                    is_async: true,
+                    methods: vec![ScaffoldMethod {
+                        name: format_ident!("resolve_reference"),
+                        tokens: reference_resolver.method(Some(todo_body())),
+                    }],
+                });
+            }
+
+            // `NodeResolver` is implemented by the type with the `node` and `nodes` fields
+            if let Some(node_lookup) = object
+                .fields
+                .iter()
+                .find_map(|field| field.node_lookup.as_ref())
+            {
+                let methods = node_lookup
+                    .implementors
+                    .iter()
+                    .map(|implementor| ScaffoldMethod {
+                        name: node_resolver_method_name(implementor),
+                        tokens: node_resolver_method(
+                            implementor,
+                            self.context_type,
+                            self.error_type,
+                            Some(todo_body()),
+                        ),
+                    })
+                    .collect();
+
+                impls.push(ScaffoldImpl {
+                    trait_name: format_ident!("NodeResolver"),
+                    self_ty: object.name.clone(),
+                    is_async: true,
+                    methods,
+                });
+            }
+        }
+
+        if let Some(subscription) = &self.subscription {
+            let fields = subscription.fields.iter().collect::<Vec<_>>();
+
+            let methods = fields
+                .iter()
+                .map(|field| ScaffoldMethod {
+                    name: field.trait_field_name(),
+                    tokens: field
+                        .to_tokens_for_subscription_trait_impl(todo_body())
+                        .into_token_stream(),
+                })
+                .collect();
+
+            impls.push(ScaffoldImpl {
+                trait_name: fields_trait_name(&subscription.name),
+                self_ty: subscription.name.clone(),
+                is_async: any_async(&fields),
+                methods,
+            });
+        }
+
+        Ok(impls)
+    }
+}
+
+/// Render the stub implementations as a formatted Rust file.
+///
+/// If `existing_code` is given only the methods missing from it are rendered. Traits that aren't
+/// implemented yet get a complete `impl` block. Traits that are already implemented, but lack some
+/// methods, only get those methods, after a comment naming the `impl` block to add them to. That
+/// part of the output isn't valid on its own and has to be merged into the existing code.
+pub fn render_scaffold(impls: Vec<ScaffoldImpl>, existing_code: Option<&syn::File>) -> String {
+    let existing_methods = existing_code.map(implemented_trait_methods);
+
+    let mut new_impls = TokenStream::new();
+    let mut missing_methods = Vec::new();
+
+    for scaffold_impl in impls {
+        let ScaffoldImpl {
+            trait_name,
+            self_ty,
+            is_async,
+            methods,
+        } = scaffold_impl;
+
+        let implemented = existing_methods
+            .as_ref()
+            .and_then(|existing| existing.get(&trait_name.unraw().to_string()));
+
+        let methods = match implemented {
+            Some(implemented) => methods
+                .into_iter()
+                .filter(|method| !implemented.contains(&method.name.unraw().to_string()))
+                .collect(),
+            None => methods,
+        };
+
+        if methods.is_empty() {
+            continue;
+        }
+
+        let methods = methods.into_iter().map(|method| method.tokens);
+
+        if implemented.is_some() {
+            missing_methods.push(render_missing_methods(&trait_name, &self_ty, methods));
+            continue;
+        }
+
+        let async_trait_attr = if is_async {
+            Some(quote! { #[juniper_from_schema::juniper::async_trait] })
+        } else {
+            None
+        };
+
+        new_impls.extend(quote! {
+            #async_trait_attr
+            impl #trait_name for #self_ty {
+                #(#methods)*
+            }
+        });
+    }
+
+    let file = syn::parse2(new_impls).expect("generated invalid scaffold");
+    let mut code = prettyplease::unparse(&file);
+
+    for methods in missing_methods {
+        if !code.is_empty() {
+            code.push('\n');
+        }
+        code.push_str(&methods);
+    }
+
+    code
+}
+
+// The methods formatted as they would be inside the `impl` block, without the block itself
+fn render_missing_methods(
+    trait_name: &Ident,
+    self_ty: &Ident,
+    methods: impl Iterator<Item = TokenStream>,
+) -> String {
+    let file = syn::parse2(quote! {
+        impl #trait_name for #self_ty {
+            #(#methods)*
+        }
+    })
+    .expect("generated invalid scaffold");
+    let formatted = prettyplease::unparse(&file);
+
+    let mut code = format!(
+        "// Add these methods to the existing `impl {} for {}` block\n",
+        trait_name, self_ty
+    );
+    let lines = formatted.lines().collect::<Vec<_>>();
+    for line in &lines[1..lines.len() - 1] {
+        code.push_str(line.strip_prefix("    ").unwrap_or(line));
+        code.push('\n');
+    }
+    code
+}
+
+fn implemented_trait_methods(file: &syn::File) -> HashMap<String, HashSet<String>> {
+    fn visit_items(items: &[syn::Item], acc: &mut HashMap<String, HashSet<String>>) {
+        for item in items {
+            match item {
+                syn::Item::Impl(item_impl) => {
+                    let trait_name = match &item_impl.trait_ {
+                        Some((_, path, _)) => match path.segments.last() {
+                            Some(segment) => segment.ident.unraw().to_string(),
+                            None => continue,
+                        },
+                        None => continue,
+                    };
+
+                    let methods = acc.entry(trait_name).or_default();
+
+                    for impl_item in &item_impl.items {
+                        if let syn::ImplItem::Method(method) = impl_item {
+                            methods.insert(method.sig.ident.unraw().to_string());
+                        }
+                    }
+                }
+                syn::Item::Mod(item_mod) => {
+                    if let Some((_, items)) = &item_mod.content {
+                        visit_items(items, acc);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut acc = HashMap::new();
+    visit_items(&file.items, &mut acc);
+    acc
+}
+
+fn any_async(fields: &[&Field<'_>]) -> bool {
+    fields.iter().any(|field| field.directives.r#async.value)
+}
+
+fn todo_body() -> TokenStream {
+    quote! { todo!() }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::CodeGen;
+
+    const SCHEMA: &str = r#"
+        schema {
+          query: Query
+          subscription: Subscription
+        }
+
+        type Query {
+          user(id: ID!): User @juniper(async: true, ownership: "owned")
+          ping: Boolean!
+        }
+
+        type Subscription {
+          users: User! @juniper(ownership: "owned")
+        }
+
+        type User {
+          id: ID!
+        }
+    "#;
+
+    #[test]
+    fn test_scaffolds_every_fields_trait() {
+        let code = CodeGen::build_from_schema_literal(SCHEMA.to_string())
+            .finish()
+            .generate_scaffold()
+            .unwrap();
+
+        let file = syn::parse_file(&code).unwrap();
+        assert_eq!(3, file.items.len());
+
+        assert!(code.contains(
+            "#[juniper_from_schema::juniper::async_trait]\nimpl QueryFields for Query {"
+        ));
+        assert!(code.contains("async fn field_user<'s, 'r, 'a>("));
+        assert!(code.contains("fn field_ping<'s, 'r, 'a>("));
+        assert!(code.contains("impl SubscriptionFields for Subscription {"));
+        assert!(code.contains("impl UserFields for User {"));
+        assert!(code.contains("todo!()"));
+    }
+
+    #[test]
+    fn test_scaffolds_only_missing_methods() {
+        let existing = r#"
+            #[async_trait]
+            impl QueryFields for Query {
+                async fn field_user<'s, 'r, 'a>(&'s self) {}
+            }
+
+            mod user {
+                impl UserFields for User {
+                    fn field_id(&self) {}
+                }
+            }
+        "#;
+
+        let code = CodeGen::build_from_schema_literal(SCHEMA.to_string())
+            .finish()
+            .generate_missing_scaffold(existing)
+            .unwrap();
+
+        assert!(!code.contains("fn field_user<"));
+        assert!(code.contains("impl SubscriptionFields for Subscription {"));
+        assert!(!code.contains("UserFields"));
+
+        // `QueryFields` is already implemented, so only the missing method is included, marked
+        // as a fragment to merge into the existing `impl` block
+        assert!(!code.contains("impl QueryFields for Query {"));
+        assert!(code.contains(
+            "// Add these methods to the existing `impl QueryFields for Query` block\nfn field_ping<'s, 'r, 'a>("
+        ));
+        assert!(code.trim_end().ends_with("todo!()\n}"), "{}", code);
+    }
+
+    #[test]
+    fn test_scaffolds_node_and_reference_resolvers() {
+        let schema = r#"
+            schema {
+              query: Query
+            }
+
+            type Query {
+              node(id: ID!): Node @juniper(async: true)
+            }
+
+            interface Node @juniper(node_interface: true) {
+              id: ID! @juniper(ownership: "owned")
+            }
+
+            type User implements Node @key(fields: "id") {
+              id: ID! @juniper(ownership: "owned")
+            }
+        "#;
+
+        let code = CodeGen::build_from_schema_literal(schema.to_string())
+            .finish()
+            .generate_scaffold()
+            .unwrap();
+
+        assert!(code.contains(
+            "#[juniper_from_schema::juniper::async_trait]\nimpl NodeResolver for Query {"
+        ));
+        assert!(code.contains("async fn resolve_user<'s, 'r, 'a>("));
+        assert!(code.contains(
+            "#[juniper_from_schema::juniper::async_trait]\nimpl UserReferenceResolver for User {"
+        ));
+        assert!(code.contains("async fn resolve_reference<'r, 'a>("));
+        assert!(!code.contains("QueryFields"));
+    }
+}
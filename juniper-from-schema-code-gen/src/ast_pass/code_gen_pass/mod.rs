@@ -1,24 +1,35 @@
 mod gen_query_trails;
+mod gen_scaffold;
+
+pub use gen_scaffold::render_scaffold;
 
 use super::{
-    directive_parsing::*, error::Error, schema_visitor::*, type_name, validations::*, AstData,
-    DateTimeScalarDefinition, EmitError, ErrorKind, NullableType, TypeKind,
+    directive_parsing::*,
+    error::Error,
+    federation,
+    lints::{LintConfig, LintPass},
+    schema_visitor::*,
+    type_name,
+    validations::*,
+    AstData, DateTimeScalarDefinition, EmitError, ErrorKind, NullableType, TypeKind,
 };
 use graphql_parser::{schema, schema::Value, Pos};
-use heck::{CamelCase, SnakeCase};
-use proc_macro2::{Span, TokenStream};
+use heck::{CamelCase, MixedCase, SnakeCase};
+use proc_macro2::TokenStream;
 use quote::{format_ident, quote, ToTokens};
 use std::{
     collections::{BTreeMap, BTreeSet, HashSet},
     convert::TryFrom,
 };
-use syn::{parse_quote, Ident, LitStr, Token};
+use syn::{ext::IdentExt, parse_quote, Ident, Token};
 
 #[derive(Debug)]
 pub struct CodeGenPass<'doc> {
     error_type: &'doc syn::Type,
     context_type: &'doc syn::Type,
+    lints: &'doc LintConfig,
     errors: BTreeSet<Error>,
+    warnings: BTreeSet<Error>,
     ast_data: AstData<'doc>,
     scalars: Vec<Scalar<'doc>>,
     objects: Vec<Object<'doc>>,
@@ -28,19 +39,24 @@ pub struct CodeGenPass<'doc> {
     enums: Vec<Enum<'doc>>,
     input_objects: Vec<InputObject<'doc>>,
     schema_type: Option<SchemaType>,
+    connections: BTreeMap<&'doc str, TokenStream>,
+    federation_sdl: Option<String>,
 }
 
 impl<'doc> CodeGenPass<'doc> {
     pub fn new(
         error_type: &'doc syn::Type,
         context_type: &'doc syn::Type,
+        lints: &'doc LintConfig,
         ast_data: AstData<'doc>,
     ) -> Self {
         Self {
             error_type,
             context_type,
+            lints,
             ast_data,
             errors: BTreeSet::new(),
+            warnings: BTreeSet::new(),
             scalars: Vec::new(),
             objects: Vec::new(),
             subscription: None,
@@ -49,18 +65,28 @@ impl<'doc> CodeGenPass<'doc> {
             enums: Vec::new(),
             input_objects: Vec::new(),
             schema_type: None,
+            connections: BTreeMap::new(),
+            federation_sdl: None,
         }
     }
 
+    /// Generate the code along with any warnings.
     pub fn gen_juniper_code(
         mut self,
         doc: &'doc schema::Document<'doc, &'doc str>,
-    ) -> Result<TokenStream, BTreeSet<Error>> {
+    ) -> Result<(TokenStream, BTreeSet<Error>), BTreeSet<Error>> {
         self.validate_doc(doc);
         self.check_for_errors()?;
 
         let query_trail_tokens = self.gen_query_trails(doc);
+
+        // Schemas with entities are Apollo Federation subgraphs
+        if !self.ast_data.entities().is_empty() {
+            self.federation_sdl = Some(federation::subgraph_sdl(doc));
+        }
+
         visit_document(&mut self, doc);
+        self.add_entity_union();
 
         self.check_for_errors()?;
 
@@ -73,13 +99,20 @@ impl<'doc> CodeGenPass<'doc> {
             enums,
             input_objects,
             schema_type,
+            connections,
 
+            warnings,
+
+            federation_sdl: _,
             error_type: _,
             context_type: _,
+            lints: _,
             errors: _,
             ast_data: _,
         } = self;
 
+        let connection_conversions = connections.values();
+
         let mut tokens = quote! {
             #(#scalars)*
             #(#objects)*
@@ -89,6 +122,7 @@ impl<'doc> CodeGenPass<'doc> {
             #(#enums)*
             #(#input_objects)*
             #schema_type
+            #(#connection_conversions)*
         };
 
         // eprintln!("\n");
@@ -97,7 +131,7 @@ impl<'doc> CodeGenPass<'doc> {
 
         tokens.extend(query_trail_tokens);
 
-        Ok(tokens)
+        Ok((tokens, warnings))
     }
 
     fn validate_doc(&mut self, doc: &'doc schema::Document<'doc, &'doc str>) {
@@ -114,6 +148,11 @@ impl<'doc> CodeGenPass<'doc> {
         {
             self.errors.insert(error);
         }
+
+        let mut lint_pass = LintPass::new(self.lints, doc, self.ast_data.mutation_type_name());
+        visit_document(&mut lint_pass, doc);
+        self.errors.extend(lint_pass.errors);
+        self.warnings.extend(lint_pass.warnings);
     }
 
     fn check_for_errors(&self) -> Result<(), BTreeSet<Error>> {
@@ -185,8 +224,11 @@ impl<'doc> SchemaVisitor<'doc> for CodeGenPass<'doc> {
         &mut self,
         node: &'doc schema::DirectiveDefinition<'doc, &'doc str>,
     ) {
-        if node.name == "juniper" {
-            self.validate_juniper_directive_definition(node)
+        match node.name {
+            "juniper" => self.validate_juniper_directive_definition(node),
+            "cost" => self.validate_cost_directive_definition(node),
+            "connection" => self.validate_connection_directive_definition(node),
+            _ => {}
         }
     }
 
@@ -245,13 +287,38 @@ impl<'doc> SchemaVisitor<'doc> for CodeGenPass<'doc> {
             fields,
         } = node;
 
-        let () = self.parse_directives(node);
+        let ObjectTypeDirectives {
+            generate_struct,
+            cost,
+            keys,
+            extends,
+        } = self.parse_directives(node);
+
+        if !keys.is_empty() {
+            self.validate_entity_keys(node, &keys);
+        }
+
+        if let Some(cost) = cost {
+            if !cost.multipliers.is_empty() {
+                self.emit_error(
+                    *position,
+                    ErrorKind::InvalidCostDirective(
+                        "`multipliers` is only supported on fields".to_string(),
+                        Some("Use `@cost(multipliers: ...)` on the fields instead".to_string()),
+                    ),
+                );
+            }
+        }
 
         if self.ast_data.is_subscription_type(name) {
             if !implements_interfaces.is_empty() {
                 self.emit_error(*position, ErrorKind::SubscriptionsCannotImplementInterfaces);
             }
 
+            if generate_struct.value {
+                self.emit_error(*position, ErrorKind::GenerateStructOnSubscription);
+            }
+
             let fields = fields
                 .iter()
                 .map(|field| self.graphql_field_to_rust_field(field, FieldLocation::Subscription))
@@ -264,22 +331,72 @@ impl<'doc> SchemaVisitor<'doc> for CodeGenPass<'doc> {
                 fields,
             });
         } else {
-            let fields = fields
+            let mut rust_fields = fields
                 .iter()
                 .map(|field| self.graphql_field_to_rust_field(field, FieldLocation::Object))
-                .collect();
+                .collect::<Vec<_>>();
+
+            if self.ast_data.query_type_name() == Some(*name) {
+                self.add_node_lookups(fields, &mut rust_fields);
+            }
+
+            self.validate_federation_fields(node, extends, &rust_fields);
+
+            for (field, rust_field) in fields.iter().zip(&rust_fields) {
+                if rust_field.is_batched()
+                    && self.is_interface_field(implements_interfaces, field.name)
+                {
+                    self.emit_error(
+                        field.position,
+                        ErrorKind::InvalidBatchField(
+                            "Fields declared by interfaces can't be batched".to_string(),
+                            Some(format!(
+                                "`{}` is resolved through the `{}Fields` trait by the interface",
+                                field.name, name
+                            )),
+                        ),
+                    );
+                }
+            }
 
             let implements_interfaces = implements_interfaces
                 .iter()
                 .map(|name| format_ident!("{}", name))
                 .collect();
 
+            let federation = if self.ast_data.query_type_name() == Some(*name) {
+                self.federation_sdl.clone().map(|sdl| Federation {
+                    sdl,
+                    entities: self
+                        .ast_data
+                        .entities()
+                        .iter()
+                        .map(|name| format_ident!("{}", name))
+                        .collect(),
+                })
+            } else {
+                None
+            };
+
+            let reference_resolver = if keys.is_empty() {
+                None
+            } else {
+                Some(ReferenceResolver {
+                    entity: format_ident!("{}", name),
+                    context_type: self.context_type,
+                    error_type: self.error_type,
+                })
+            };
+
             self.objects.push(Object {
                 name: format_ident!("{}", name),
                 description: description.as_ref(),
                 context_type: self.context_type,
-                fields,
+                fields: rust_fields,
                 implements_interfaces,
+                generate_struct: generate_struct.value,
+                federation,
+                reference_resolver,
             });
         }
     }
@@ -293,7 +410,13 @@ impl<'doc> SchemaVisitor<'doc> for CodeGenPass<'doc> {
             directives: _,
         } = node;
 
-        let () = self.parse_directives(node);
+        let NodeInterface {
+            value: node_interface,
+        } = self.parse_directives(node);
+
+        if node_interface {
+            self.validate_node_interface(node);
+        }
 
         let implementors = self
             .ast_data
@@ -317,6 +440,8 @@ impl<'doc> SchemaVisitor<'doc> for CodeGenPass<'doc> {
             fields,
             implementors,
             context_type: self.context_type,
+            error_type: self.error_type,
+            node_interface,
         });
     }
 
@@ -433,10 +558,12 @@ impl<'doc> SchemaVisitor<'doc> for CodeGenPass<'doc> {
 
                 let ty = self.graphql_type_to_rust_type(value_type, false, *position);
 
+                let graphql_name = name;
                 let name = format_ident!("{}", name.to_snake_case());
 
                 InputObjectField {
                     name,
+                    graphql_name,
                     ty,
                     description: description.as_ref(),
                 }
@@ -511,6 +638,7 @@ impl<'doc> CodeGenPass<'doc> {
         let field_directives = self.parse_directives(field);
 
         self.validate_directive_for_field(&field_directives, field_location, *position);
+        self.validate_cost_multipliers(&field_directives, arguments, *position);
 
         let args = arguments
             .iter()
@@ -547,16 +675,21 @@ impl<'doc> CodeGenPass<'doc> {
             })
             .collect();
 
-        let return_type = self.graphql_type_to_rust_type(
-            field_type,
-            field_directives.ownership.is_as_ref(),
-            *position,
-        );
+        // Connections are always owned since they are built from the `Connection` returned by
+        // the resolver
+        let as_ref = field_directives.ownership.is_as_ref() && !field_directives.connection;
+        let return_type = self.graphql_type_to_rust_type(field_type, as_ref, *position);
 
-        if field_directives.ownership == Ownership::AsRef && !return_type.supports_as_ref() {
+        if as_ref && !return_type.supports_as_ref() {
             self.emit_error(*position, ErrorKind::AsRefOwnershipForNamedType);
         }
 
+        let connection_node = if field_directives.connection {
+            self.connection_node_type(type_name(field_type), *position)
+        } else {
+            None
+        };
+
         Field {
             description: description.as_ref(),
             name: format_ident!("r#{}", name.to_snake_case()),
@@ -564,10 +697,50 @@ impl<'doc> CodeGenPass<'doc> {
             error_type: self.error_type,
             args,
             return_type,
+            connection_node,
+            node_lookup: None,
             directives: field_directives,
         }
     }
 
+    // The type of the items of a connection type added by `expand_connections`. Also generates
+    // the conversion from `juniper_from_schema::Connection`.
+    fn connection_node_type(&mut self, connection: &'doc str, pos: Pos) -> Option<Type> {
+        let find_field = |type_name: &str, field_name: &str| {
+            self.ast_data
+                .output_fields(type_name)?
+                .iter()
+                .find(|field| field.name == field_name)
+                .copied()
+        };
+
+        let edges = find_field(connection, "edges")?;
+        let edge = type_name(&edges.field_type);
+        let node = find_field(edge, "node")?;
+        let page_info = find_field(connection, "pageInfo")?;
+
+        let node_type = self.graphql_type_to_rust_type(&node.field_type, false, pos);
+
+        let connection_ident = format_ident!("{}", connection);
+        let edge = format_ident!("{}", edge);
+        let page_info = format_ident!("{}", type_name(&page_info.field_type));
+
+        self.connections.insert(
+            connection,
+            quote! {
+                impl std::convert::From<juniper_from_schema::Connection<#node_type>>
+                    for #connection_ident
+                {
+                    fn from(connection: juniper_from_schema::Connection<#node_type>) -> Self {
+                        connection.convert(#edge::new, #page_info::new, #connection_ident::new)
+                    }
+                }
+            },
+        );
+
+        Some(node_type)
+    }
+
     fn graphql_type_to_rust_type(
         &mut self,
         graphql_type: &schema::Type<'doc, &'doc str>,
@@ -791,6 +964,8 @@ impl<'doc> CodeGenPass<'doc> {
 
         let mut field_location_present = false;
         let mut scalar_location_present = false;
+        let mut object_location_present = false;
+        let mut interface_location_present = false;
 
         for location in directive.locations.iter() {
             match location {
@@ -800,6 +975,12 @@ impl<'doc> CodeGenPass<'doc> {
                 DirectiveLocation::Scalar => {
                     scalar_location_present = true;
                 }
+                DirectiveLocation::Object => {
+                    object_location_present = true;
+                }
+                DirectiveLocation::Interface => {
+                    interface_location_present = true;
+                }
                 other => self.emit_error(
                     directive.position,
                     ErrorKind::InvalidJuniperDirective(
@@ -807,7 +988,10 @@ impl<'doc> CodeGenPass<'doc> {
                             "Invalid location for @juniper directive: `{}`",
                             other.as_str()
                         ),
-                        Some("Location must be `FIELD_DEFINITION | SCALAR`".to_string()),
+                        Some(
+                            "Location must be `FIELD_DEFINITION | SCALAR | OBJECT | INTERFACE`"
+                                .to_string(),
+                        ),
                     ),
                 ),
             }
@@ -819,7 +1003,10 @@ impl<'doc> CodeGenPass<'doc> {
                 ErrorKind::InvalidJuniperDirective(
                     "Missing `FIELD_DEFINITION` directive location for @juniper directive"
                         .to_string(),
-                    Some("Location must be `FIELD_DEFINITION | SCALAR`".to_string()),
+                    Some(
+                        "Location must be `FIELD_DEFINITION | SCALAR | OBJECT | INTERFACE`"
+                            .to_string(),
+                    ),
                 ),
             )
         }
@@ -829,7 +1016,36 @@ impl<'doc> CodeGenPass<'doc> {
                 directive.position,
                 ErrorKind::InvalidJuniperDirective(
                     "Missing `SCALAR` directive location for @juniper directive".to_string(),
-                    Some("Location must be `FIELD_DEFINITION | SCALAR`".to_string()),
+                    Some(
+                        "Location must be `FIELD_DEFINITION | SCALAR | OBJECT | INTERFACE`"
+                            .to_string(),
+                    ),
+                ),
+            )
+        }
+
+        if !object_location_present {
+            self.emit_error(
+                directive.position,
+                ErrorKind::InvalidJuniperDirective(
+                    "Missing `OBJECT` directive location for @juniper directive".to_string(),
+                    Some(
+                        "Location must be `FIELD_DEFINITION | SCALAR | OBJECT | INTERFACE`"
+                            .to_string(),
+                    ),
+                ),
+            )
+        }
+
+        if !interface_location_present {
+            self.emit_error(
+                directive.position,
+                ErrorKind::InvalidJuniperDirective(
+                    "Missing `INTERFACE` directive location for @juniper directive".to_string(),
+                    Some(
+                        "Location must be `FIELD_DEFINITION | SCALAR | OBJECT | INTERFACE`"
+                            .to_string(),
+                    ),
                 ),
             )
         }
@@ -900,6 +1116,9 @@ impl<'doc> CodeGenPass<'doc> {
         let mut async_present = false;
         let mut stream_item_infallible_present = false;
         let mut stream_type_present = false;
+        let mut generate_struct_present = false;
+        let mut batch_present = false;
+        let mut node_interface_present = false;
 
         for arg in directive.arguments.iter() {
             match arg.name {
@@ -939,13 +1158,31 @@ impl<'doc> CodeGenPass<'doc> {
                     no_directives(self, arg, name);
                     default_value(self, arg, Value::Null, name);
                 }
+                name @ "generate_struct" => {
+                    generate_struct_present = true;
+                    of_type(self, arg, GraphqlType::NamedType("Boolean"), name);
+                    no_directives(self, arg, name);
+                    default_value(self, arg, Value::Boolean(false), name);
+                }
+                name @ "batch" => {
+                    batch_present = true;
+                    of_type(self, arg, GraphqlType::NamedType("Boolean"), name);
+                    no_directives(self, arg, name);
+                    default_value(self, arg, Value::Boolean(false), name);
+                }
+                name @ "node_interface" => {
+                    node_interface_present = true;
+                    of_type(self, arg, GraphqlType::NamedType("Boolean"), name);
+                    no_directives(self, arg, name);
+                    default_value(self, arg, Value::Boolean(false), name);
+                }
                 name => {
                     self.emit_error(
                         arg.position,
                         ErrorKind::InvalidJuniperDirective(
                             format!("Invalid argument for @juniper directive: `{}`", name),
                             Some(
-                                "Supported arguments are `ownership`, `infallible`, `with_time_zone`, `async`, `stream_item_infallible`, and `stream_type`".to_string()
+                                "Supported arguments are `ownership`, `infallible`, `with_time_zone`, `async`, `stream_item_infallible`, `stream_type`, `generate_struct`, `batch`, and `node_interface`".to_string()
                             ),
                         ),
                     )
@@ -1009,118 +1246,691 @@ impl<'doc> CodeGenPass<'doc> {
                 ),
             )
         }
+
+        if !generate_struct_present {
+            self.emit_error(
+                directive.position,
+                ErrorKind::InvalidJuniperDirective(
+                    "Missing argument `generate_struct`".to_string(),
+                    None,
+                ),
+            )
+        }
+
+        if !batch_present {
+            self.emit_error(
+                directive.position,
+                ErrorKind::InvalidJuniperDirective("Missing argument `batch`".to_string(), None),
+            )
+        }
+
+        if !node_interface_present {
+            self.emit_error(
+                directive.position,
+                ErrorKind::InvalidJuniperDirective(
+                    "Missing argument `node_interface`".to_string(),
+                    None,
+                ),
+            )
+        }
     }
 
-    fn validate_directive_for_field(
+    fn validate_connection_directive_definition(
         &mut self,
-        directives: &FieldDirectives,
-        field_location: FieldLocation,
-        pos: Pos,
+        directive: &'doc schema::DirectiveDefinition<'doc, &'doc str>,
     ) {
-        match field_location {
-            FieldLocation::Object | FieldLocation::Interface => {
-                if directives.stream_type.is_some() {
-                    self.emit_error(pos, ErrorKind::StreamTypeNotSupportedHere);
-                }
+        use schema::DirectiveLocation;
 
-                if directives.stream_item_infallible.is_some() {
-                    self.emit_error(pos, ErrorKind::StreamItemInfallibleNotSupportedHere);
-                }
-            }
-            FieldLocation::Subscription => {
-                match &directives.ownership {
-                    Ownership::Borrowed | Ownership::AsRef => {
-                        self.emit_error(pos, ErrorKind::SubscriptionFieldMustBeOwned);
-                    }
-                    Ownership::Owned => {}
-                }
+        assert_eq!(directive.name, "connection");
 
-                if let Some(ty) = &directives.stream_type {
-                    if let Err(err) = syn::parse_str::<syn::Type>(&ty.value) {
-                        self.emit_error(pos, ErrorKind::InvalidStreamReturnType(err.to_string()));
-                    }
-                }
-            }
+        let valid = directive.arguments.is_empty()
+            && directive.locations == [DirectiveLocation::FieldDefinition];
+
+        if !valid {
+            self.emit_error(
+                directive.position,
+                ErrorKind::InvalidConnectionField(
+                    "Invalid definition of the @connection directive".to_string(),
+                    Some(format!("Use `{}`", crate::CONNECTION_DIRECTIVE_DEFINITION)),
+                ),
+            );
         }
     }
-}
 
-#[derive(Debug, Clone)]
-enum Either<A, B> {
-    A(A),
-    B(B),
-}
+    fn validate_cost_directive_definition(
+        &mut self,
+        directive: &'doc schema::DirectiveDefinition<'doc, &'doc str>,
+    ) {
+        use schema::{DirectiveLocation, Type as GraphqlType};
 
-impl<A, B> ToTokens for Either<A, B>
-where
-    A: ToTokens,
-    B: ToTokens,
-{
-    fn to_tokens(&self, tokens: &mut TokenStream) {
-        match self {
-            Either::A(a) => a.to_tokens(tokens),
-            Either::B(b) => b.to_tokens(tokens),
-        }
-    }
-}
+        assert_eq!(directive.name, "cost");
 
-#[derive(Debug, Clone)]
-enum Type {
-    Scalar(Either<syn::Type, Ident>),
-    Enum(Ident),
-    Union(Ident),
-    Interface(Ident),
-    Object(Ident),
-    Ref(Box<Type>),
-    List(Box<Type>),
-    Nullable(Box<Type>),
-}
+        let location_note = || Some("Location must be `FIELD_DEFINITION | OBJECT`".to_string());
 
-impl Type {
-    fn is_nullable(&self) -> bool {
-        matches!(self, Type::Nullable(_))
-    }
+        for location in directive.locations.iter() {
+            match location {
+                DirectiveLocation::FieldDefinition | DirectiveLocation::Object => {}
+                other => self.emit_error(
+                    directive.position,
+                    ErrorKind::InvalidCostDirective(
+                        format!("Invalid location for @cost directive: `{}`", other.as_str()),
+                        location_note(),
+                    ),
+                ),
+            }
+        }
 
-    fn supports_as_ref(&self) -> bool {
-        match self {
-            Type::Scalar(_) => false,
-            Type::Enum(_) => false,
-            Type::Union(_) => false,
-            Type::Interface(_) => false,
-            Type::Object(_) => false,
-            Type::Ref(_) => false,
-            Type::List(_) => true,
-            Type::Nullable(_) => true,
+        for required in &[
+            DirectiveLocation::FieldDefinition,
+            DirectiveLocation::Object,
+        ] {
+            if !directive.locations.contains(required) {
+                self.emit_error(
+                    directive.position,
+                    ErrorKind::InvalidCostDirective(
+                        format!(
+                            "Missing `{}` directive location for @cost directive",
+                            required.as_str()
+                        ),
+                        location_note(),
+                    ),
+                )
+            }
         }
-    }
 
-    fn remove_one_layer_of_nullability_by_value(self) -> Box<Type> {
-        match self {
-            Type::Nullable(inner) => inner,
-            other => Box::new(other),
+        let expected_args = [
+            ("complexity", GraphqlType::NamedType("Int")),
+            (
+                "multipliers",
+                GraphqlType::ListType(Box::new(GraphqlType::NonNullType(Box::new(
+                    GraphqlType::NamedType("String"),
+                )))),
+            ),
+        ];
+
+        for arg in directive.arguments.iter() {
+            let expected_type = match expected_args.iter().find(|(name, _)| *name == arg.name) {
+                Some((_, ty)) => ty,
+                None => {
+                    self.emit_error(
+                        arg.position,
+                        ErrorKind::InvalidCostDirective(
+                            format!("Invalid argument for @cost directive: `{}`", arg.name),
+                            Some(
+                                "Supported arguments are `complexity` and `multipliers`"
+                                    .to_string(),
+                            ),
+                        ),
+                    );
+                    continue;
+                }
+            };
+
+            if &arg.value_type != expected_type {
+                self.emit_error(
+                    arg.position,
+                    ErrorKind::InvalidCostDirective(
+                        format!("`{}` argument must have type `{}`", arg.name, expected_type),
+                        Some(format!("Got `{}`", arg.value_type)),
+                    ),
+                )
+            }
+
+            if let Some(default) = &arg.default_value {
+                self.emit_error(
+                    arg.position,
+                    ErrorKind::InvalidCostDirective(
+                        format!("`{}` argument must not have a default value", arg.name),
+                        Some(format!("Got `{}`", default)),
+                    ),
+                )
+            }
+
+            for dir in arg.directives.iter() {
+                self.emit_error(
+                    dir.position,
+                    ErrorKind::InvalidCostDirective(
+                        format!("`{}` argument doesn't support directives", arg.name),
+                        None,
+                    ),
+                )
+            }
         }
-    }
 
-    fn remove_one_layer_of_nullability(&self) -> &Type {
-        match self {
-            Type::Nullable(inner) => inner,
-            other => other,
+        for (name, _) in expected_args.iter() {
+            if !directive.arguments.iter().any(|arg| arg.name == *name) {
+                self.emit_error(
+                    directive.position,
+                    ErrorKind::InvalidCostDirective(format!("Missing argument `{}`", name), None),
+                )
+            }
         }
     }
 
-    fn kind(&self) -> TypeKind {
-        match self {
-            Type::Scalar(_) => TypeKind::Scalar,
-            Type::Enum(_) => TypeKind::Scalar,
-            Type::Union(_) => TypeKind::Type,
-            Type::Object(_) => TypeKind::Type,
-            Type::Interface { .. } => TypeKind::Type,
-            Type::Ref(inner) => inner.kind(),
+    fn validate_cost_multipliers(
+        &mut self,
+        directives: &FieldDirectives,
+        arguments: &'doc [schema::InputValue<'doc, &'doc str>],
+        pos: Pos,
+    ) {
+        let cost = match &directives.cost {
+            Some(cost) => cost,
+            None => return,
+        };
+
+        for multiplier in &cost.multipliers {
+            let is_int_argument = arguments
+                .iter()
+                .any(|arg| arg.name == multiplier && is_int_type(&arg.value_type));
+
+            if !is_int_argument {
+                self.emit_error(
+                    pos,
+                    ErrorKind::InvalidCostDirective(
+                        format!(
+                            "Cost multiplier `{}` isn't an `Int` argument of the field",
+                            multiplier
+                        ),
+                        Some("Multipliers must name arguments of type `Int` or `Int!`".to_string()),
+                    ),
+                );
+            }
+        }
+    }
+
+    fn is_interface_field(&self, interfaces: &[&'doc str], field_name: &str) -> bool {
+        interfaces.iter().any(|interface| {
+            self.ast_data
+                .output_fields(interface)
+                .into_iter()
+                .flatten()
+                .any(|field| field.name == field_name)
+        })
+    }
+
+    fn validate_batch_field(
+        &mut self,
+        directives: &FieldDirectives,
+        field_location: &FieldLocation,
+        pos: Pos,
+    ) {
+        let mut invalid = |message: &str, note: &str| {
+            self.emit_error(
+                pos,
+                ErrorKind::InvalidBatchField(message.to_string(), Some(note.to_string())),
+            );
+        };
+
+        match field_location {
+            FieldLocation::Object => {}
+            FieldLocation::Interface => invalid(
+                "Fields of interfaces can't be batched",
+                "Use `@juniper(batch: true)` on the fields of the object types instead",
+            ),
+            FieldLocation::Subscription => invalid(
+                "Fields of subscriptions can't be batched",
+                "Subscription fields are only resolved once per subscription",
+            ),
+        }
+
+        if !directives.r#async.value {
+            invalid(
+                "Batched fields must be async",
+                "Add `async: true` to the `@juniper` directive",
+            );
+        }
+
+        if directives.ownership != Ownership::Owned {
+            invalid(
+                "Batched fields must be owned",
+                "Add `ownership: \"owned\"` to the `@juniper` directive",
+            );
+        }
+
+        if directives.infallible.value {
+            invalid(
+                "Batched fields can't be infallible",
+                "Loading a batch can fail, so remove `infallible: true`",
+            );
+        }
+    }
+
+    fn validate_node_interface(&mut self, interface: &'doc schema::InterfaceType<'doc, &'doc str>) {
+        let existing_node_interface = self.ast_data.node_interface();
+
+        let mut invalid = |message: String, note: &str| {
+            self.emit_error(
+                interface.position,
+                ErrorKind::InvalidNodeInterface(message, Some(note.to_string())),
+            );
+        };
+
+        if let Some(node_interface) = existing_node_interface {
+            if node_interface != interface.name {
+                invalid(
+                    "Only one interface can have `node_interface: true`".to_string(),
+                    &format!("`{}` is already the node interface", node_interface),
+                );
+            }
+        }
+
+        let has_id_field = interface.fields.iter().any(|field| {
+            field.name == "id"
+                && field.arguments.is_empty()
+                && field.field_type.to_string() == "ID!"
+        });
+
+        if !has_id_field {
+            invalid(
+                format!(
+                    "The node interface `{}` must have an `id: ID!` field",
+                    interface.name
+                ),
+                "The `id` field returns the global id of the node",
+            );
+        }
+    }
+
+    fn validate_entity_keys(
+        &mut self,
+        object: &'doc schema::ObjectType<'doc, &'doc str>,
+        keys: &[String],
+    ) {
+        let name = object.name;
+
+        if self.ast_data.query_type_name() == Some(name)
+            || self.ast_data.mutation_type_name() == Some(name)
+            || self.ast_data.is_subscription_type(name)
+        {
+            self.emit_error(
+                object.position,
+                ErrorKind::InvalidFederationDirective(
+                    format!("The root type `{}` can't be an entity", name),
+                    Some("Entities are resolved through `_entities` on the query type".to_string()),
+                ),
+            );
+            return;
+        }
+
+        for key in keys {
+            let key_fields = field_set_top_level_fields(key);
+
+            if key_fields.is_empty() {
+                self.emit_error(
+                    object.position,
+                    ErrorKind::InvalidFederationDirective(
+                        format!("The `@key` of `{}` doesn't select any fields", name),
+                        None,
+                    ),
+                );
+            }
+
+            for key_field in key_fields {
+                if !object.fields.iter().any(|field| field.name == key_field) {
+                    self.emit_error(
+                        object.position,
+                        ErrorKind::InvalidFederationDirective(
+                            format!("`{}` doesn't have the key field `{}`", name, key_field),
+                            Some(format!("The key is `@key(fields: {:?})`", key)),
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn validate_federation_fields(
+        &mut self,
+        object: &'doc schema::ObjectType<'doc, &'doc str>,
+        extends: bool,
+        rust_fields: &[Field<'doc>],
+    ) {
+        let is_external = |name: &str| {
+            object
+                .fields
+                .iter()
+                .zip(rust_fields)
+                .any(|(field, rust_field)| field.name == name && rust_field.directives.external)
+        };
+
+        let mut errors = Vec::new();
+
+        for (field, rust_field) in object.fields.iter().zip(rust_fields) {
+            let FieldDirectives {
+                external,
+                requires,
+                provides,
+                ..
+            } = &rust_field.directives;
+
+            // External fields are owned by the subgraph that defines the type
+            if *external && !extends {
+                errors.push((
+                    field.position,
+                    format!(
+                        "`{}.{}` is `@external` but `{}` doesn't have `@extends`",
+                        object.name, field.name, object.name
+                    ),
+                    Some("Only types defined by other subgraphs have external fields".to_string()),
+                ));
+            }
+
+            if let Some(requires) = requires {
+                for required in field_set_top_level_fields(requires) {
+                    if !is_external(required) {
+                        errors.push((
+                            field.position,
+                            format!(
+                                "`@requires` of `{}.{}` selects `{}`, which isn't an `@external` field",
+                                object.name, field.name, required
+                            ),
+                            None,
+                        ));
+                    }
+                }
+            }
+
+            if let Some(provides) = provides {
+                let return_type = type_name(&field.field_type);
+                let return_type_fields = self.ast_data.output_fields(return_type);
+
+                for provided in field_set_top_level_fields(provides) {
+                    let exists = return_type_fields
+                        .into_iter()
+                        .flatten()
+                        .any(|field| field.name == provided);
+
+                    if !exists {
+                        errors.push((
+                            field.position,
+                            format!(
+                                "`@provides` of `{}.{}` selects `{}`, which isn't a field of `{}`",
+                                object.name, field.name, provided, return_type
+                            ),
+                            None,
+                        ));
+                    }
+                }
+            }
+        }
+
+        for (pos, message, note) in errors {
+            self.emit_error(pos, ErrorKind::InvalidFederationDirective(message, note));
+        }
+    }
+
+    // The `_Entity` union of Apollo Federation, which `Query._entities` returns
+    fn add_entity_union(&mut self) {
+        let variants = self
+            .ast_data
+            .entities()
+            .to_vec()
+            .into_iter()
+            .map(|name| {
+                let graphql_type: schema::Type<'doc, &'doc str> = schema::Type::NamedType(name);
+                let type_inside = self
+                    .graphql_type_to_rust_type(&graphql_type, false, Pos::default())
+                    .remove_one_layer_of_nullability_by_value();
+                UnionVariant {
+                    rust_name: format_ident!("{}", name),
+                    type_inside,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if variants.is_empty() {
+            return;
+        }
+
+        self.unions.push(Union {
+            name: format_ident!("_Entity"),
+            variants,
+            description: None,
+            context_type: self.context_type,
+        });
+    }
+
+    // `Query.node` and `Query.nodes` are resolved through the generated `NodeResolver` trait
+    fn add_node_lookups(
+        &mut self,
+        fields: &'doc [schema::Field<'doc, &'doc str>],
+        rust_fields: &mut [Field<'doc>],
+    ) {
+        let interface = match self.ast_data.node_interface() {
+            Some(interface) => interface,
+            None => return,
+        };
+
+        let implementors = self
+            .ast_data
+            .get_implementors_of_interface(interface)
+            .cloned()
+            .unwrap_or_else(Vec::new);
+
+        for (field, rust_field) in fields.iter().zip(rust_fields) {
+            let (expected, many) = match field.name {
+                "node" => (format!("node(id: ID!): {}", interface), false),
+                "nodes" => (format!("nodes(ids: [ID!]!): [{}]!", interface), true),
+                _ => continue,
+            };
+
+            let arguments = field
+                .arguments
+                .iter()
+                .map(|arg| format!("{}: {}", arg.name, arg.value_type))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let signature = format!("{}({}): {}", field.name, arguments, field.field_type);
+
+            let mut invalid = |message: String, note: String| {
+                self.emit_error(
+                    field.position,
+                    ErrorKind::InvalidNodeInterface(message, Some(note)),
+                );
+            };
+
+            if signature != expected {
+                invalid(
+                    format!("Node lookup fields must be `{}`", expected),
+                    format!("Got `{}`", signature),
+                );
+                continue;
+            }
+
+            let directives = &rust_field.directives;
+
+            if !directives.r#async.value {
+                invalid(
+                    "Node lookup fields must be async".to_string(),
+                    "Add `async: true` to the `@juniper` directive".to_string(),
+                );
+            }
+
+            if directives.infallible.value {
+                invalid(
+                    "Node lookup fields can't be infallible".to_string(),
+                    "Decoding the global id can fail, so remove `infallible: true`".to_string(),
+                );
+            }
+
+            if directives.batch.value {
+                invalid(
+                    "Node lookup fields can't be batched".to_string(),
+                    "Nodes are loaded through the `NodeResolver` trait".to_string(),
+                );
+            }
+
+            rust_field.node_lookup = Some(NodeLookup {
+                interface: format_ident!("{}", interface),
+                implementors: implementors
+                    .iter()
+                    .map(|name| format_ident!("{}", name))
+                    .collect(),
+                many,
+            });
+        }
+    }
+
+    fn validate_connection_field(
+        &mut self,
+        directives: &FieldDirectives,
+        field_location: &FieldLocation,
+        pos: Pos,
+    ) {
+        let mut invalid = |message: &str, note: &str| {
+            self.emit_error(
+                pos,
+                ErrorKind::InvalidConnectionField(message.to_string(), Some(note.to_string())),
+            );
+        };
+
+        match field_location {
+            FieldLocation::Object => {}
+            FieldLocation::Interface => invalid(
+                "Fields of interfaces can't be connections",
+                "Use `@connection` on the fields of the object types instead",
+            ),
+            FieldLocation::Subscription => invalid(
+                "Fields of subscriptions can't be connections",
+                "Subscriptions return streams which can't be paginated",
+            ),
+        }
+
+        if directives.batch.value {
+            invalid(
+                "Connection fields can't be batched",
+                "Remove `batch: true` from the `@juniper` directive",
+            );
+        }
+    }
+
+    fn validate_directive_for_field(
+        &mut self,
+        directives: &FieldDirectives,
+        field_location: FieldLocation,
+        pos: Pos,
+    ) {
+        if directives.batch.value {
+            self.validate_batch_field(directives, &field_location, pos);
+        }
+
+        if directives.connection {
+            self.validate_connection_field(directives, &field_location, pos);
+        }
+
+        match field_location {
+            FieldLocation::Object | FieldLocation::Interface => {
+                if directives.stream_type.is_some() {
+                    self.emit_error(pos, ErrorKind::StreamTypeNotSupportedHere);
+                }
+
+                if directives.stream_item_infallible.is_some() {
+                    self.emit_error(pos, ErrorKind::StreamItemInfallibleNotSupportedHere);
+                }
+            }
+            FieldLocation::Subscription => {
+                match &directives.ownership {
+                    Ownership::Borrowed | Ownership::AsRef => {
+                        self.emit_error(pos, ErrorKind::SubscriptionFieldMustBeOwned);
+                    }
+                    Ownership::Owned => {}
+                }
+
+                if let Some(ty) = &directives.stream_type {
+                    if let Err(err) = syn::parse_str::<syn::Type>(&ty.value) {
+                        self.emit_error(pos, ErrorKind::InvalidStreamReturnType(err.to_string()));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Either<A, B> {
+    A(A),
+    B(B),
+}
+
+impl<A, B> ToTokens for Either<A, B>
+where
+    A: ToTokens,
+    B: ToTokens,
+{
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Either::A(a) => a.to_tokens(tokens),
+            Either::B(b) => b.to_tokens(tokens),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Type {
+    Scalar(Either<syn::Type, Ident>),
+    Enum(Ident),
+    Union(Ident),
+    Interface(Ident),
+    Object(Ident),
+    Ref(Box<Type>),
+    List(Box<Type>),
+    Nullable(Box<Type>),
+}
+
+impl Type {
+    fn is_nullable(&self) -> bool {
+        matches!(self, Type::Nullable(_))
+    }
+
+    fn supports_as_ref(&self) -> bool {
+        match self {
+            Type::Scalar(_) => false,
+            Type::Enum(_) => false,
+            Type::Union(_) => false,
+            Type::Interface(_) => false,
+            Type::Object(_) => false,
+            Type::Ref(_) => false,
+            Type::List(_) => true,
+            Type::Nullable(_) => true,
+        }
+    }
+
+    fn remove_one_layer_of_nullability_by_value(self) -> Box<Type> {
+        match self {
+            Type::Nullable(inner) => inner,
+            other => Box::new(other),
+        }
+    }
+
+    fn remove_one_layer_of_nullability(&self) -> &Type {
+        match self {
+            Type::Nullable(inner) => inner,
+            other => other,
+        }
+    }
+
+    fn kind(&self) -> TypeKind {
+        match self {
+            Type::Scalar(_) => TypeKind::Scalar,
+            Type::Enum(_) => TypeKind::Scalar,
+            Type::Union(_) => TypeKind::Type,
+            Type::Object(_) => TypeKind::Type,
+            Type::Interface { .. } => TypeKind::Type,
+            Type::Ref(inner) => inner.kind(),
             Type::List(inner) => inner.kind(),
             Type::Nullable(inner) => inner.kind(),
         }
     }
 
+    fn without_refs(&self) -> Type {
+        match self {
+            Type::Ref(inner) => inner.without_refs(),
+            Type::List(inner) => Type::List(Box::new(inner.without_refs())),
+            Type::Nullable(inner) => Type::Nullable(Box::new(inner.without_refs())),
+            other => other.clone(),
+        }
+    }
+
     fn innermost_type(&self) -> &Type {
         match self {
             Type::Scalar(_) => self,
@@ -1224,9 +2034,8 @@ impl<'doc> ToTokens for Scalar<'doc> {
             impl<'a, 'b> query_trails::FromLookAheadValue<#name>
                 for &'a juniper_from_schema::juniper::LookAheadValue<'b, juniper_from_schema::juniper::DefaultScalarValue>
             {
-                fn from(self) -> #name {
-                    let s = query_trails::FromLookAheadValue::<String>::from(self);
-                    #name(s)
+                fn from(self) -> Result<#name, juniper_from_schema::QueryTrailError> {
+                    query_trails::FromLookAheadValue::<String>::from(self).map(#name)
                 }
             }
         };
@@ -1242,6 +2051,9 @@ struct Object<'doc> {
     context_type: &'doc syn::Type,
     fields: Vec<Field<'doc>>,
     implements_interfaces: Vec<Ident>,
+    generate_struct: bool,
+    federation: Option<Federation>,
+    reference_resolver: Option<ReferenceResolver<'doc>>,
 }
 
 impl<'doc> ToTokens for Object<'doc> {
@@ -1252,6 +2064,9 @@ impl<'doc> ToTokens for Object<'doc> {
             description,
             fields,
             implements_interfaces,
+            generate_struct,
+            federation,
+            reference_resolver,
         } = self;
 
         let mut graphql_attrs = GraphqlAttr::new_object();
@@ -1277,10 +2092,21 @@ impl<'doc> ToTokens for Object<'doc> {
 
         let fields_for_impl = fields
             .iter()
-            .map(|field| field.to_tokens_for_graphql_object_impl(&trait_name));
+            .map(|field| field.to_tokens_for_graphql_object_impl(name, &trait_name));
+
+        // Batched fields are resolved through their own trait and node lookups through
+        // `NodeResolver`
+        let (batched_fields, fields): (Vec<_>, Vec<_>) = fields
+            .iter()
+            .filter(|field| !field.is_node_lookup())
+            .partition(|field| field.is_batched());
 
         let fields_for_trait = fields.iter().map(|field| field.to_tokens_for_trait());
 
+        let batch_traits = batched_fields
+            .iter()
+            .map(|field| field.to_tokens_for_batch_trait(name));
+
         let async_trait_attr = if fields.iter().any(|f| f.directives.r#async.value) {
             Some(quote! { #[juniper_from_schema::juniper::async_trait] })
         } else {
@@ -1291,15 +2117,134 @@ impl<'doc> ToTokens for Object<'doc> {
             #graphql_attrs
             impl #name {
                 #(#fields_for_impl)*
+                #federation
             }
 
             #async_trait_attr
             pub trait #trait_name {
                 #(#fields_for_trait)*
             }
+
+            #(#batch_traits)*
+            #reference_resolver
         };
 
         tokens.extend(code);
+
+        if *generate_struct {
+            self.gen_struct(tokens);
+        }
+    }
+}
+
+impl<'doc> Object<'doc> {
+    fn gen_struct(&self, tokens: &mut TokenStream) {
+        let Object {
+            name,
+            description,
+            fields,
+            context_type: _,
+            implements_interfaces: _,
+            generate_struct: _,
+            federation: _,
+            reference_resolver: _,
+        } = self;
+
+        let trait_name = fields_trait_name(name);
+        let resolvers_trait_name = resolvers_trait_name(name);
+
+        // Batched fields and node lookups are neither struct fields nor part of the `*Fields`
+        // trait
+        let fields = fields
+            .iter()
+            .filter(|field| !field.is_batched() && !field.is_node_lookup())
+            .collect::<Vec<_>>();
+
+        // Fields that take arguments cannot be backed by a struct field so they are resolved
+        // through a separate trait that users must implement themselves
+        let (fields_with_args, fields_without_args): (Vec<&Field<'doc>>, Vec<_>) = fields
+            .iter()
+            .copied()
+            .partition(|field| !field.args.is_empty());
+
+        let doc_attr = description.map(|description| quote! { #[doc = #description] });
+
+        let struct_fields = fields_without_args.iter().map(|field| {
+            let name = &field.name;
+            let ty = field.return_type.without_refs();
+            let doc_attr = field
+                .description
+                .map(|description| quote! { #[doc = #description] });
+            quote! {
+                #doc_attr
+                pub #name: #ty
+            }
+        });
+
+        let constructor_args = fields_without_args.iter().map(|field| {
+            let name = &field.name;
+            let ty = field.return_type.without_refs();
+            quote! { #name: #ty }
+        });
+
+        let constructor_field_names = fields_without_args.iter().map(|field| &field.name);
+
+        let fields_for_impl = fields.iter().map(|field| {
+            if field.args.is_empty() {
+                field.to_tokens_for_trait_impl(field.struct_field_resolver_body())
+            } else {
+                field.to_tokens_for_trait_impl(field.forward_to_trait_body(&resolvers_trait_name))
+            }
+        });
+
+        let async_trait_attr = |fields: &[&Field<'doc>]| {
+            if fields.iter().any(|f| f.directives.r#async.value) {
+                Some(quote! { #[juniper_from_schema::juniper::async_trait] })
+            } else {
+                None
+            }
+        };
+
+        let fields_async_trait_attr = async_trait_attr(&fields);
+
+        let resolvers_trait = if fields_with_args.is_empty() {
+            None
+        } else {
+            let resolvers_async_trait_attr = async_trait_attr(&fields_with_args);
+            let fields_for_trait = fields_with_args
+                .iter()
+                .map(|field| field.to_tokens_for_trait());
+            Some(quote! {
+                #resolvers_async_trait_attr
+                pub trait #resolvers_trait_name {
+                    #(#fields_for_trait)*
+                }
+            })
+        };
+
+        tokens.extend(quote! {
+            #doc_attr
+            pub struct #name {
+                #(#struct_fields,)*
+            }
+
+            impl #name {
+                #[allow(clippy::too_many_arguments)]
+                pub fn new(#(#constructor_args),*) -> Self {
+                    Self {
+                        #(#constructor_field_names,)*
+                    }
+                }
+            }
+
+            #resolvers_trait
+
+            #fields_async_trait_attr
+            #[allow(unused_variables)]
+            impl #trait_name for #name {
+                #(#fields_for_impl)*
+            }
+        });
     }
 }
 
@@ -1307,6 +2252,56 @@ fn fields_trait_name(name: &Ident) -> Ident {
     format_ident!("{}Fields", name)
 }
 
+fn resolvers_trait_name(name: &Ident) -> Ident {
+    format_ident!("{}Resolvers", name)
+}
+
+fn node_resolver_method_name(implementor: &Ident) -> Ident {
+    format_ident!("resolve_{}", implementor.to_string().to_snake_case())
+}
+
+fn reference_resolver_trait_name(entity: &Ident) -> Ident {
+    format_ident!("{}ReferenceResolver", entity)
+}
+
+// The top level fields of a federation field set, such as `id` and `organization` in
+// `"id organization { id }"`.
+fn field_set_top_level_fields(field_set: &str) -> Vec<&str> {
+    let mut fields = Vec::new();
+    let mut depth = 0;
+    let mut start = None;
+
+    for (idx, c) in field_set.char_indices().chain(Some((field_set.len(), ' '))) {
+        if c.is_alphanumeric() || c == '_' {
+            start.get_or_insert(idx);
+            continue;
+        }
+
+        if let Some(start) = start.take() {
+            if depth == 0 {
+                fields.push(&field_set[start..idx]);
+            }
+        }
+
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    fields
+}
+
+// Whether an argument can be used as a `@cost` multiplier.
+fn is_int_type<'doc>(ty: &schema::Type<'doc, &'doc str>) -> bool {
+    match ty {
+        schema::Type::NamedType(name) => *name == "Int",
+        schema::Type::NonNullType(inner) => matches!(&**inner, schema::Type::NamedType("Int")),
+        schema::Type::ListType(_) => false,
+    }
+}
+
 #[derive(Debug)]
 struct Field<'doc> {
     description: Option<&'doc String>,
@@ -1315,22 +2310,55 @@ struct Field<'doc> {
     context_type: &'doc syn::Type,
     args: Vec<FieldArg<'doc>>,
     return_type: Type,
+    // The item type of fields marked with `@connection`
+    connection_node: Option<Type>,
+    // Set for `Query.node` and `Query.nodes` when the schema has a node interface
+    node_lookup: Option<NodeLookup>,
     directives: FieldDirectives,
 }
 
+#[derive(Debug)]
+struct NodeLookup {
+    interface: Ident,
+    implementors: Vec<Ident>,
+    many: bool,
+}
+
 impl<'doc> Field<'doc> {
     fn to_tokens_for_graphql_object_impl<'a>(
         &'a self,
+        object_name: &'a Ident,
         trait_name: &'a Ident,
     ) -> FieldToTokensGraphqlObject<'a, 'doc> {
         FieldToTokensGraphqlObject {
             field: self,
+            object_name,
             trait_name,
         }
     }
 
+    fn to_tokens_for_batch_trait<'a>(
+        &'a self,
+        object_name: &'a Ident,
+    ) -> FieldToTokensBatchTrait<'a, 'doc> {
+        FieldToTokensBatchTrait {
+            field: self,
+            object_name,
+        }
+    }
+
     fn to_tokens_for_trait<'a>(&'a self) -> FieldToTokensTrait<'a, 'doc> {
-        FieldToTokensTrait { field: self }
+        FieldToTokensTrait {
+            field: self,
+            body: None,
+        }
+    }
+
+    fn to_tokens_for_trait_impl<'a>(&'a self, body: TokenStream) -> FieldToTokensTrait<'a, 'doc> {
+        FieldToTokensTrait {
+            field: self,
+            body: Some(body),
+        }
     }
 
     fn to_tokens_for_interface<'a>(&'a self) -> FieldToTokensInterface<'a, 'doc> {
@@ -1360,13 +2388,48 @@ impl<'doc> Field<'doc> {
     fn to_tokens_for_subscription_trait<'a>(
         &'a self,
     ) -> FieldToTokensForSubscriptionTrait<'a, 'doc> {
-        FieldToTokensForSubscriptionTrait { field: self }
+        FieldToTokensForSubscriptionTrait {
+            field: self,
+            body: None,
+        }
+    }
+
+    fn to_tokens_for_subscription_trait_impl<'a>(
+        &'a self,
+        body: TokenStream,
+    ) -> FieldToTokensForSubscriptionTrait<'a, 'doc> {
+        FieldToTokensForSubscriptionTrait {
+            field: self,
+            body: Some(body),
+        }
     }
 
     fn trait_field_name(&self) -> Ident {
         format_ident!("field_{}", self.name)
     }
 
+    fn is_batched(&self) -> bool {
+        self.directives.batch.value
+    }
+
+    fn is_node_lookup(&self) -> bool {
+        self.node_lookup.is_some()
+    }
+
+    fn batch_trait_name(&self, object_name: &Ident) -> Ident {
+        format_ident!(
+            "{}{}Batch",
+            object_name,
+            self.name.unraw().to_string().to_camel_case()
+        )
+    }
+
+    // The type of the values loaded by batches. Objects whose keys are missing resolve to `null`
+    // if the field is nullable.
+    fn batch_value_type(&self) -> &Type {
+        self.return_type.remove_one_layer_of_nullability()
+    }
+
     fn asyncness(&self) -> Option<Token![async]> {
         if self.directives.r#async.value {
             Some(syn::token::Async::default())
@@ -1386,6 +2449,15 @@ impl<'doc> Field<'doc> {
     fn return_type_not_wrapped_in_result(&self) -> syn::Type {
         let return_type = &self.return_type;
 
+        if let Some(node) = &self.connection_node {
+            let connection = quote! { juniper_from_schema::Connection<#node> };
+            return if return_type.is_nullable() {
+                parse_quote! { std::option::Option<#connection> }
+            } else {
+                parse_quote! { #connection }
+            };
+        }
+
         match &self.directives.ownership {
             Ownership::Owned => {
                 parse_quote! { #return_type }
@@ -1447,10 +2519,59 @@ impl<'doc> Field<'doc> {
         }
     }
 
+    fn struct_field_resolver_body(&self) -> TokenStream {
+        let name = &self.name;
+
+        let value = match &self.directives.ownership {
+            Ownership::Borrowed => quote! { &self.#name },
+            Ownership::Owned => quote! { std::clone::Clone::clone(&self.#name) },
+            Ownership::AsRef => match &self.return_type {
+                Type::List(_) => quote! { self.#name.iter().collect() },
+                _ => quote! { self.#name.as_ref() },
+            },
+        };
+
+        if self.directives.infallible.value {
+            value
+        } else {
+            quote! { Ok(#value) }
+        }
+    }
+
+    fn forward_to_trait_body(&self, trait_name: &Ident) -> TokenStream {
+        let trait_field_name = self.trait_field_name();
+        let arg_names = self.args.iter().map(|arg| &arg.name);
+        let query_trail_arg = self.query_trail_param().map(|_| quote! { trail, });
+        let awaitness = self.awaitness();
+
+        quote! {
+            <Self as #trait_name>::#trait_field_name(
+                self,
+                executor,
+                #query_trail_arg
+                #(#arg_names,)*
+            ) #awaitness
+        }
+    }
+
     fn query_trail_type(&self) -> &Type {
         self.return_type.innermost_type()
     }
 
+    // The walked `QueryTrail` passed to resolvers of fields that return objects, interfaces, or
+    // unions. For subscriptions it is the trail of the stream items.
+    fn query_trail_arg(&self) -> Option<TokenStream> {
+        self.query_trail_param()?;
+
+        let query_trail_type = self.query_trail_type();
+        Some(quote! {
+            &juniper_from_schema::QueryTrail::<
+                #query_trail_type,
+                juniper_from_schema::Walked,
+            >::new(&executor.look_ahead()),
+        })
+    }
+
     fn query_trail_param(&self) -> Option<TokenStream> {
         match self.return_type.kind() {
             TypeKind::Type => {
@@ -1467,6 +2588,7 @@ impl<'doc> Field<'doc> {
 #[derive(Debug)]
 struct FieldToTokensGraphqlObject<'a, 'doc> {
     field: &'a Field<'doc>,
+    object_name: &'a Ident,
     trait_name: &'a Ident,
 }
 
@@ -1480,6 +2602,8 @@ impl<'a, 'doc> ToTokens for FieldToTokensGraphqlObject<'a, 'doc> {
             context_type: _,
             args,
             return_type: _,
+            connection_node: _,
+            node_lookup: _,
             directives,
         } = self.field;
 
@@ -1507,11 +2631,6 @@ impl<'a, 'doc> ToTokens for FieldToTokensGraphqlObject<'a, 'doc> {
             graphql_attrs.push_key_value(format_ident!("description"), description);
         };
 
-        let trait_name = self.trait_name;
-        let trait_field_name = self.field.trait_field_name();
-        let arg_names = args.iter().map(|arg| &arg.name);
-        let return_type = self.field.full_return_type();
-
         let args_for_signature = args
             .iter()
             .map(|arg| arg.to_tokens_for_graphql_object_impl());
@@ -1525,20 +2644,17 @@ impl<'a, 'doc> ToTokens for FieldToTokensGraphqlObject<'a, 'doc> {
             }
         });
 
-        let query_trail_arg = if self.field.query_trail_param().is_some() {
-            let query_trail_type = self.field.query_trail_type();
-            quote! {
-                &juniper_from_schema::QueryTrail::<
-                    #query_trail_type,
-                    juniper_from_schema::Walked,
-                >::new(&executor.look_ahead()),
-            }
+        let (return_type, body) = if self.field.is_batched() {
+            self.batched_resolver()
+        } else if self.field.connection_node.is_some() {
+            self.connection_resolver()
+        } else if let Some(node_lookup) = &self.field.node_lookup {
+            self.node_lookup_resolver(node_lookup)
         } else {
-            quote! {}
+            self.resolver()
         };
 
         let asyncness = self.field.asyncness();
-        let awaitness = self.field.awaitness();
 
         tokens.extend(quote! {
             #graphql_attrs
@@ -1548,12 +2664,264 @@ impl<'a, 'doc> ToTokens for FieldToTokensGraphqlObject<'a, 'doc> {
                 #(#args_for_signature,)*
             ) -> #return_type {
                 #(#rebind_args_with_default_values)*
-                <Self as #trait_name>::#trait_field_name(
-                    self,
-                    executor,
-                    #query_trail_arg
-                    #(#arg_names,)*
-                ) #awaitness
+                #body
+            }
+        });
+    }
+}
+
+impl<'a, 'doc> FieldToTokensGraphqlObject<'a, 'doc> {
+    fn resolver(&self) -> (syn::Type, TokenStream) {
+        let trait_name = self.trait_name;
+        let trait_field_name = self.field.trait_field_name();
+        let arg_names = self.field.args.iter().map(|arg| &arg.name);
+        let query_trail_arg = self.field.query_trail_arg();
+        let awaitness = self.field.awaitness();
+
+        let body = quote! {
+            <Self as #trait_name>::#trait_field_name(
+                self,
+                executor,
+                #query_trail_arg
+                #(#arg_names,)*
+            ) #awaitness
+        };
+
+        (self.field.full_return_type(), body)
+    }
+
+    // Converts the `Connection` returned by the resolver into the generated connection type
+    fn connection_resolver(&self) -> (syn::Type, TokenStream) {
+        let (_, call) = self.resolver();
+        let return_type = &self.field.return_type;
+
+        let unwrap = if self.field.directives.infallible.value {
+            None
+        } else {
+            Some(quote! { let connection = connection?; })
+        };
+
+        let convert = if return_type.is_nullable() {
+            quote! { connection.map(std::convert::From::from) }
+        } else {
+            quote! { std::convert::From::from(connection) }
+        };
+
+        let wrap = if self.field.directives.infallible.value {
+            quote! { #convert }
+        } else {
+            quote! { Ok(#convert) }
+        };
+
+        let body = quote! {
+            let connection = #call;
+            #unwrap
+            #wrap
+        };
+
+        let return_type = maybe_wrap_final_return_type_in_result(
+            parse_quote! { #return_type },
+            self.field.error_type,
+            &self.field.directives,
+        );
+
+        (return_type, body)
+    }
+
+    // Decodes the global ids and loads the nodes through `NodeResolver`. Ids of unknown types
+    // resolve to `null`.
+    fn node_lookup_resolver(&self, node_lookup: &NodeLookup) -> (syn::Type, TokenStream) {
+        let NodeLookup {
+            interface,
+            implementors,
+            many,
+        } = node_lookup;
+
+        let type_names = implementors.iter().map(|name| name.to_string());
+        let methods = implementors.iter().map(node_resolver_method_name);
+
+        let lookup = quote! {
+            {
+                let (type_name, id) = juniper_from_schema::decode_global_id(&id)?;
+                match type_name.as_str() {
+                    #(
+                        #type_names => {
+                            let trail: juniper_from_schema::QueryTrail<
+                                #implementors,
+                                juniper_from_schema::Walked,
+                            > = trail.downcast();
+                            <Self as NodeResolver>::#methods(
+                                self,
+                                executor,
+                                &trail,
+                                juniper_from_schema::GlobalId::new(id),
+                            )
+                            .await
+                            .map_err(juniper_from_schema::juniper::IntoFieldError::into_field_error)?
+                            .map(#interface::from)
+                        }
+                    )*
+                    _ => None,
+                }
+            }
+        };
+
+        let arg = &self.field.args[0].name;
+
+        let (return_type, body) = if *many {
+            (
+                quote! { std::vec::Vec<std::option::Option<#interface>> },
+                quote! {
+                    let mut nodes = std::vec::Vec::with_capacity(#arg.len());
+                    for id in #arg {
+                        nodes.push(#lookup);
+                    }
+                    Ok(nodes)
+                },
+            )
+        } else {
+            (
+                quote! { std::option::Option<#interface> },
+                quote! {
+                    let id = #arg;
+                    Ok(#lookup)
+                },
+            )
+        };
+
+        let body = quote! {
+            let look_ahead = executor.look_ahead();
+            let trail = juniper_from_schema::QueryTrail::<
+                #interface,
+                juniper_from_schema::Walked,
+            >::new(&look_ahead);
+
+            #body
+        };
+
+        let return_type = parse_quote! {
+            std::result::Result<#return_type, juniper_from_schema::juniper::FieldError>
+        };
+
+        (return_type, body)
+    }
+
+    // Joins the batch of the field selected at the same place in the query with the same
+    // arguments, which is loaded once all the objects in it have been polled.
+    fn batched_resolver(&self) -> (syn::Type, TokenStream) {
+        let return_type = &self.field.return_type;
+        let batch_trait_name = self.field.batch_trait_name(self.object_name);
+        let arg_names = self.field.args.iter().map(|arg| &arg.name);
+        let batch_name = format!(
+            "{}.{}",
+            self.object_name,
+            self.field.name.unraw().to_string().to_mixed_case()
+        );
+
+        let (trail, trail_arg) = if self.field.query_trail_param().is_some() {
+            let query_trail_type = self.field.query_trail_type();
+            (
+                quote! {
+                    let trail = juniper_from_schema::QueryTrail::<
+                        #query_trail_type,
+                        juniper_from_schema::Walked,
+                    >::new(&look_ahead);
+                },
+                quote! { &trail, },
+            )
+        } else {
+            (quote! {}, quote! {})
+        };
+
+        let value = if return_type.is_nullable() {
+            quote! { Ok(value) }
+        } else {
+            let missing = format!("No value was loaded for `{}`", batch_name);
+            quote! {
+                value.ok_or_else(|| juniper_from_schema::juniper::FieldError::from(#missing))
+            }
+        };
+
+        let body = quote! {
+            let key = <Self as #batch_trait_name>::batch_key(self);
+            let look_ahead = executor.look_ahead();
+            #trail
+
+            let value = juniper_from_schema::load_batched(
+                juniper_from_schema::BatchContext::batch_loaders(executor.context()),
+                #batch_name,
+                executor.location().index(),
+                juniper_from_schema::look_ahead_arguments(&look_ahead),
+                key,
+                move |keys| async move {
+                    <Self as #batch_trait_name>::load_many(
+                        executor,
+                        #trail_arg
+                        &keys,
+                        #(#arg_names,)*
+                    )
+                    .await
+                    .map_err(juniper_from_schema::juniper::IntoFieldError::into_field_error)
+                },
+            )
+            .await?;
+
+            #value
+        };
+
+        (
+            parse_quote! { juniper_from_schema::juniper::FieldResult<#return_type> },
+            body,
+        )
+    }
+}
+
+#[derive(Debug)]
+struct FieldToTokensBatchTrait<'a, 'doc> {
+    field: &'a Field<'doc>,
+    object_name: &'a Ident,
+}
+
+impl<'a, 'doc> ToTokens for FieldToTokensBatchTrait<'a, 'doc> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Field {
+            description: _,
+            name: _,
+            error_type,
+            context_type,
+            args,
+            return_type: _,
+            connection_node: _,
+            node_lookup: _,
+            directives: _,
+        } = self.field;
+
+        let trait_name = self.field.batch_trait_name(self.object_name);
+        let value_type = self.field.batch_value_type();
+        let args = args.iter().map(|arg| arg.to_tokens_for_trait());
+        let query_trail_param = self.field.query_trail_param();
+
+        tokens.extend(quote! {
+            #[juniper_from_schema::juniper::async_trait]
+            pub trait #trait_name {
+                type Key: std::hash::Hash
+                    + std::cmp::Eq
+                    + std::clone::Clone
+                    + std::marker::Send
+                    + std::marker::Sync
+                    + 'static;
+
+                fn batch_key(&self) -> Self::Key;
+
+                async fn load_many<'r, 'a>(
+                    executor: &juniper_from_schema::juniper::Executor<'r, 'a, #context_type>,
+                    #query_trail_param
+                    keys: &[Self::Key],
+                    #(#args,)*
+                ) -> std::result::Result<
+                    std::collections::HashMap<Self::Key, #value_type>,
+                    #error_type,
+                >;
             }
         });
     }
@@ -1562,6 +2930,7 @@ impl<'a, 'doc> ToTokens for FieldToTokensGraphqlObject<'a, 'doc> {
 #[derive(Debug)]
 struct FieldToTokensTrait<'a, 'doc> {
     field: &'a Field<'doc>,
+    body: Option<TokenStream>,
 }
 
 impl<'a, 'doc> ToTokens for FieldToTokensTrait<'a, 'doc> {
@@ -1573,6 +2942,8 @@ impl<'a, 'doc> ToTokens for FieldToTokensTrait<'a, 'doc> {
             context_type,
             args,
             return_type: _,
+            connection_node: _,
+            node_lookup: _,
             directives: _,
         } = self.field;
 
@@ -1585,13 +2956,18 @@ impl<'a, 'doc> ToTokens for FieldToTokensTrait<'a, 'doc> {
 
         let asyncness = self.field.asyncness();
 
+        let body = match &self.body {
+            Some(body) => quote! { { #body } },
+            None => quote! { ; },
+        };
+
         tokens.extend(quote! {
             #asyncness fn #name<'s, 'r, 'a>(
                 &'s self,
                 executor: &juniper_from_schema::juniper::Executor<'r, 'a, #context_type>,
                 #query_trail_param
                 #(#args,)*
-            ) -> #full_return_type;
+            ) -> #full_return_type #body
         });
     }
 }
@@ -1610,6 +2986,8 @@ impl<'a, 'doc> ToTokens for FieldToTokensInterface<'a, 'doc> {
             context_type,
             args,
             return_type: _,
+            connection_node: _,
+            node_lookup: _,
             directives,
         } = self.field;
 
@@ -1660,6 +3038,8 @@ impl<'a, 'doc> ToTokens for FieldToTokensInterfaceImpl<'a, 'doc> {
                     context_type,
                     args,
                     return_type: _,
+                    connection_node: _,
+                    node_lookup: _,
                     directives: _,
                 },
             trait_name,
@@ -1687,17 +3067,7 @@ impl<'a, 'doc> ToTokens for FieldToTokensInterfaceImpl<'a, 'doc> {
             }
         });
 
-        let query_trail_arg = if self.field.query_trail_param().is_some() {
-            let query_trail_type = self.field.query_trail_type();
-            quote! {
-                &juniper_from_schema::QueryTrail::<
-                    #query_trail_type,
-                    juniper_from_schema::Walked,
-                >::new(&executor.look_ahead()),
-            }
-        } else {
-            quote! {}
-        };
+        let query_trail_arg = self.field.query_trail_arg();
 
         let asyncness = self.field.asyncness();
         let awaitness = self.field.awaitness();
@@ -1741,6 +3111,8 @@ impl<'a, 'doc> ToTokens for FieldToTokensForSubscriptionImpl<'a, 'doc> {
             error_type: _,
             context_type: _,
             return_type: _,
+            connection_node: _,
+            node_lookup: _,
             directives: _,
         } = self.field;
 
@@ -1785,17 +3157,7 @@ impl<'a, 'doc> ToTokens for FieldToTokensForSubscriptionImpl<'a, 'doc> {
             }
         });
 
-        let query_trail_arg = if self.field.query_trail_param().is_some() {
-            let query_trail_type = self.field.query_trail_type();
-            quote! {
-                &juniper_from_schema::QueryTrail::<
-                    #query_trail_type,
-                    juniper_from_schema::Walked,
-                >::new(&executor.look_ahead()),
-            }
-        } else {
-            quote! {}
-        };
+        let query_trail_arg = self.field.query_trail_arg();
 
         let awaitness = self.field.awaitness();
 
@@ -1835,6 +3197,7 @@ impl<'a, 'doc> ToTokens for FieldToTokensForSubscriptionImpl<'a, 'doc> {
 #[derive(Debug)]
 struct FieldToTokensForSubscriptionTrait<'a, 'doc> {
     field: &'a Field<'doc>,
+    body: Option<TokenStream>,
 }
 
 impl<'a, 'doc> ToTokens for FieldToTokensForSubscriptionTrait<'a, 'doc> {
@@ -1846,6 +3209,8 @@ impl<'a, 'doc> ToTokens for FieldToTokensForSubscriptionTrait<'a, 'doc> {
             name: _,
             error_type: _,
             return_type: _,
+            connection_node: _,
+            node_lookup: _,
             directives: _,
         } = self.field;
 
@@ -1855,13 +3220,18 @@ impl<'a, 'doc> ToTokens for FieldToTokensForSubscriptionTrait<'a, 'doc> {
         let asyncness = self.field.asyncness();
         let return_type = self.field.full_stream_return_type();
 
+        let body = match &self.body {
+            Some(body) => quote! { { #body } },
+            None => quote! { ; },
+        };
+
         tokens.extend(quote! {
             #asyncness fn #name<'s, 'r, 'a>(
                 &'s self,
                 executor: &juniper_from_schema::juniper::Executor<'r, 'a, #context_type>,
                 #query_trail_param
                 #(#args,)*
-            ) -> #return_type;
+            ) -> #return_type #body
         });
     }
 }
@@ -2015,6 +3385,8 @@ struct Interface<'doc> {
     fields: Vec<Field<'doc>>,
     implementors: Vec<Ident>,
     context_type: &'doc syn::Type,
+    error_type: &'doc syn::Type,
+    node_interface: bool,
 }
 
 impl<'doc> ToTokens for Interface<'doc> {
@@ -2025,6 +3397,8 @@ impl<'doc> ToTokens for Interface<'doc> {
             trait_name: interface_trait_name,
             implementors,
             context_type,
+            error_type,
+            node_interface,
             fields,
         } = self;
 
@@ -2069,6 +3443,166 @@ impl<'doc> ToTokens for Interface<'doc> {
                 }
             })
         }
+
+        if *node_interface {
+            let type_names = implementors.iter().map(|name| name.to_string());
+            let methods = implementors.iter().map(|implementor| {
+                node_resolver_method(implementor, context_type, error_type, None)
+            });
+
+            tokens.extend(quote! {
+                #(
+                    impl juniper_from_schema::NodeType for #implementors {
+                        const TYPE_NAME: &'static str = #type_names;
+                    }
+                )*
+
+                #[juniper_from_schema::juniper::async_trait]
+                pub trait NodeResolver {
+                    #(#methods)*
+                }
+            });
+        }
+    }
+}
+
+// The `NodeResolver` method that loads `implementor`. Without a body it is the declaration in the
+// trait.
+fn node_resolver_method(
+    implementor: &Ident,
+    context_type: &syn::Type,
+    error_type: &syn::Type,
+    body: Option<TokenStream>,
+) -> TokenStream {
+    let name = node_resolver_method_name(implementor);
+
+    let body = match body {
+        Some(body) => quote! { { #body } },
+        None => quote! { ; },
+    };
+
+    quote! {
+        async fn #name<'s, 'r, 'a>(
+            &'s self,
+            executor: &juniper_from_schema::juniper::Executor<'r, 'a, #context_type>,
+            trail: &juniper_from_schema::QueryTrail<
+                'r,
+                #implementor,
+                juniper_from_schema::Walked,
+            >,
+            id: juniper_from_schema::GlobalId<#implementor>,
+        ) -> std::result::Result<std::option::Option<#implementor>, #error_type> #body
+    }
+}
+
+// The `_service` and `_entities` fields that Apollo Federation adds to the query type
+#[derive(Debug)]
+struct Federation {
+    sdl: String,
+    entities: Vec<Ident>,
+}
+
+impl ToTokens for Federation {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Federation { sdl, entities } = self;
+
+        let type_names = entities.iter().map(|name| name.to_string());
+        let traits = entities.iter().map(reference_resolver_trait_name);
+
+        tokens.extend(quote! {
+            #[graphql(name = "_service")]
+            fn federation_service(&self, executor: &Executor) -> juniper_from_schema::Service {
+                juniper_from_schema::Service::new(#sdl)
+            }
+
+            #[graphql(name = "_entities")]
+            async fn federation_entities(
+                &self,
+                executor: &Executor,
+                representations: std::vec::Vec<juniper_from_schema::Representation>,
+            ) -> std::result::Result<
+                std::vec::Vec<std::option::Option<_Entity>>,
+                juniper_from_schema::juniper::FieldError,
+            > {
+                let look_ahead = executor.look_ahead();
+
+                let mut entities = std::vec::Vec::with_capacity(representations.len());
+                for representation in &representations {
+                    let entity = match representation.typename() {
+                        #(
+                            #type_names => {
+                                let trail = juniper_from_schema::QueryTrail::<
+                                    #entities,
+                                    juniper_from_schema::Walked,
+                                >::new(&look_ahead);
+                                <#entities as #traits>::resolve_reference(
+                                    executor,
+                                    &trail,
+                                    representation,
+                                )
+                                .await
+                                .map_err(juniper_from_schema::juniper::IntoFieldError::into_field_error)?
+                                .map(_Entity::from)
+                            }
+                        )*
+                        typename => {
+                            return Err(juniper_from_schema::juniper::FieldError::from(format!(
+                                "Unknown entity type `{}`",
+                                typename
+                            )));
+                        }
+                    };
+                    entities.push(entity);
+                }
+                Ok(entities)
+            }
+        });
+    }
+}
+
+// The trait through which `_entities` resolves the entities of a type with `@key`
+#[derive(Debug)]
+struct ReferenceResolver<'doc> {
+    entity: Ident,
+    context_type: &'doc syn::Type,
+    error_type: &'doc syn::Type,
+}
+
+impl<'doc> ReferenceResolver<'doc> {
+    // `resolve_reference`. Without a body it is the declaration in the trait.
+    fn method(&self, body: Option<TokenStream>) -> TokenStream {
+        let ReferenceResolver {
+            entity,
+            context_type,
+            error_type,
+        } = self;
+
+        let body = match body {
+            Some(body) => quote! { { #body } },
+            None => quote! { ; },
+        };
+
+        quote! {
+            async fn resolve_reference<'r, 'a>(
+                executor: &juniper_from_schema::juniper::Executor<'r, 'a, #context_type>,
+                trail: &juniper_from_schema::QueryTrail<'r, #entity, juniper_from_schema::Walked>,
+                representation: &juniper_from_schema::Representation,
+            ) -> std::result::Result<std::option::Option<#entity>, #error_type> #body
+        }
+    }
+}
+
+impl<'doc> ToTokens for ReferenceResolver<'doc> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let trait_name = reference_resolver_trait_name(&self.entity);
+        let method = self.method(None);
+
+        tokens.extend(quote! {
+            #[juniper_from_schema::juniper::async_trait]
+            pub trait #trait_name {
+                #method
+            }
+        });
     }
 }
 
@@ -2167,7 +3701,7 @@ impl<'doc> ToTokens for Enum<'doc> {
         let string_to_enum_value_mappings = variants.iter().map(|variant| {
             let graphql_name = variant.graphql_name;
             let variant_name = &variant.name;
-            quote! { &#graphql_name => #name::#variant_name }
+            quote! { #graphql_name => Ok(#name::#variant_name) }
         });
 
         tokens.extend(quote! {
@@ -2190,25 +3724,27 @@ impl<'doc> ToTokens for Enum<'doc> {
             impl<'a, 'b> query_trails::FromLookAheadValue<#name>
                 for &'a juniper_from_schema::juniper::LookAheadValue<'b, juniper_from_schema::juniper::DefaultScalarValue>
             {
-                fn from(self) -> #name {
-                    match self {
-                        juniper_from_schema::juniper::LookAheadValue::Enum(name) => {
-                            match name {
-                                #(#string_to_enum_value_mappings,)*
-                                other => panic!("Invalid enum name: {}", other),
-                            }
-                        },
-                        juniper_from_schema::juniper::LookAheadValue::Null => panic!(
-                            "Failed converting look ahead value. Expected enum type got `null`",
-                        ),
-                        juniper_from_schema::juniper::LookAheadValue::List(_) => panic!(
-                            "Failed converting look ahead value. Expected enum type got `list`",
-                        ),
-                        juniper_from_schema::juniper::LookAheadValue::Object(_) => panic!(
-                            "Failed converting look ahead value. Expected enum type got `object`",
+                fn from(self) -> Result<#name, juniper_from_schema::QueryTrailError> {
+                    // Enum values passed in variables are strings
+                    let name: &str = match self {
+                        juniper_from_schema::juniper::LookAheadValue::Enum(name) => name,
+                        juniper_from_schema::juniper::LookAheadValue::Scalar(
+                            juniper_from_schema::juniper::DefaultScalarValue::String(name),
+                        ) => name,
+                        other => return Err(
+                            juniper_from_schema::QueryTrailError::unexpected_value(
+                                "enum type",
+                                other,
+                            ),
                         ),
-                        juniper_from_schema::juniper::LookAheadValue::Scalar(_) => panic!(
-                            "Failed converting look ahead value. Expected enum type got `scalar`",
+                    };
+
+                    match name {
+                        #(#string_to_enum_value_mappings,)*
+                        other => Err(
+                            juniper_from_schema::QueryTrailError::UnknownEnumValue(
+                                other.to_string(),
+                            ),
                         ),
                     }
                 }
@@ -2285,7 +3821,7 @@ impl<'doc> ToTokens for InputObject<'doc> {
         let temp_field_setters = fields
             .iter()
             .map(|field| {
-                let name = LitStr::new(&field.name.to_string(), Span::call_site());
+                let name = field.graphql_name;
                 let temp_name = format_ident!("{}_temp", field.name);
                 let rust_type = &field.ty;
                 quote! {
@@ -2293,7 +3829,7 @@ impl<'doc> ToTokens for InputObject<'doc> {
                         #temp_name = Some(
                             query_trails::FromLookAheadValue::<#rust_type>::from(
                                 look_ahead_value
-                            )
+                            )?
                         );
                     },
                 }
@@ -2304,9 +3840,20 @@ impl<'doc> ToTokens for InputObject<'doc> {
             .iter()
             .map(|field| {
                 let name = &field.name;
+                let graphql_name = field.graphql_name;
                 let temp_name = format_ident!("{}_temp", &field.name);
-                quote! {
-                    #name: #temp_name.unwrap_or_else(|| panic!("Field `{}` was not set", stringify!(#name))),
+                if field.ty.is_nullable() {
+                    quote! {
+                        #name: #temp_name.unwrap_or(None),
+                    }
+                } else {
+                    quote! {
+                        #name: #temp_name.ok_or_else(|| {
+                            juniper_from_schema::QueryTrailError::MissingInputObjectField(
+                                #graphql_name.to_string(),
+                            )
+                        })?,
+                    }
                 }
             })
             .collect::<Vec<_>>();
@@ -2321,7 +3868,7 @@ impl<'doc> ToTokens for InputObject<'doc> {
             impl<'a, 'b> query_trails::FromLookAheadValue<#name>
                 for &'a juniper_from_schema::juniper::LookAheadValue<'b, juniper_from_schema::juniper::DefaultScalarValue>
             {
-                fn from(self) -> #name {
+                fn from(self) -> Result<#name, juniper_from_schema::QueryTrailError> {
                     match self {
                         juniper_from_schema::juniper::LookAheadValue::Object(pairs) => {
                             #(
@@ -2330,25 +3877,21 @@ impl<'doc> ToTokens for InputObject<'doc> {
                             for (look_ahead_key, look_ahead_value) in pairs {
                                 match *look_ahead_key {
                                     #(#temp_field_setters)*
-                                    other => panic!("Invalid input object key: {}", other),
+                                    other => return Err(
+                                        juniper_from_schema::QueryTrailError::UnknownInputObjectKey(
+                                            other.to_string(),
+                                        ),
+                                    ),
                                 }
                             }
-                            #name {
+                            Ok(#name {
                                 #(#field_setters)*
-                            }
+                            })
                         },
-                        juniper_from_schema::juniper::LookAheadValue::Enum(_) => panic!(
-                            "Failed converting look ahead value. Expected object type got `enum`",
-                        ),
-                        juniper_from_schema::juniper::LookAheadValue::Null => panic!(
-                            "Failed converting look ahead value. Expected object type got `null`",
-                        ),
-                        juniper_from_schema::juniper::LookAheadValue::List(_) => panic!(
-                            "Failed converting look ahead value. Expected object type got `list`",
-                        ),
-                        juniper_from_schema::juniper::LookAheadValue::Scalar(_) => panic!(
-                            "Failed converting look ahead value. Expected object type got `scalar`",
-                        ),
+                        other => Err(juniper_from_schema::QueryTrailError::unexpected_value(
+                            "object type",
+                            other,
+                        )),
                     }
                 }
             }
@@ -2359,6 +3902,7 @@ impl<'doc> ToTokens for InputObject<'doc> {
 #[derive(Debug)]
 struct InputObjectField<'doc> {
     name: Ident,
+    graphql_name: &'doc str,
     ty: Type,
     description: Option<&'doc String>,
 }
@@ -2367,6 +3911,7 @@ impl<'doc> ToTokens for InputObjectField<'doc> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let InputObjectField {
             name,
+            graphql_name: _,
             ty,
             description,
         } = self;
@@ -0,0 +1,636 @@
+use super::{
+    error::Error,
+    schema_visitor::{visit_document, SchemaVisitor},
+    EmitError, ErrorKind,
+};
+use graphql_parser::{parse_schema, query::Value, schema::*, Pos};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+const PAGE_INFO_TYPE_NAME: &str = "PageInfo";
+
+const CONNECTION_ARGUMENTS: &[(&str, &str)] = &[
+    ("first", "Int"),
+    ("after", "String"),
+    ("last", "Int"),
+    ("before", "String"),
+];
+
+// The fields of `PageInfo` in the order the generated conversion from
+// `juniper_from_schema::Connection` passes them to `PageInfo::new`
+const PAGE_INFO_FIELDS: &[PageInfoField] = &[
+    PageInfoField {
+        name: "hasNextPage",
+        type_name: "Boolean",
+        non_null: true,
+        ownership: "owned",
+        description: "When paginating forwards, are there more items?",
+    },
+    PageInfoField {
+        name: "hasPreviousPage",
+        type_name: "Boolean",
+        non_null: true,
+        ownership: "owned",
+        description: "When paginating backwards, are there more items?",
+    },
+    PageInfoField {
+        name: "startCursor",
+        type_name: "String",
+        non_null: false,
+        ownership: "as_ref",
+        description: "When paginating backwards, the cursor to continue.",
+    },
+    PageInfoField {
+        name: "endCursor",
+        type_name: "String",
+        non_null: false,
+        ownership: "as_ref",
+        description: "When paginating forwards, the cursor to continue.",
+    },
+];
+
+struct PageInfoField {
+    name: &'static str,
+    type_name: &'static str,
+    non_null: bool,
+    ownership: &'static str,
+    description: &'static str,
+}
+
+/// The connection types needed by the fields marked with `@connection`.
+///
+/// [`expand`](#method.expand) rewrites every field marked with `@connection` to return a
+/// `{Type}Connection` with the `first`, `after`, `last`, and `before` arguments, and adds the
+/// connection, edge, and `PageInfo` types to the schema.
+///
+/// The parsed document is rewritten rather than the schema text, so errors keep pointing at the
+/// schema as it was written. The names of the added types are owned here since the document only
+/// borrows its names.
+#[derive(Debug, Default)]
+pub struct Connections {
+    nodes: BTreeMap<String, Node>,
+    // `false` if the schema defines its own `PageInfo`
+    generate_page_info: bool,
+}
+
+#[derive(Debug)]
+struct Node {
+    connection: String,
+    edge: String,
+    non_null: bool,
+    // The first `@connection` returning the node, which the added types point to
+    position: Pos,
+}
+
+impl Connections {
+    /// Find the connection types needed by `schema`. Schemas that don't parse have none, so the
+    /// parse error is reported as usual.
+    pub fn new(schema: &str) -> Result<Self, BTreeSet<Error>> {
+        let doc = match parse_schema::<&str>(schema) {
+            Ok(doc) => doc,
+            Err(_) => return Ok(Self::default()),
+        };
+
+        let mut finder = ConnectionFinder::new(&doc);
+        visit_document(&mut finder, &doc);
+
+        if !finder.errors.is_empty() {
+            return Err(finder.errors);
+        }
+
+        let nodes = finder
+            .nodes
+            .into_iter()
+            .map(|(name, (non_null, position))| {
+                let node = Node {
+                    connection: connection_type_name(name),
+                    edge: edge_type_name(name),
+                    non_null,
+                    position,
+                };
+                (name.to_string(), node)
+            })
+            .collect();
+
+        Ok(Self {
+            nodes,
+            generate_page_info: !finder.type_names.contains_key(PAGE_INFO_TYPE_NAME),
+        })
+    }
+
+    /// Rewrite the connection fields of `doc` and add the connection types to it.
+    pub fn expand<'a>(&'a self, mut doc: Document<'a, &'a str>) -> Document<'a, &'a str> {
+        if self.nodes.is_empty() {
+            return doc;
+        }
+
+        for def in &mut doc.definitions {
+            let fields = match def {
+                Definition::TypeDefinition(TypeDefinition::Object(ty)) => &mut ty.fields,
+                Definition::TypeDefinition(TypeDefinition::Interface(ty)) => &mut ty.fields,
+                _ => continue,
+            };
+
+            for field in fields {
+                self.expand_field(field);
+            }
+        }
+
+        for (node_name, node) in &self.nodes {
+            doc.definitions.extend(connection_types(node_name, node));
+        }
+
+        if self.generate_page_info {
+            let position = self.nodes.values().map(|node| node.position).min();
+            doc.definitions
+                .push(page_info_type(position.unwrap_or_default()));
+        }
+
+        doc
+    }
+
+    fn expand_field<'a>(&'a self, field: &mut Field<'a, &'a str>) {
+        let position = match field.directives.iter().find(|d| d.name == "connection") {
+            Some(directive) => directive.position,
+            None => return,
+        };
+
+        let node = match connection_node(&field.field_type)
+            .and_then(|(name, _, nullable)| self.nodes.get(name).map(|node| (node, nullable)))
+        {
+            Some((node, nullable)) => {
+                let connection = Type::NamedType(node.connection.as_str());
+                if nullable {
+                    connection
+                } else {
+                    Type::NonNullType(Box::new(connection))
+                }
+            }
+            None => return,
+        };
+
+        field.field_type = node;
+        field
+            .arguments
+            .extend(CONNECTION_ARGUMENTS.iter().map(|(name, ty)| InputValue {
+                position,
+                description: None,
+                name: *name,
+                value_type: Type::NamedType(*ty),
+                default_value: None,
+                directives: Vec::new(),
+            }));
+    }
+}
+
+// The name of the item type of a connection field, whether the items are non-null, and whether
+// the field itself is nullable
+fn connection_node<'a>(field_type: &Type<'a, &'a str>) -> Option<(&'a str, bool, bool)> {
+    let (item_type, nullable) = match field_type {
+        Type::NonNullType(inner) => match &**inner {
+            Type::ListType(item_type) => (&**item_type, false),
+            _ => return None,
+        },
+        Type::ListType(item_type) => (&**item_type, true),
+        Type::NamedType(_) => return None,
+    };
+
+    match item_type {
+        Type::NamedType(name) => Some((*name, false, nullable)),
+        Type::NonNullType(inner) => match &**inner {
+            Type::NamedType(name) => Some((*name, true, nullable)),
+            _ => None,
+        },
+        Type::ListType(_) => None,
+    }
+}
+
+// Validates the fields marked with `@connection` and collects the item types of the connections
+struct ConnectionFinder<'doc> {
+    type_names: HashMap<&'doc str, &'doc TypeDefinition<'doc, &'doc str>>,
+    // Whether the items are non-null, and the first `@connection` returning them
+    nodes: BTreeMap<&'doc str, (bool, Pos)>,
+    errors: BTreeSet<Error>,
+}
+
+impl<'doc> ConnectionFinder<'doc> {
+    fn new(doc: &'doc Document<'doc, &'doc str>) -> Self {
+        let type_names = doc
+            .definitions
+            .iter()
+            .filter_map(|def| match def {
+                Definition::TypeDefinition(type_def) => {
+                    Some((type_definition_name(type_def), type_def))
+                }
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            type_names,
+            nodes: BTreeMap::new(),
+            errors: BTreeSet::new(),
+        }
+    }
+
+    fn check_fields(&mut self, fields: &'doc [Field<'doc, &'doc str>]) {
+        for field in fields {
+            if let Some(directive) = field.directives.iter().find(|d| d.name == "connection") {
+                self.check_field(field, directive);
+            }
+        }
+    }
+
+    fn check_field(
+        &mut self,
+        field: &'doc Field<'doc, &'doc str>,
+        directive: &'doc Directive<'doc, &'doc str>,
+    ) {
+        if !directive.arguments.is_empty() {
+            self.emit_error(
+                directive.position,
+                error(
+                    "@connection doesn't take any arguments",
+                    "The connection arguments are added to the field",
+                ),
+            );
+        }
+
+        let (node_name, non_null) = match connection_node(&field.field_type) {
+            Some((name, non_null, _)) if self.is_output_type(name) => (name, non_null),
+            _ => {
+                self.emit_error(
+                    field.position,
+                    error(
+                        "Connection fields must return a list of objects, interfaces, or unions",
+                        "For example `users: [User!]! @connection`",
+                    ),
+                );
+                return;
+            }
+        };
+
+        for (name, _) in CONNECTION_ARGUMENTS {
+            if field.arguments.iter().any(|arg| arg.name == *name) {
+                self.emit_error(
+                    field.position,
+                    error(
+                        &format!("Connection fields can't declare the `{}` argument", name),
+                        "`first`, `after`, `last`, and `before` are added by @connection",
+                    ),
+                );
+            }
+        }
+
+        match self.nodes.get(node_name) {
+            Some((existing, _)) => {
+                if *existing != non_null {
+                    let bang = if *existing { "!" } else { "" };
+                    self.emit_error(
+                        field.position,
+                        error(
+                            &format!("Connections of `{}` have different item types", node_name),
+                            &format!("Another connection returns `[{}{}]`", node_name, bang),
+                        ),
+                    );
+                }
+            }
+            None => {
+                self.check_generated_type_names(node_name, field.position);
+                self.nodes.insert(node_name, (non_null, directive.position));
+            }
+        }
+    }
+
+    fn check_generated_type_names(&mut self, node_name: &str, pos: Pos) {
+        for name in &[connection_type_name(node_name), edge_type_name(node_name)] {
+            if self.type_names.contains_key(name.as_str()) {
+                self.emit_error(
+                    pos,
+                    error(
+                        &format!("The type `{}` is already defined", name),
+                        "@connection generates it, so remove or rename the existing type",
+                    ),
+                );
+            }
+        }
+
+        if let Some(page_info) = self.type_names.get(PAGE_INFO_TYPE_NAME) {
+            if !is_standard_page_info(page_info) {
+                self.emit_error(
+                    pos,
+                    error(
+                        &format!("The type `{}` is already defined", PAGE_INFO_TYPE_NAME),
+                        "@connection uses an existing `PageInfo` only if its fields are exactly \
+                         `hasNextPage: Boolean!`, `hasPreviousPage: Boolean!`, \
+                         `startCursor: String`, and `endCursor: String`",
+                    ),
+                );
+            }
+        }
+    }
+
+    fn is_output_type(&self, name: &str) -> bool {
+        matches!(
+            self.type_names.get(name),
+            Some(TypeDefinition::Object(_))
+                | Some(TypeDefinition::Interface(_))
+                | Some(TypeDefinition::Union(_))
+        )
+    }
+}
+
+impl<'doc> EmitError for ConnectionFinder<'doc> {
+    fn emit_error(&mut self, pos: Pos, kind: ErrorKind) {
+        self.errors.emit_error(pos, kind)
+    }
+}
+
+impl<'doc> SchemaVisitor<'doc> for ConnectionFinder<'doc> {
+    fn visit_object_type(&mut self, ty: &'doc ObjectType<'doc, &'doc str>) {
+        self.check_fields(&ty.fields);
+    }
+
+    fn visit_interface_type(&mut self, ty: &'doc InterfaceType<'doc, &'doc str>) {
+        self.check_fields(&ty.fields);
+    }
+}
+
+fn error(message: &str, note: &str) -> ErrorKind {
+    ErrorKind::InvalidConnectionField(message.to_string(), Some(note.to_string()))
+}
+
+fn type_definition_name<'doc>(type_def: &TypeDefinition<'doc, &'doc str>) -> &'doc str {
+    match type_def {
+        TypeDefinition::Scalar(ty) => ty.name,
+        TypeDefinition::Object(ty) => ty.name,
+        TypeDefinition::Interface(ty) => ty.name,
+        TypeDefinition::Union(ty) => ty.name,
+        TypeDefinition::Enum(ty) => ty.name,
+        TypeDefinition::InputObject(ty) => ty.name,
+    }
+}
+
+// An object type with the fields of the generated `PageInfo`, in the same order
+fn is_standard_page_info<'doc>(type_def: &TypeDefinition<'doc, &'doc str>) -> bool {
+    let fields = match type_def {
+        TypeDefinition::Object(ty) => &ty.fields,
+        _ => return false,
+    };
+
+    fields.len() == PAGE_INFO_FIELDS.len()
+        && fields
+            .iter()
+            .zip(PAGE_INFO_FIELDS)
+            .all(|(field, expected)| {
+                field.name == expected.name
+                    && field.arguments.is_empty()
+                    && field.field_type == page_info_field_type(expected)
+            })
+}
+
+fn connection_type_name(node_name: &str) -> String {
+    format!("{}Connection", node_name)
+}
+
+fn edge_type_name(node_name: &str) -> String {
+    format!("{}Edge", node_name)
+}
+
+fn connection_types<'a>(node_name: &'a str, node: &'a Node) -> Vec<Definition<'a, &'a str>> {
+    let non_null = |ty| Type::NonNullType(Box::new(ty));
+    let node_type = if node.non_null {
+        non_null(Type::NamedType(node_name))
+    } else {
+        Type::NamedType(node_name)
+    };
+
+    let connection = object_type(
+        node.position,
+        &node.connection,
+        format!("A connection to a list of `{}` items.", node_name),
+        vec![
+            field(
+                node.position,
+                "edges",
+                "The edges of the connection.",
+                non_null(Type::ListType(Box::new(non_null(Type::NamedType(
+                    node.edge.as_str(),
+                ))))),
+                vec![],
+            ),
+            field(
+                node.position,
+                "pageInfo",
+                "Information to aid in pagination.",
+                non_null(Type::NamedType(PAGE_INFO_TYPE_NAME)),
+                vec![],
+            ),
+        ],
+    );
+
+    let edge = object_type(
+        node.position,
+        &node.edge,
+        format!(
+            "An edge in a connection to a list of `{}` items.",
+            node_name
+        ),
+        vec![
+            field(
+                node.position,
+                "node",
+                "The item at the end of the edge.",
+                node_type,
+                vec![],
+            ),
+            field(
+                node.position,
+                "cursor",
+                "A cursor for use in pagination.",
+                non_null(Type::NamedType("String")),
+                vec![],
+            ),
+        ],
+    );
+
+    vec![connection, edge]
+}
+
+fn page_info_type<'a>(position: Pos) -> Definition<'a, &'a str> {
+    let fields = PAGE_INFO_FIELDS
+        .iter()
+        .map(|page_info_field| {
+            let ownership = juniper_directive(
+                position,
+                "ownership",
+                Value::String(page_info_field.ownership.to_string()),
+            );
+
+            field(
+                position,
+                page_info_field.name,
+                page_info_field.description,
+                page_info_field_type(page_info_field),
+                vec![ownership],
+            )
+        })
+        .collect();
+
+    object_type(
+        position,
+        PAGE_INFO_TYPE_NAME,
+        "Information about pagination in a connection.".to_string(),
+        fields,
+    )
+}
+
+fn page_info_field_type<'a>(page_info_field: &PageInfoField) -> Type<'a, &'a str> {
+    let ty = Type::NamedType(page_info_field.type_name);
+    if page_info_field.non_null {
+        Type::NonNullType(Box::new(ty))
+    } else {
+        ty
+    }
+}
+
+// An object type with `@juniper(generate_struct: true)`
+fn object_type<'a>(
+    position: Pos,
+    name: &'a str,
+    description: String,
+    fields: Vec<Field<'a, &'a str>>,
+) -> Definition<'a, &'a str> {
+    Definition::TypeDefinition(TypeDefinition::Object(ObjectType {
+        position,
+        description: Some(description),
+        name,
+        implements_interfaces: vec![],
+        directives: vec![juniper_directive(
+            position,
+            "generate_struct",
+            Value::Boolean(true),
+        )],
+        fields,
+    }))
+}
+
+fn field<'a>(
+    position: Pos,
+    name: &'a str,
+    description: &str,
+    field_type: Type<'a, &'a str>,
+    directives: Vec<Directive<'a, &'a str>>,
+) -> Field<'a, &'a str> {
+    Field {
+        position,
+        description: Some(description.to_string()),
+        name,
+        arguments: vec![],
+        field_type,
+        directives,
+    }
+}
+
+fn juniper_directive<'a>(
+    position: Pos,
+    argument: &'a str,
+    value: Value<'a, &'a str>,
+) -> Directive<'a, &'a str> {
+    Directive {
+        position,
+        name: "juniper",
+        arguments: vec![(argument, value)],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn expand(schema: &str) -> String {
+        let connections = Connections::new(schema).unwrap();
+        let doc = parse_schema::<&str>(schema).unwrap();
+        connections.expand(doc).to_string()
+    }
+
+    #[test]
+    fn test_expanding_connection_fields() {
+        let schema = r#"
+            type Query {
+              "All the users"
+              users(
+                role: String = "a (b)" # comment (
+              ): [User!]! @connection @juniper(ownership: "owned")
+              posts:
+                [Post]
+                @connection
+            }
+            type User { id: ID! }
+            type Post { id: ID! }
+        "#;
+
+        let connections = Connections::new(schema).unwrap();
+        let doc = connections.expand(parse_schema::<&str>(schema).unwrap());
+
+        let query = match &doc.definitions[0] {
+            Definition::TypeDefinition(TypeDefinition::Object(ty)) => ty,
+            _ => panic!("expected the query type"),
+        };
+        let users = &query.fields[0];
+        let posts = &query.fields[1];
+
+        // The fields keep the positions they have in the schema
+        assert_eq!((3, 15), (users.position.line, users.position.column));
+        assert_eq!((7, 15), (posts.position.line, posts.position.column));
+
+        assert_eq!("UserConnection!", users.field_type.to_string());
+        assert_eq!("PostConnection", posts.field_type.to_string());
+        assert_eq!(
+            vec!["role", "first", "after", "last", "before"],
+            users
+                .arguments
+                .iter()
+                .map(|arg| arg.name)
+                .collect::<Vec<_>>()
+        );
+
+        let expanded = doc.to_string();
+        assert!(expanded.contains("type UserConnection @juniper(generate_struct: true)"));
+        assert!(expanded.contains("edges: [UserEdge!]!"));
+        assert!(expanded.contains("node: User!"));
+        assert!(expanded.contains("node: Post\n"));
+        assert_eq!(1, expanded.matches("type PageInfo").count());
+        assert!(expanded.contains(r#"startCursor: String @juniper(ownership: "as_ref")"#));
+    }
+
+    #[test]
+    fn test_existing_page_info_types() {
+        let schema = r#"
+            type Query { users: [User!]! @connection }
+            type User { id: ID! }
+            type PageInfo @juniper(generate_struct: true) {
+              hasNextPage: Boolean!
+              hasPreviousPage: Boolean!
+              startCursor: String
+              endCursor: String
+            }
+        "#;
+
+        let expanded = expand(schema);
+        assert_eq!(1, expanded.matches("type PageInfo").count());
+        assert!(expanded.contains("pageInfo: PageInfo!"));
+
+        let schema = schema.replace("endCursor: String", "endCursor: String!");
+        let errors = Connections::new(&schema).unwrap_err();
+        assert_eq!(1, errors.len());
+    }
+
+    #[test]
+    fn test_schemas_without_connections_are_unchanged() {
+        let schema = "type Query { users: [User!]! } type User { id: ID! }";
+        assert_eq!(
+            parse_schema::<&str>(schema).unwrap().to_string(),
+            expand(schema)
+        );
+    }
+}
@@ -94,6 +94,81 @@ impl FromDirective for Deprecation {
     }
 }
 
+#[derive(Debug, Default)]
+pub struct Cost {
+    pub complexity: Option<u64>,
+    pub multipliers: Vec<String>,
+}
+
+impl FromDirective for Cost {
+    fn from_directive<'doc>(dir: &'doc Directive<'doc, &'doc str>) -> Result<Self, ErrorKind> {
+        let name = &dir.name;
+        if *name != "cost" {
+            return Err(ErrorKind::UnsupportedDirective(
+                UnsupportedDirectiveKind::Cost(error::Cost::InvalidName(name.to_string())),
+            ));
+        }
+
+        let mut cost = Cost::default();
+
+        for (key, value) in &dir.arguments {
+            match *key {
+                "complexity" => {
+                    let complexity = match value {
+                        Value::Int(n) => n.as_i64().unwrap_or_default(),
+                        other => {
+                            return Err(ErrorKind::UnsupportedDirective(
+                                UnsupportedDirectiveKind::InvalidType {
+                                    expected: ValueType::Int,
+                                    actual: ValueType::from(other),
+                                },
+                            ));
+                        }
+                    };
+
+                    if complexity < 0 {
+                        return Err(ErrorKind::UnsupportedDirective(
+                            UnsupportedDirectiveKind::Cost(error::Cost::NegativeComplexity(
+                                complexity.to_string(),
+                            )),
+                        ));
+                    }
+
+                    cost.complexity = Some(complexity as u64);
+                }
+                "multipliers" => {
+                    cost.multipliers = match value {
+                        // A single value is coerced to a list with one item, as required by the
+                        // spec
+                        Value::List(values) => values
+                            .iter()
+                            .map(|value| value_as_string(value).map(ToString::to_string))
+                            .collect::<Result<_, _>>()?,
+                        other => vec![value_as_string(other)?.to_string()],
+                    };
+                }
+                key => {
+                    return Err(ErrorKind::UnsupportedDirective(
+                        UnsupportedDirectiveKind::Cost(error::Cost::InvalidKey(key.to_string())),
+                    ));
+                }
+            }
+        }
+
+        Ok(cost)
+    }
+}
+
+/// The `@cost` directive among `directives`, if there is a valid one.
+///
+/// Invalid directives are reported when the field or type is visited by `CodeGenPass`.
+pub fn cost_directive<'doc>(directives: &'doc [Directive<'doc, &'doc str>]) -> Option<Cost> {
+    directives
+        .iter()
+        .filter(|dir| dir.name == "cost")
+        .find_map(|dir| Cost::from_directive(dir).ok())
+}
+
 #[derive(Debug)]
 pub struct JuniperDirective<T> {
     pub name: String,
@@ -169,6 +244,7 @@ impl_from_directive_for! { (T1, T2) }
 impl_from_directive_for! { (T1, T2, T3) }
 impl_from_directive_for! { (T1, T2, T3, T4) }
 impl_from_directive_for! { (T1, T2, T3, T4, T5) }
+impl_from_directive_for! { (T1, T2, T3, T4, T5, T6) }
 
 #[derive(Debug)]
 pub struct FieldDirectives {
@@ -178,6 +254,12 @@ pub struct FieldDirectives {
     pub r#async: Async,
     pub stream_type: Option<StreamType>,
     pub stream_item_infallible: Option<StreamItemInfallible>,
+    pub batch: Batch,
+    pub connection: bool,
+    pub cost: Option<Cost>,
+    pub external: bool,
+    pub requires: Option<String>,
+    pub provides: Option<String>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -341,6 +423,78 @@ impl FromDirectiveArguments for StreamItemInfallible {
     }
 }
 
+#[derive(Debug, Default)]
+pub struct Batch {
+    pub value: bool,
+}
+
+impl FromDirectiveArguments for Batch {
+    const KEY: &'static str = "batch";
+
+    fn from_directive_args<'doc>(
+        (key, value): &'doc (&'doc str, Value<'doc, &'doc str>),
+    ) -> Option<Result<Self, ErrorKind>> {
+        if *key != Self::KEY {
+            return None;
+        }
+
+        let directive = (|| {
+            let value = value_as_bool(value)?;
+            Ok(Self { value })
+        })();
+
+        Some(directive)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct GenerateStruct {
+    pub value: bool,
+}
+
+impl FromDirectiveArguments for GenerateStruct {
+    const KEY: &'static str = "generate_struct";
+
+    fn from_directive_args<'doc>(
+        (key, value): &'doc (&'doc str, Value<'doc, &'doc str>),
+    ) -> Option<Result<Self, ErrorKind>> {
+        if *key != Self::KEY {
+            return None;
+        }
+
+        let directive = (|| {
+            let value = value_as_bool(value)?;
+            Ok(Self { value })
+        })();
+
+        Some(directive)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NodeInterface {
+    pub value: bool,
+}
+
+impl FromDirectiveArguments for NodeInterface {
+    const KEY: &'static str = "node_interface";
+
+    fn from_directive_args<'doc>(
+        (key, value): &'doc (&'doc str, Value<'doc, &'doc str>),
+    ) -> Option<Result<Self, ErrorKind>> {
+        if *key != Self::KEY {
+            return None;
+        }
+
+        let directive = (|| {
+            let value = value_as_bool(value)?;
+            Ok(Self { value })
+        })();
+
+        Some(directive)
+    }
+}
+
 #[derive(Debug)]
 pub struct DateTimeScalarArguments {
     pub with_time_zone: bool,
@@ -373,6 +527,33 @@ impl FromDirectiveArguments for DateTimeScalarArguments {
     }
 }
 
+/// The `fields` argument of the Apollo Federation directives `@key`, `@requires` and
+/// `@provides`.
+fn federation_field_set<'doc>(dir: &'doc Directive<'doc, &'doc str>) -> Result<String, ErrorKind> {
+    match dir.arguments.as_slice() {
+        [("fields", Value::String(fields))] => Ok(fields.clone()),
+        _ => Err(ErrorKind::InvalidFederationDirective(
+            format!("`@{}` takes a single `fields` argument", dir.name),
+            Some(format!(
+                "Select the fields with `@{}(fields: \"id\")`",
+                dir.name
+            )),
+        )),
+    }
+}
+
+/// Checks the Apollo Federation directives that take no arguments, `@extends` and `@external`.
+fn federation_flag<'doc>(dir: &'doc Directive<'doc, &'doc str>) -> Result<(), ErrorKind> {
+    if dir.arguments.is_empty() {
+        Ok(())
+    } else {
+        Err(ErrorKind::InvalidFederationDirective(
+            format!("`@{}` doesn't take any arguments", dir.name),
+            None,
+        ))
+    }
+}
+
 fn value_as_string<'doc>(value: &'doc Value<'doc, &'doc str>) -> Result<&'doc str, ErrorKind> {
     match value {
         Value::String(x) => Ok(x),
@@ -413,14 +594,58 @@ impl<'doc> ParseDirective<&'doc Field<'doc, &'doc str>> for CodeGenPass<'doc> {
         let mut r#async = Async::default();
         let mut stream_type = None::<StreamType>;
         let mut stream_item_infallible = None::<StreamItemInfallible>;
+        let mut batch = Batch::default();
+        let mut connection = false;
+        let mut cost = None::<Cost>;
+        let mut external = false;
+        let mut requires = None::<String>;
+        let mut provides = None::<String>;
 
         for dir in &input.directives {
+            match dir.name {
+                "external" => {
+                    match federation_flag(dir) {
+                        Ok(()) => external = true,
+                        Err(err) => self.emit_error(dir.position, err),
+                    }
+                    continue;
+                }
+                "requires" | "provides" => {
+                    match federation_field_set(dir) {
+                        Ok(fields) if dir.name == "requires" => requires = Some(fields),
+                        Ok(fields) => provides = Some(fields),
+                        Err(err) => self.emit_error(dir.position, err),
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+
+            // The arguments of `@connection` are validated when the connection is expanded
+            if dir.name == "connection" {
+                connection = true;
+                continue;
+            }
+
+            if dir.name == "cost" {
+                match Cost::from_directive(dir) {
+                    Ok(x) => {
+                        cost = Some(x);
+                    }
+                    Err(err) => {
+                        self.emit_error(dir.position, err);
+                    }
+                }
+                continue;
+            }
+
             if let Ok(juniper_directive) = JuniperDirective::<(
                 Ownership,
                 Infallible,
                 Async,
                 Option<StreamType>,
                 Option<StreamItemInfallible>,
+                Batch,
             )>::from_directive(dir)
             {
                 ownership = juniper_directive.args.0;
@@ -428,6 +653,7 @@ impl<'doc> ParseDirective<&'doc Field<'doc, &'doc str>> for CodeGenPass<'doc> {
                 r#async = juniper_directive.args.2;
                 stream_type = juniper_directive.args.3;
                 stream_item_infallible = juniper_directive.args.4;
+                batch = juniper_directive.args.5;
                 continue;
             }
 
@@ -451,6 +677,12 @@ impl<'doc> ParseDirective<&'doc Field<'doc, &'doc str>> for CodeGenPass<'doc> {
             r#async,
             stream_type,
             stream_item_infallible,
+            batch,
+            connection,
+            cost,
+            external,
+            requires,
+            provides,
         }
     }
 }
@@ -476,6 +708,74 @@ impl<'doc> ParseDirective<&'doc EnumValue<'doc, &'doc str>> for CodeGenPass<'doc
     }
 }
 
+#[derive(Debug)]
+pub struct ObjectTypeDirectives {
+    pub generate_struct: GenerateStruct,
+    pub cost: Option<Cost>,
+    pub keys: Vec<String>,
+    pub extends: bool,
+}
+
+impl<'doc> ParseDirective<&'doc ObjectType<'doc, &'doc str>> for CodeGenPass<'doc> {
+    type Output = ObjectTypeDirectives;
+
+    fn parse_directives(&mut self, input: &'doc ObjectType<'doc, &'doc str>) -> Self::Output {
+        let mut generate_struct = GenerateStruct::default();
+        let mut cost = None::<Cost>;
+        let mut keys = Vec::new();
+        let mut extends = false;
+
+        for dir in &input.directives {
+            match dir.name {
+                // `@key` is repeatable since an entity can be referenced by different keys
+                "key" => {
+                    match federation_field_set(dir) {
+                        Ok(fields) => keys.push(fields),
+                        Err(err) => self.emit_error(dir.position, err),
+                    }
+                    continue;
+                }
+                "extends" => {
+                    match federation_flag(dir) {
+                        Ok(()) => extends = true,
+                        Err(err) => self.emit_error(dir.position, err),
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+
+            if dir.name == "cost" {
+                match Cost::from_directive(dir) {
+                    Ok(x) => {
+                        cost = Some(x);
+                    }
+                    Err(err) => {
+                        self.emit_error(dir.position, err);
+                    }
+                }
+                continue;
+            }
+
+            match JuniperDirective::<GenerateStruct>::from_directive(dir) {
+                Ok(x) => {
+                    generate_struct = x.args;
+                }
+                Err(err) => {
+                    self.emit_error(dir.position, err);
+                }
+            }
+        }
+
+        ObjectTypeDirectives {
+            generate_struct,
+            cost,
+            keys,
+            extends,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct DateTimeScalarType<'a>(pub &'a ScalarType<'a, &'a str>);
 
@@ -503,6 +803,31 @@ where
     }
 }
 
+// Also parsed by `AstData` since `Query.node` can be declared before the interface
+impl<'doc, T> ParseDirective<&'doc InterfaceType<'doc, &'doc str>> for T
+where
+    T: EmitError,
+{
+    type Output = NodeInterface;
+
+    fn parse_directives(&mut self, input: &'doc InterfaceType<'doc, &'doc str>) -> Self::Output {
+        let mut node_interface = NodeInterface::default();
+
+        for dir in &input.directives {
+            match JuniperDirective::<NodeInterface>::from_directive(dir) {
+                Ok(x) => {
+                    node_interface = x.args;
+                }
+                Err(err) => {
+                    self.emit_error(dir.position, err);
+                }
+            }
+        }
+
+        node_interface
+    }
+}
+
 macro_rules! supports_no_directives {
     ($ty:ty) => {
         impl<'doc> ParseDirective<&'doc $ty> for CodeGenPass<'doc> {
@@ -524,8 +849,6 @@ macro_rules! supports_no_directives {
 
 supports_no_directives!(SchemaDefinition<'doc, &'doc str>);
 supports_no_directives!(ScalarType<'doc, &'doc str>);
-supports_no_directives!(ObjectType<'doc, &'doc str>);
-supports_no_directives!(InterfaceType<'doc, &'doc str>);
 supports_no_directives!(UnionType<'doc, &'doc str>);
 supports_no_directives!(EnumType<'doc, &'doc str>);
 supports_no_directives!(InputObjectType<'doc, &'doc str>);
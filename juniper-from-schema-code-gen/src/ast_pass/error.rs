@@ -1,3 +1,5 @@
+use super::lints::{Lint, LintLevel};
+use crate::diagnostic::{Diagnostic, DiagnosticSeverity, Range};
 use colored::*;
 use graphql_parser::{query::Value, Pos};
 use std::fmt::{self, Write};
@@ -9,10 +11,64 @@ pub struct Error {
 }
 
 impl Error {
+    pub fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+
     pub fn display<'a>(&'a self, raw_schema: &'a str) -> ErrorDisplay<'a> {
+        self.display_in(raw_schema, "schema")
+    }
+
+    /// Display the error pointing into `source`, which is referred to as `source_name`.
+    pub fn display_in<'a>(&'a self, source: &'a str, source_name: &'a str) -> ErrorDisplay<'a> {
         ErrorDisplay {
             error: self,
-            raw_schema,
+            raw_schema: source,
+            source_name,
+            is_warning: false,
+        }
+    }
+
+    /// Like [`display_in`](#method.display_in) but labels the error as a warning.
+    pub fn display_as_warning<'a>(
+        &'a self,
+        source: &'a str,
+        source_name: &'a str,
+    ) -> ErrorDisplay<'a> {
+        ErrorDisplay {
+            is_warning: true,
+            ..self.display_in(source, source_name)
+        }
+    }
+
+    /// The error as a structured diagnostic pointing into `source`, which is referred to as
+    /// `source_name`.
+    pub fn to_diagnostic(
+        &self,
+        severity: DiagnosticSeverity,
+        source: &str,
+        source_name: &str,
+    ) -> Diagnostic {
+        let notes = self
+            .kind
+            .notes()
+            .map(|notes| {
+                notes
+                    .split("\n\n")
+                    .map(|note| note.trim_end().to_string())
+                    .filter(|note| !note.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Diagnostic {
+            severity,
+            code: Some(self.kind.code().to_string()),
+            message: self.kind.description(),
+            notes,
+            file: Some(source_name.to_string()),
+            range: Range::of_name_at(self.pos, source),
+            suggestions: self.kind.suggestions(),
         }
     }
 }
@@ -21,52 +77,91 @@ impl Error {
 pub struct ErrorDisplay<'a> {
     error: &'a Error,
     raw_schema: &'a str,
+    source_name: &'a str,
+    is_warning: bool,
 }
 
 impl<'a> fmt::Display for ErrorDisplay<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let schema_lines = self.raw_schema.lines().collect::<Vec<_>>();
-
-        let number_of_digits_in_line_count = number_of_digits(self.error.pos.line as i32);
-        let indent = 4;
+        let code = self.error.kind.code();
+        let level = if self.is_warning {
+            format!("warning[{}]", code).yellow()
+        } else {
+            format!("error[{}]", code).bright_red()
+        };
 
-        writeln!(
-            f,
-            "{error}: {kind}",
-            error = "error".bright_red(),
-            kind = self.error.kind.description()
-        )?;
-        writeln!(
+        write_diagnostic(
             f,
-            "{indent} --> schema:{line}:{col}",
-            indent = "".indent(number_of_digits_in_line_count - 1),
-            line = self.error.pos.line,
-            col = self.error.pos.column
-        )?;
-        writeln!(f, "{} |", "".indent(number_of_digits_in_line_count))?;
-        writeln!(
-            f,
-            "{} |{}",
-            self.error.pos.line,
-            schema_lines[self.error.pos.line - 1].indent(indent),
-        )?;
-        writeln!(
-            f,
-            "{} |{}{}",
-            "".indent(number_of_digits_in_line_count),
-            "".indent(self.error.pos.column - 1 + indent),
-            "^".bright_red(),
-        )?;
+            level,
+            &self.error.kind.description(),
+            self.error.kind.notes(),
+            self.error.pos,
+            self.raw_schema,
+            self.source_name,
+        )
+    }
+}
 
-        if let Some(notes) = self.error.kind.notes() {
-            writeln!(f)?;
-            for line in notes.lines() {
-                writeln!(f, "{}", line)?;
-            }
-        }
+/// Write a diagnostic with a snippet of `source` pointing at `pos`.
+pub(super) fn write_diagnostic(
+    f: &mut fmt::Formatter,
+    level: ColoredString,
+    message: &str,
+    notes: Option<String>,
+    pos: Pos,
+    source: &str,
+    source_name: &str,
+) -> fmt::Result {
+    writeln!(f, "{level}: {message}", level = level, message = message)?;
 
-        Ok(())
+    // Nodes added by the code generation have the default position, so there is nothing to
+    // point at
+    if pos.line == 0 || pos.column == 0 {
+        writeln!(f, " --> {}", source_name)?;
+    } else {
+        write_snippet(f, pos, source, source_name)?;
     }
+
+    if let Some(notes) = notes {
+        writeln!(f)?;
+        for line in notes.lines() {
+            writeln!(f, "{}", line)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_snippet(f: &mut fmt::Formatter, pos: Pos, source: &str, source_name: &str) -> fmt::Result {
+    let schema_lines = source.lines().collect::<Vec<_>>();
+
+    let number_of_digits_in_line_count = number_of_digits(pos.line as i32);
+    let indent = 4;
+
+    writeln!(
+        f,
+        "{indent} --> {source}:{line}:{col}",
+        indent = "".indent(number_of_digits_in_line_count - 1),
+        source = source_name,
+        line = pos.line,
+        col = pos.column
+    )?;
+    writeln!(f, "{} |", "".indent(number_of_digits_in_line_count))?;
+    writeln!(
+        f,
+        "{} |{}",
+        pos.line,
+        schema_lines[pos.line - 1].indent(indent),
+    )?;
+    writeln!(
+        f,
+        "{} |{}{}",
+        "".indent(number_of_digits_in_line_count),
+        "".indent(pos.column - 1 + indent),
+        "^".bright_red(),
+    )?;
+
+    Ok(())
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -165,11 +260,37 @@ impl fmt::Display for Juniper {
     }
 }
 
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Cost {
+    InvalidName(String),
+    InvalidKey(String),
+    NegativeComplexity(String),
+}
+
+impl fmt::Display for Cost {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidName(name) => write!(f, "Invalid name `{}`. Expected `cost`", name),
+            Self::InvalidKey(key) => write!(
+                f,
+                "Invalid key. Expected `complexity` or `multipliers`, got `{}`",
+                key
+            ),
+            Self::NegativeComplexity(value) => write!(
+                f,
+                "Invalid complexity. Expected a non-negative `Int`, got `{}`",
+                value
+            ),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub enum UnsupportedDirectiveKind {
     Deprecation(Deprecation),
     Ownership(Ownership),
     Juniper(Juniper),
+    Cost(Cost),
     InvalidType {
         actual: ValueType,
         expected: ValueType,
@@ -182,6 +303,7 @@ impl fmt::Display for UnsupportedDirectiveKind {
             Self::Deprecation(inner) => write!(f, "{}", inner),
             Self::Ownership(inner) => write!(f, "{}", inner),
             Self::Juniper(inner) => write!(f, "{}", inner),
+            Self::Cost(inner) => write!(f, "{}", inner),
             Self::InvalidType { expected, actual } => {
                 write!(f, "Invalid type. Expected `{}`, got `{}`", expected, actual)
             }
@@ -204,14 +326,6 @@ pub enum ErrorKind {
     NoQueryType,
     NonnullableFieldWithDefaultValue,
     TypeExtensionNotSupported,
-    UnionFieldTypeMismatch {
-        union_name: String,
-        field_name: String,
-        type_a: String,
-        field_type_a: String,
-        type_b: String,
-        field_type_b: String,
-    },
     VariableDefaultValue,
     InputTypeFieldWithDefaultValue,
     AsRefOwnershipForNamedType,
@@ -224,6 +338,60 @@ pub enum ErrorKind {
     StreamItemInfallibleNotSupportedHere,
     SubscriptionsCannotImplementInterfaces,
     SubscriptionFieldMustBeOwned,
+    GenerateStructOnSubscription,
+    AnonymousOperation,
+    DuplicateOperationName(String),
+    SubscriptionOperationNotSupported,
+    NoMutationType,
+    UnknownField {
+        type_name: String,
+        field_name: String,
+        available: Vec<String>,
+    },
+    UnknownArgument {
+        field_name: String,
+        argument_name: String,
+    },
+    MissingArgument {
+        field_name: String,
+        argument_name: String,
+    },
+    SelectionOnLeafField {
+        field_name: String,
+        type_name: String,
+    },
+    MissingSelection {
+        field_name: String,
+        type_name: String,
+    },
+    UnknownFragment(String),
+    FragmentCycle(String),
+    UnknownType(String),
+    UndefinedVariable(String),
+    NonInputVariableType(String),
+    MissingTypename(String),
+    InvalidArgumentValue {
+        argument_name: String,
+        expected_type: String,
+        value: String,
+    },
+    VariableTypeMismatch {
+        variable_name: String,
+        variable_type: String,
+        argument_name: String,
+        expected_type: String,
+    },
+    Lint {
+        lint: Lint,
+        level: LintLevel,
+        message: String,
+    },
+    InvalidLintConfig(String),
+    InvalidCostDirective(String, Option<String>),
+    InvalidBatchField(String, Option<String>),
+    InvalidConnectionField(String, Option<String>),
+    InvalidNodeInterface(String, Option<String>),
+    InvalidFederationDirective(String, Option<String>),
 }
 
 impl ErrorKind {
@@ -258,10 +426,6 @@ impl ErrorKind {
                 "Default arguments cannot refer to variables".to_string()
             }
             ErrorKind::TypeExtensionNotSupported => "Type extentions are not supported".to_string(),
-            ErrorKind::UnionFieldTypeMismatch { union_name, .. } => format!(
-                "Error while generating `QueryTrail` for union `{}`",
-                union_name
-            ),
             ErrorKind::InputTypeFieldWithDefaultValue => {
                 "Default values for input type fields are not supported".to_string()
             }
@@ -296,46 +460,136 @@ impl ErrorKind {
             ErrorKind::SubscriptionFieldMustBeOwned => {
                 "Subscription fields must use `@juniper(ownership: \"owned\")`".to_string()
             }
+            ErrorKind::GenerateStructOnSubscription => {
+                "`generate_struct` directive argument is not supported on subscription types".to_string()
+            }
+            ErrorKind::AnonymousOperation => {
+                "Operations must be named to generate client code for them".to_string()
+            }
+            ErrorKind::DuplicateOperationName(name) => {
+                format!("There can only be one operation named `{}`", name)
+            }
+            ErrorKind::SubscriptionOperationNotSupported => {
+                "Generating client code for subscriptions is not supported".to_string()
+            }
+            ErrorKind::NoMutationType => "Schema doesn't have a Mutation type".to_string(),
+            ErrorKind::UnknownField { type_name, field_name, .. } => {
+                format!("Unknown field `{}` on type `{}`", field_name, type_name)
+            }
+            ErrorKind::UnknownArgument { field_name, argument_name } => {
+                format!("Unknown argument `{}` on field `{}`", argument_name, field_name)
+            }
+            ErrorKind::MissingArgument { field_name, argument_name } => {
+                format!("Field `{}` is missing the required argument `{}`", field_name, argument_name)
+            }
+            ErrorKind::SelectionOnLeafField { field_name, type_name } => {
+                format!("Field `{}` of type `{}` cannot have a selection set", field_name, type_name)
+            }
+            ErrorKind::MissingSelection { field_name, type_name } => {
+                format!("Field `{}` of type `{}` must have a selection set", field_name, type_name)
+            }
+            ErrorKind::UnknownFragment(name) => format!("Unknown fragment `{}`", name),
+            ErrorKind::FragmentCycle(name) => format!("Fragment `{}` spreads itself", name),
+            ErrorKind::UnknownType(name) => format!("Unknown type `{}`", name),
+            ErrorKind::UndefinedVariable(name) => format!("Variable `${}` is not defined", name),
+            ErrorKind::NonInputVariableType(name) => {
+                format!("Variables cannot be of type `{}` because it is not an input type", name)
+            }
+            ErrorKind::MissingTypename(name) => {
+                format!("Selections on `{}` with type conditions must include `__typename`", name)
+            }
+            ErrorKind::InvalidArgumentValue { argument_name, expected_type, value } => {
+                format!("Argument `{}` expects type `{}`, got `{}`", argument_name, expected_type, value)
+            }
+            ErrorKind::VariableTypeMismatch { variable_name, variable_type, argument_name, expected_type } => {
+                format!(
+                    "Variable `${}` of type `{}` cannot be used for argument `{}` of type `{}`",
+                    variable_name, variable_type, argument_name, expected_type,
+                )
+            }
+            ErrorKind::Lint { message, .. } => message.to_string(),
+            ErrorKind::InvalidLintConfig(message) => format!("Invalid `@lints` directive: {}", message),
+            ErrorKind::InvalidCostDirective(msg, _) => msg.clone(),
+            ErrorKind::InvalidBatchField(msg, _) => msg.clone(),
+            ErrorKind::InvalidConnectionField(msg, _) => msg.clone(),
+            ErrorKind::InvalidNodeInterface(msg, _) => msg.clone(),
+            ErrorKind::InvalidFederationDirective(msg, _) => msg.clone(),
         }
     }
 
-    #[allow(unused_must_use)]
-    fn notes(&self) -> Option<String> {
+    /// The stable code of the error, explained by `juniper-from-schema explain <code>`. Codes are
+    /// never reused, so new variants get new codes.
+    pub(crate) fn code(&self) -> &'static str {
         match self {
-            ErrorKind::UnionFieldTypeMismatch {
-                union_name,
-                field_name,
-                type_a,
-                type_b,
-                field_type_a,
-                field_type_b,
-            } => {
-                let mut f = String::new();
-
-                writeln!(
-                    f,
-                    "`{}.{}` and `{}.{}` are not the same type",
-                    type_a, field_name, type_b, field_name
-                );
-                writeln!(
-                    f,
-                    "    `{}.{}` is of type `{}`",
-                    type_a, field_name, field_type_a
-                );
-                writeln!(
-                    f,
-                    "    `{}.{}` is of type `{}`",
-                    type_b, field_name, field_type_b
-                );
-                writeln!(f, "That makes it impossible to generate code for the method `QueryTrail<_, {}, _>::{}()`", union_name, field_name);
-                writeln!(
-                    f,
-                    "It would have to return `{}` if `{}` is `{},` but `{}` if it is a `{}`",
-                    field_type_a, union_name, type_a, field_type_b, type_b
-                );
+            ErrorKind::DateTimeScalarNotDefined => "JFS0001",
+            ErrorKind::DateScalarNotDefined => "JFS0002",
+            ErrorKind::UuidScalarNotDefined => "JFS0003",
+            ErrorKind::UrlScalarNotDefined => "JFS0004",
+            ErrorKind::SpecialCaseScalarWithDescription => "JFS0005",
+            ErrorKind::UnsupportedDirective(_) => "JFS0006",
+            ErrorKind::UnknownDirective { .. } => "JFS0007",
+            ErrorKind::NoQueryType => "JFS0008",
+            ErrorKind::NonnullableFieldWithDefaultValue => "JFS0009",
+            ErrorKind::TypeExtensionNotSupported => "JFS0010",
+            // JFS0011 is no longer emitted
+            ErrorKind::VariableDefaultValue => "JFS0012",
+            ErrorKind::InputTypeFieldWithDefaultValue => "JFS0013",
+            ErrorKind::AsRefOwnershipForNamedType => "JFS0014",
+            ErrorKind::FieldNameInSnakeCase => "JFS0015",
+            ErrorKind::UppercaseUuidScalar => "JFS0016",
+            ErrorKind::InvalidJuniperDirective(_, _) => "JFS0017",
+            ErrorKind::CannotDeclareBuiltinAsScalar => "JFS0018",
+            ErrorKind::InvalidStreamReturnType(_) => "JFS0019",
+            ErrorKind::StreamTypeNotSupportedHere => "JFS0020",
+            ErrorKind::StreamItemInfallibleNotSupportedHere => "JFS0021",
+            ErrorKind::SubscriptionsCannotImplementInterfaces => "JFS0022",
+            ErrorKind::SubscriptionFieldMustBeOwned => "JFS0023",
+            ErrorKind::GenerateStructOnSubscription => "JFS0024",
+            ErrorKind::AnonymousOperation => "JFS0025",
+            ErrorKind::DuplicateOperationName(_) => "JFS0026",
+            ErrorKind::SubscriptionOperationNotSupported => "JFS0027",
+            ErrorKind::NoMutationType => "JFS0028",
+            ErrorKind::UnknownField { .. } => "JFS0029",
+            ErrorKind::UnknownArgument { .. } => "JFS0030",
+            ErrorKind::MissingArgument { .. } => "JFS0031",
+            ErrorKind::SelectionOnLeafField { .. } => "JFS0032",
+            ErrorKind::MissingSelection { .. } => "JFS0033",
+            ErrorKind::UnknownFragment(_) => "JFS0034",
+            ErrorKind::FragmentCycle(_) => "JFS0035",
+            ErrorKind::UnknownType(_) => "JFS0036",
+            ErrorKind::UndefinedVariable(_) => "JFS0037",
+            ErrorKind::NonInputVariableType(_) => "JFS0038",
+            ErrorKind::MissingTypename(_) => "JFS0039",
+            ErrorKind::InvalidArgumentValue { .. } => "JFS0040",
+            ErrorKind::VariableTypeMismatch { .. } => "JFS0041",
+            ErrorKind::Lint { lint, .. } => lint.code(),
+            ErrorKind::InvalidLintConfig(_) => "JFS0042",
+            ErrorKind::InvalidCostDirective(_, _) => "JFS0043",
+            ErrorKind::InvalidBatchField(_, _) => "JFS0044",
+            ErrorKind::InvalidConnectionField(_, _) => "JFS0045",
+            ErrorKind::InvalidNodeInterface(_, _) => "JFS0046",
+            ErrorKind::InvalidFederationDirective(_, _) => "JFS0047",
+        }
+    }
 
-                Some(f)
+    fn suggestions(&self) -> Vec<String> {
+        match self {
+            ErrorKind::DateTimeScalarNotDefined => vec!["scalar DateTimeUtc".to_string()],
+            ErrorKind::DateScalarNotDefined => vec!["scalar Date".to_string()],
+            ErrorKind::UuidScalarNotDefined => vec!["scalar Uuid".to_string()],
+            ErrorKind::UrlScalarNotDefined => vec!["scalar Url".to_string()],
+            ErrorKind::UppercaseUuidScalar => vec!["Uuid".to_string()],
+            ErrorKind::SubscriptionFieldMustBeOwned => {
+                vec!["@juniper(ownership: \"owned\")".to_string()]
             }
+            ErrorKind::MissingTypename(_) => vec!["__typename".to_string()],
+            _ => vec![],
+        }
+    }
+
+    #[allow(unused_must_use)]
+    fn notes(&self) -> Option<String> {
+        match self {
             ErrorKind::DateTimeScalarNotDefined => {
                 Some("Insert `scalar DateTimeUtc` into your schema".to_string())
             }
@@ -371,7 +625,31 @@ impl ErrorKind {
                 Some("This is to be consistent with the naming the \"uuid\" crate".to_string())
             }
             ErrorKind::InvalidJuniperDirective(_, notes) => notes.to_owned(),
+            ErrorKind::InvalidCostDirective(_, notes) => notes.to_owned(),
+            ErrorKind::InvalidBatchField(_, notes) => notes.to_owned(),
+            ErrorKind::InvalidConnectionField(_, notes) => notes.to_owned(),
+            ErrorKind::InvalidNodeInterface(_, notes) => notes.to_owned(),
+            ErrorKind::InvalidFederationDirective(_, notes) => notes.to_owned(),
             ErrorKind::InvalidStreamReturnType(syn_error) => Some(syn_error.to_owned()),
+            ErrorKind::UnknownField { available, .. } => {
+                if available.is_empty() {
+                    None
+                } else {
+                    let available = available
+                        .iter()
+                        .map(|name| format!("`{}`", name))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    Some(format!("Available fields are: {}", available))
+                }
+            }
+            ErrorKind::Lint { lint, level, .. } => {
+                Some(format!("The `{}` lint is set to `{}`", lint, level))
+            }
+            ErrorKind::MissingTypename(_) => Some(
+                "It is used to decide which variant of the generated enum the response is"
+                    .to_string(),
+            ),
             _ => None,
         }
     }
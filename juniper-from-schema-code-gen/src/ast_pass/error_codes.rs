@@ -0,0 +1,789 @@
+// The long-form explanation of every error code, shown by `juniper-from-schema explain <code>`.
+//
+// Codes are never reused or renumbered. Errors that are no longer emitted keep their explanation,
+// so old codes still explain something.
+pub const EXPLANATIONS: &[(&str, &str)] = &[
+    (
+        "JFS0001",
+        r#"A field uses the `DateTimeUtc` type but the schema doesn't define it.
+
+`DateTimeUtc` is mapped to `chrono::DateTime<chrono::Utc>`, but like any other
+custom scalar it has to be declared in the schema:
+
+    scalar DateTimeUtc
+
+    type Query {
+      createdAt: DateTimeUtc!
+    }
+"#,
+    ),
+    (
+        "JFS0002",
+        r#"A field uses the `Date` type but the schema doesn't define it.
+
+`Date` is mapped to `chrono::NaiveDate`, but like any other custom scalar it has
+to be declared in the schema:
+
+    scalar Date
+
+    type Query {
+      birthday: Date!
+    }
+"#,
+    ),
+    (
+        "JFS0003",
+        r#"A field uses the `Uuid` type but the schema doesn't define it.
+
+`Uuid` is mapped to `uuid::Uuid`, but like any other custom scalar it has to be
+declared in the schema:
+
+    scalar Uuid
+"#,
+    ),
+    (
+        "JFS0004",
+        r#"A field uses the `Url` type but the schema doesn't define it.
+
+`Url` is mapped to `url::Url`, but like any other custom scalar it has to be
+declared in the schema:
+
+    scalar Url
+"#,
+    ),
+    (
+        "JFS0005",
+        r#"One of the special case scalars has a description.
+
+`Date`, `DateTimeUtc`, `Uuid`, and `Url` are mapped to types from external
+crates, so there is nowhere to put the description. Remove it:
+
+    "A point in time"   <- remove this
+    scalar DateTimeUtc
+"#,
+    ),
+    (
+        "JFS0006",
+        r#"A directive is used with arguments it doesn't support.
+
+For example `@deprecated` only supports a `reason` argument and the `ownership`
+argument of `@juniper` must be `"owned"`, `"borrowed"`, or `"as_ref"`. The note
+on the error says what is wrong.
+"#,
+    ),
+    (
+        "JFS0007",
+        r#"A directive or directive argument isn't supported in this position.
+
+Only `@juniper`, `@deprecated`, `@lints`, `@cost`, and `@connection`, and the
+federation directives `@key`, `@extends`, `@external`, `@requires`, and
+`@provides`, are understood, and only in the positions described in the docs.
+Other directives would be silently ignored by the generated code, so they are
+rejected instead.
+"#,
+    ),
+    (
+        "JFS0008",
+        r#"The schema doesn't have a query type.
+
+Every GraphQL schema must have a query type:
+
+    schema {
+      query: Query
+    }
+
+    type Query {
+      ping: Boolean!
+    }
+"#,
+    ),
+    (
+        "JFS0009",
+        r#"An argument with a default value is non-null.
+
+The default value is used when the argument is omitted, which requires it to be
+nullable. Remove the `!`:
+
+    type Query {
+      users(first: Int = 10): [User!]!
+    }
+"#,
+    ),
+    (
+        "JFS0010",
+        r#"The schema uses `extend type` or another type extension.
+
+Type extensions are not supported. Add the fields to the original definition
+instead.
+"#,
+    ),
+    (
+        "JFS0011",
+        r#"Two members of a union have a field of the same name but different types.
+
+This error is no longer emitted. `QueryTrail` methods for a union are only
+generated for fields that have the same type and arguments on every member.
+Other fields can be walked after downcasting the trail to a member with
+`DowncastQueryTrail::downcast`.
+"#,
+    ),
+    (
+        "JFS0012",
+        r#"A default value refers to a variable.
+
+Default values in a schema must be constants:
+
+    type Query {
+      users(first: Int = 10): [User!]!
+    }
+"#,
+    ),
+    (
+        "JFS0013",
+        r#"A field of an input type has a default value.
+
+This isn't supported because the spec isn't clear about what should happen when
+there are defaults in both the input type definition and a field argument. Use
+a default value on the field argument instead.
+"#,
+    ),
+    (
+        "JFS0014",
+        r#"`@juniper(ownership: "as_ref")` is used on a field that isn't a list or
+nullable.
+
+`as_ref` changes the return type from `Option<T>` to `Option<&T>`, or from
+`Vec<T>` to `Vec<&T>`, so it only applies to nullable and list fields. Use
+`borrowed` or `owned` for other fields.
+"#,
+    ),
+    (
+        "JFS0015",
+        r#"A field name is in snake_case.
+
+Juniper always converts field names to camelCase, so a field called
+`created_at` would be exposed as `createdAt`. Name the field in camelCase in the
+schema:
+
+    type User {
+      createdAt: DateTimeUtc!
+    }
+"#,
+    ),
+    (
+        "JFS0016",
+        r#"The UUID scalar is named `UUID`.
+
+The scalar must be named `Uuid`, to be consistent with the naming of the `uuid`
+crate:
+
+    scalar Uuid
+"#,
+    ),
+    (
+        "JFS0017",
+        r#"The schema includes a definition of `@juniper` that doesn't match the
+supported one.
+
+Schemas are allowed to include the definition of `@juniper`, for the benefit of
+other tools, but it must match exactly. Print the supported definition with
+
+    juniper-from-schema print-directive
+"#,
+    ),
+    (
+        "JFS0018",
+        r#"A custom scalar has the same name as a built-in scalar.
+
+`Int`, `Float`, `String`, `Boolean`, and `ID` are built into GraphQL and can't
+be declared again. Remove the declaration.
+"#,
+    ),
+    (
+        "JFS0019",
+        r#"The `stream_type` argument of `@juniper` isn't a valid Rust type.
+
+The value is parsed as a Rust type and used as the return type of the
+subscription resolver, for example:
+
+    users: User! @juniper(ownership: "owned", stream_type: "BoxStream<'static, User>")
+"#,
+    ),
+    (
+        "JFS0020",
+        r#"`@juniper(stream_type: ...)` is used on a field that isn't on the
+subscription type.
+
+Only subscription fields return streams.
+"#,
+    ),
+    (
+        "JFS0021",
+        r#"`@juniper(stream_item_infallible: ...)` is used on a field that isn't on
+the subscription type.
+
+Only subscription fields return streams, so only they have stream items.
+"#,
+    ),
+    (
+        "JFS0022",
+        r#"The subscription type implements an interface.
+
+Juniper doesn't support subscription types implementing interfaces. Remove the
+`implements` clause.
+"#,
+    ),
+    (
+        "JFS0023",
+        r#"A subscription field doesn't use `@juniper(ownership: "owned")`.
+
+Streams can't borrow from the subscription type, so every subscription field
+must be owned:
+
+    type Subscription {
+      users: User! @juniper(ownership: "owned")
+    }
+"#,
+    ),
+    (
+        "JFS0024",
+        r#"`@juniper(generate_struct: true)` is used on the subscription type.
+
+Subscription fields return streams, which can't be stored in a struct. Implement
+the `*Fields` trait instead.
+"#,
+    ),
+    (
+        "JFS0025",
+        r#"An operation used for client code doesn't have a name.
+
+The name is used for the generated module, so every operation must be named:
+
+    query GetUser($id: ID!) {
+      user(id: $id) { name }
+    }
+"#,
+    ),
+    (
+        "JFS0026",
+        r#"Two operations have the same name.
+
+The name is used for the generated module, so names must be unique.
+"#,
+    ),
+    (
+        "JFS0027",
+        r#"A subscription operation is used for client code.
+
+Generating client code for subscriptions is not supported. Only queries and
+mutations are.
+"#,
+    ),
+    (
+        "JFS0028",
+        r#"A mutation operation is used but the schema doesn't have a mutation type.
+
+Add a mutation type to the schema definition:
+
+    schema {
+      query: Query
+      mutation: Mutation
+    }
+"#,
+    ),
+    (
+        "JFS0029",
+        r#"An operation selects a field that doesn't exist.
+
+The note lists the fields that are available on the type. Field names are
+case sensitive.
+"#,
+    ),
+    (
+        "JFS0030",
+        r#"An operation passes an argument that the field doesn't have.
+
+Check the field definition in the schema for the arguments it accepts.
+"#,
+    ),
+    (
+        "JFS0031",
+        r#"An operation doesn't pass a required argument.
+
+Arguments that are non-null and don't have a default value must be passed.
+"#,
+    ),
+    (
+        "JFS0032",
+        r#"An operation has a selection set on a scalar or enum field.
+
+Only fields of object, interface, and union types have fields to select. Remove
+the selection set.
+"#,
+    ),
+    (
+        "JFS0033",
+        r#"An operation selects a field of an object, interface, or union type without
+a selection set.
+
+Select the fields you need:
+
+    query GetUser {
+      user { name }
+    }
+"#,
+    ),
+    (
+        "JFS0034",
+        r#"An operation spreads a fragment that isn't defined.
+
+Fragments must be defined in the same file as the operations using them.
+"#,
+    ),
+    (
+        "JFS0035",
+        r#"A fragment spreads itself, directly or through other fragments.
+
+That would make the selection infinitely large. Remove the cycle.
+"#,
+    ),
+    (
+        "JFS0036",
+        r#"An operation refers to a type that isn't in the schema.
+
+This is usually a variable type or a type condition on a fragment. Type names
+are case sensitive.
+"#,
+    ),
+    (
+        "JFS0037",
+        r#"An operation uses a variable that isn't defined.
+
+Declare it on the operation:
+
+    query GetUser($id: ID!) {
+      user(id: $id) { name }
+    }
+"#,
+    ),
+    (
+        "JFS0038",
+        r#"A variable has a type that isn't an input type.
+
+Variables can only be scalars, enums, input objects, or lists of those.
+"#,
+    ),
+    (
+        "JFS0039",
+        r#"A selection on an interface or union uses type conditions without
+selecting `__typename`.
+
+The generated client code uses `__typename` to decide which variant of the
+generated enum the response is:
+
+    query GetNode {
+      node {
+        __typename
+        ... on User { name }
+      }
+    }
+"#,
+    ),
+    (
+        "JFS0040",
+        r#"An argument value doesn't have the type the argument expects.
+
+For example passing a string to an `Int` argument, or `null` to a non-null
+argument.
+"#,
+    ),
+    (
+        "JFS0041",
+        r#"A variable is used for an argument of an incompatible type.
+
+A nullable variable can only be used for a non-null argument if the variable has
+a default value.
+"#,
+    ),
+    (
+        "JFS0042",
+        r#"The `@lints` directive on the schema definition is invalid.
+
+It supports `allow`, `warn`, and `deny`, each a list of lint names, and
+`max_list_nesting_depth`, a non-negative integer:
+
+    schema @lints(deny: ["missing_description"], max_list_nesting_depth: 2) {
+      query: Query
+    }
+"#,
+    ),
+    (
+        "JFS0043",
+        r#"A `@cost` directive, or the schema's definition of `@cost`, is invalid.
+
+`multipliers` must name `Int` arguments of the field the directive is on, and
+is only supported on fields:
+
+    type Query {
+      users(first: Int = 10): [User!]! @cost(complexity: 2, multipliers: ["first"])
+    }
+
+Schemas are allowed to include the definition of `@cost`, for the benefit of
+other tools, but it must match exactly. Print the supported definition with
+
+    juniper-from-schema print-directive
+"#,
+    ),
+    (
+        "JFS0044",
+        r#"A field with `@juniper(batch: true)` can't be batch loaded.
+
+Batched fields must be on object types, not interfaces or subscriptions, and
+must also use `async: true` and `ownership: "owned"`. They can't be
+`infallible`, since loading a batch can fail:
+
+    type User {
+      posts: [Post!]! @juniper(batch: true, async: true, ownership: "owned")
+    }
+
+Fields that are also declared by an interface the type implements can't be
+batched either, because the interface resolves them through the `*Fields`
+trait.
+"#,
+    ),
+    (
+        "JFS0045",
+        r#"A field with `@connection` can't be turned into a connection.
+
+Connection fields must return a list of objects, interfaces, or unions, and
+must not declare the `first`, `after`, `last`, or `before` arguments since
+`@connection` adds them:
+
+    type Query {
+      users(role: String): [User!]! @connection
+    }
+
+The `{Type}Connection`, `{Type}Edge`, and `PageInfo` types are generated, so
+the schema can't define types with those names. The only exception is a
+`PageInfo` with exactly these fields, in this order:
+
+    type PageInfo {
+      hasNextPage: Boolean!
+      hasPreviousPage: Boolean!
+      startCursor: String
+      endCursor: String
+    }
+
+Connection fields are only supported on object types, not interfaces or
+subscriptions, and can't be batched.
+"#,
+    ),
+    (
+        "JFS0046",
+        r#"The Relay node interface or the fields that look up nodes are invalid.
+
+Only one interface can be marked with `@juniper(node_interface: true)` and it
+must have an `id: ID!` field. The `node` and `nodes` fields of the query type
+are resolved through the generated `NodeResolver` trait, so they must have
+these signatures and be async:
+
+    interface Node @juniper(node_interface: true) {
+      id: ID!
+    }
+
+    type Query {
+      node(id: ID!): Node @juniper(async: true)
+      nodes(ids: [ID!]!): [Node]! @juniper(async: true)
+    }
+
+Node lookups can fail when the id is malformed, so the fields can't be
+infallible, and they can't be batched.
+"#,
+    ),
+    (
+        "JFS0047",
+        r#"An Apollo Federation directive is invalid.
+
+`@key`, `@requires` and `@provides` take a single `fields` argument with the
+selection of fields as a string, while `@extends` and `@external` take no
+arguments:
+
+    type User @key(fields: "id") @extends {
+      id: ID! @external
+      reviews: [Review!]! @requires(fields: "name")
+    }
+
+The fields selected by `@key` must exist on the type. Entities are resolved
+through the `_entities` field of the query type, so `@key` can't be used on
+the query, mutation or subscription type.
+
+Only types with `@extends` are defined by other subgraphs, so only their
+fields can be `@external`. `@requires` must select `@external` fields of the
+type, and `@provides` must select fields of the type the field returns.
+"#,
+    ),
+    (
+        "JFS1001",
+        r#"A type doesn't have a description (the `missing_description` lint).
+
+Descriptions are shown by GraphQL tools and become the docs of the generated
+Rust types:
+
+    "A user of the app"
+    type User {
+      id: ID!
+    }
+"#,
+    ),
+    (
+        "JFS1002",
+        r#"An enum value isn't in SCREAMING_SNAKE_CASE (the `enum_value_case` lint).
+
+SCREAMING_SNAKE_CASE is the convention for GraphQL enum values:
+
+    enum Status {
+      ACTIVE
+      SOFT_DELETED
+    }
+"#,
+    ),
+    (
+        "JFS1003",
+        r#"A mutation isn't named verb+noun (the `mutation_name` lint).
+
+Names such as `createUser` or `deleteComment` make it clear what the mutation
+does, unlike `user`.
+"#,
+    ),
+    (
+        "JFS1004",
+        r#"An input type name doesn't end with `Input` (the `input_type_suffix`
+lint).
+
+The suffix keeps input types apart from the output types they often mirror,
+such as `User` and `UserInput`.
+"#,
+    ),
+    (
+        "JFS1005",
+        r#"Lists are nested deeper than allowed (the `list_nesting_depth` lint).
+
+Deeply nested lists are hard to use from clients. Consider an object type for
+the inner lists. The maximum depth is set with `max_list_nesting_depth`.
+"#,
+    ),
+    (
+        "JFS1006",
+        r#"`@deprecated` is used without a reason (the `deprecated_without_reason`
+lint).
+
+The reason tells clients what to use instead:
+
+    type User {
+      name: String! @deprecated(reason: "Use `fullName`")
+    }
+"#,
+    ),
+    (
+        "JFS1007",
+        r#"A type isn't reachable from the query, mutation, or subscription types (the
+`unused_type` lint).
+
+Clients can never see the type, so it is probably left over from a refactoring.
+Remove it, or use it from a field.
+"#,
+    ),
+    (
+        "JFS1008",
+        r#"A type has the same name as an item in the Rust prelude (the
+`prelude_shadowing` lint).
+
+Code in the module the schema is generated in that uses, for example, `Result`
+would refer to the generated type instead of `std::result::Result`. Rename the
+type, such as `Result` to `SearchResult`.
+"#,
+    ),
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ast_pass::{
+        error::{ErrorKind, UnsupportedDirectiveKind, ValueType},
+        lints::{Lint, LintLevel},
+    };
+    use std::{collections::HashSet, mem};
+
+    #[test]
+    fn test_codes_are_sorted_and_unique() {
+        let codes = EXPLANATIONS
+            .iter()
+            .map(|(code, _)| *code)
+            .collect::<Vec<_>>();
+        let mut sorted = codes.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted, codes);
+    }
+
+    // One value of every `ErrorKind`. The `match` stops compiling when a variant is added, so it
+    // has to be added to the list as well.
+    fn every_error_kind() -> Vec<ErrorKind> {
+        let kinds = vec![
+            ErrorKind::DateTimeScalarNotDefined,
+            ErrorKind::DateScalarNotDefined,
+            ErrorKind::UuidScalarNotDefined,
+            ErrorKind::UrlScalarNotDefined,
+            ErrorKind::SpecialCaseScalarWithDescription,
+            ErrorKind::UnsupportedDirective(UnsupportedDirectiveKind::InvalidType {
+                actual: ValueType::Int,
+                expected: ValueType::String,
+            }),
+            ErrorKind::UnknownDirective {
+                suggestions: Vec::new(),
+            },
+            ErrorKind::NoQueryType,
+            ErrorKind::NonnullableFieldWithDefaultValue,
+            ErrorKind::TypeExtensionNotSupported,
+            ErrorKind::VariableDefaultValue,
+            ErrorKind::InputTypeFieldWithDefaultValue,
+            ErrorKind::AsRefOwnershipForNamedType,
+            ErrorKind::FieldNameInSnakeCase,
+            ErrorKind::UppercaseUuidScalar,
+            ErrorKind::InvalidJuniperDirective(String::new(), None),
+            ErrorKind::CannotDeclareBuiltinAsScalar,
+            ErrorKind::InvalidStreamReturnType(String::new()),
+            ErrorKind::StreamTypeNotSupportedHere,
+            ErrorKind::StreamItemInfallibleNotSupportedHere,
+            ErrorKind::SubscriptionsCannotImplementInterfaces,
+            ErrorKind::SubscriptionFieldMustBeOwned,
+            ErrorKind::GenerateStructOnSubscription,
+            ErrorKind::AnonymousOperation,
+            ErrorKind::DuplicateOperationName(String::new()),
+            ErrorKind::SubscriptionOperationNotSupported,
+            ErrorKind::NoMutationType,
+            ErrorKind::UnknownField {
+                type_name: String::new(),
+                field_name: String::new(),
+                available: Vec::new(),
+            },
+            ErrorKind::UnknownArgument {
+                field_name: String::new(),
+                argument_name: String::new(),
+            },
+            ErrorKind::MissingArgument {
+                field_name: String::new(),
+                argument_name: String::new(),
+            },
+            ErrorKind::SelectionOnLeafField {
+                field_name: String::new(),
+                type_name: String::new(),
+            },
+            ErrorKind::MissingSelection {
+                field_name: String::new(),
+                type_name: String::new(),
+            },
+            ErrorKind::UnknownFragment(String::new()),
+            ErrorKind::FragmentCycle(String::new()),
+            ErrorKind::UnknownType(String::new()),
+            ErrorKind::UndefinedVariable(String::new()),
+            ErrorKind::NonInputVariableType(String::new()),
+            ErrorKind::MissingTypename(String::new()),
+            ErrorKind::InvalidArgumentValue {
+                argument_name: String::new(),
+                expected_type: String::new(),
+                value: String::new(),
+            },
+            ErrorKind::VariableTypeMismatch {
+                variable_name: String::new(),
+                variable_type: String::new(),
+                argument_name: String::new(),
+                expected_type: String::new(),
+            },
+            ErrorKind::Lint {
+                lint: Lint::MissingDescription,
+                level: LintLevel::Warn,
+                message: String::new(),
+            },
+            ErrorKind::InvalidLintConfig(String::new()),
+            ErrorKind::InvalidCostDirective(String::new(), None),
+            ErrorKind::InvalidBatchField(String::new(), None),
+            ErrorKind::InvalidConnectionField(String::new(), None),
+            ErrorKind::InvalidNodeInterface(String::new(), None),
+            ErrorKind::InvalidFederationDirective(String::new(), None),
+        ];
+
+        for kind in &kinds {
+            match kind {
+                ErrorKind::DateTimeScalarNotDefined
+                | ErrorKind::DateScalarNotDefined
+                | ErrorKind::UuidScalarNotDefined
+                | ErrorKind::UrlScalarNotDefined
+                | ErrorKind::SpecialCaseScalarWithDescription
+                | ErrorKind::UnsupportedDirective(_)
+                | ErrorKind::UnknownDirective { .. }
+                | ErrorKind::NoQueryType
+                | ErrorKind::NonnullableFieldWithDefaultValue
+                | ErrorKind::TypeExtensionNotSupported
+                | ErrorKind::VariableDefaultValue
+                | ErrorKind::InputTypeFieldWithDefaultValue
+                | ErrorKind::AsRefOwnershipForNamedType
+                | ErrorKind::FieldNameInSnakeCase
+                | ErrorKind::UppercaseUuidScalar
+                | ErrorKind::InvalidJuniperDirective(_, _)
+                | ErrorKind::CannotDeclareBuiltinAsScalar
+                | ErrorKind::InvalidStreamReturnType(_)
+                | ErrorKind::StreamTypeNotSupportedHere
+                | ErrorKind::StreamItemInfallibleNotSupportedHere
+                | ErrorKind::SubscriptionsCannotImplementInterfaces
+                | ErrorKind::SubscriptionFieldMustBeOwned
+                | ErrorKind::GenerateStructOnSubscription
+                | ErrorKind::AnonymousOperation
+                | ErrorKind::DuplicateOperationName(_)
+                | ErrorKind::SubscriptionOperationNotSupported
+                | ErrorKind::NoMutationType
+                | ErrorKind::UnknownField { .. }
+                | ErrorKind::UnknownArgument { .. }
+                | ErrorKind::MissingArgument { .. }
+                | ErrorKind::SelectionOnLeafField { .. }
+                | ErrorKind::MissingSelection { .. }
+                | ErrorKind::UnknownFragment(_)
+                | ErrorKind::FragmentCycle(_)
+                | ErrorKind::UnknownType(_)
+                | ErrorKind::UndefinedVariable(_)
+                | ErrorKind::NonInputVariableType(_)
+                | ErrorKind::MissingTypename(_)
+                | ErrorKind::InvalidArgumentValue { .. }
+                | ErrorKind::VariableTypeMismatch { .. }
+                | ErrorKind::Lint { .. }
+                | ErrorKind::InvalidLintConfig(_)
+                | ErrorKind::InvalidCostDirective(_, _)
+                | ErrorKind::InvalidBatchField(_, _)
+                | ErrorKind::InvalidConnectionField(_, _)
+                | ErrorKind::InvalidNodeInterface(_, _)
+                | ErrorKind::InvalidFederationDirective(_, _) => {}
+            }
+        }
+
+        kinds
+    }
+
+    #[test]
+    fn test_every_error_kind_has_an_explanation() {
+        let kinds = every_error_kind();
+
+        let variants = kinds.iter().map(mem::discriminant).collect::<HashSet<_>>();
+        assert_eq!(kinds.len(), variants.len());
+
+        for kind in kinds {
+            assert!(crate::explain(kind.code()).is_some(), "{:?}", kind);
+        }
+    }
+
+    #[test]
+    fn test_every_lint_has_an_explanation() {
+        for lint in Lint::ALL {
+            assert!(crate::explain(lint.code()).is_some(), "{}", lint);
+        }
+    }
+}
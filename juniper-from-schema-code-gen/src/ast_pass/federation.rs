@@ -0,0 +1,72 @@
+use graphql_parser::schema::*;
+
+// The directives understood by the code generation, other than the standard `@deprecated`. They
+// only configure the generated code, so the gateway doesn't know them
+const CODE_GEN_DIRECTIVES: &[&str] = &["juniper", "cost", "connection", "lints"];
+
+// Defined by the gateway itself, so subgraphs leave their definitions out
+const FEDERATION_DIRECTIVES: &[&str] = &["key", "extends", "external", "requires", "provides"];
+
+/// The SDL returned by `Query._service`, which the gateway composes the supergraph from.
+///
+/// It is the schema without the directives that only concern `juniper-from-schema` and without
+/// the definitions of the federation directives.
+pub fn subgraph_sdl<'doc>(doc: &Document<'doc, &'doc str>) -> String {
+    let mut doc = doc.clone();
+
+    doc.definitions.retain(|definition| match definition {
+        Definition::DirectiveDefinition(directive) => {
+            !CODE_GEN_DIRECTIVES.contains(&directive.name)
+                && !FEDERATION_DIRECTIVES.contains(&directive.name)
+        }
+        _ => true,
+    });
+
+    for definition in &mut doc.definitions {
+        match definition {
+            Definition::SchemaDefinition(schema) => strip_directives(&mut schema.directives),
+            Definition::TypeDefinition(type_definition) => strip_type_definition(type_definition),
+            Definition::TypeExtension(_) | Definition::DirectiveDefinition(_) => {}
+        }
+    }
+
+    doc.to_string()
+}
+
+fn strip_type_definition<'doc>(type_definition: &mut TypeDefinition<'doc, &'doc str>) {
+    match type_definition {
+        TypeDefinition::Scalar(scalar) => strip_directives(&mut scalar.directives),
+        TypeDefinition::Object(object) => {
+            strip_directives(&mut object.directives);
+            object.fields.iter_mut().for_each(strip_field);
+        }
+        TypeDefinition::Interface(interface) => {
+            strip_directives(&mut interface.directives);
+            interface.fields.iter_mut().for_each(strip_field);
+        }
+        TypeDefinition::Union(union) => strip_directives(&mut union.directives),
+        TypeDefinition::Enum(enum_type) => {
+            strip_directives(&mut enum_type.directives);
+            for value in &mut enum_type.values {
+                strip_directives(&mut value.directives);
+            }
+        }
+        TypeDefinition::InputObject(input_object) => {
+            strip_directives(&mut input_object.directives);
+            for field in &mut input_object.fields {
+                strip_directives(&mut field.directives);
+            }
+        }
+    }
+}
+
+fn strip_field<'doc>(field: &mut Field<'doc, &'doc str>) {
+    strip_directives(&mut field.directives);
+    for argument in &mut field.arguments {
+        strip_directives(&mut argument.directives);
+    }
+}
+
+fn strip_directives<'doc>(directives: &mut Vec<Directive<'doc, &'doc str>>) {
+    directives.retain(|directive| !CODE_GEN_DIRECTIVES.contains(&directive.name));
+}
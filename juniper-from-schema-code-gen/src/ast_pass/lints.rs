@@ -0,0 +1,814 @@
+use super::{error::Error, schema_visitor::SchemaVisitor, type_name, EmitError, ErrorKind};
+use graphql_parser::{
+    query::Value,
+    schema::{self, *},
+    Pos,
+};
+use heck::{ShoutySnakeCase, SnakeCase};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
+    str::FromStr,
+};
+
+/// A style check that can be turned on or off.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Lint {
+    /// Types must have descriptions.
+    MissingDescription,
+    /// Enum values must be in SCREAMING_SNAKE_CASE.
+    EnumValueCase,
+    /// Mutations must be named verb+noun, such as `createUser`.
+    MutationName,
+    /// Input object names must end with `Input`.
+    InputTypeSuffix,
+    /// Lists must not be nested deeper than the configured maximum.
+    ListNestingDepth,
+    /// `@deprecated` must have a reason.
+    DeprecatedWithoutReason,
+    /// Types must be reachable from the `query`, `mutation`, or `subscription` types.
+    UnusedType,
+    /// Types must not have the same name as an item in the Rust prelude, such as `Result`.
+    PreludeShadowing,
+}
+
+impl Lint {
+    /// All the lints.
+    pub const ALL: &'static [Lint] = &[
+        Lint::MissingDescription,
+        Lint::EnumValueCase,
+        Lint::MutationName,
+        Lint::InputTypeSuffix,
+        Lint::ListNestingDepth,
+        Lint::DeprecatedWithoutReason,
+        Lint::UnusedType,
+        Lint::PreludeShadowing,
+    ];
+
+    /// The name used to configure the lint, such as `missing_description`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::MissingDescription => "missing_description",
+            Lint::EnumValueCase => "enum_value_case",
+            Lint::MutationName => "mutation_name",
+            Lint::InputTypeSuffix => "input_type_suffix",
+            Lint::ListNestingDepth => "list_nesting_depth",
+            Lint::DeprecatedWithoutReason => "deprecated_without_reason",
+            Lint::UnusedType => "unused_type",
+            Lint::PreludeShadowing => "prelude_shadowing",
+        }
+    }
+
+    /// The stable error code of the lint, such as `JFS1001`.
+    pub fn code(self) -> &'static str {
+        match self {
+            Lint::MissingDescription => "JFS1001",
+            Lint::EnumValueCase => "JFS1002",
+            Lint::MutationName => "JFS1003",
+            Lint::InputTypeSuffix => "JFS1004",
+            Lint::ListNestingDepth => "JFS1005",
+            Lint::DeprecatedWithoutReason => "JFS1006",
+            Lint::UnusedType => "JFS1007",
+            Lint::PreludeShadowing => "JFS1008",
+        }
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Lint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Lint::ALL
+            .iter()
+            .copied()
+            .find(|lint| lint.name() == s || lint.code() == s)
+            .ok_or_else(|| {
+                let names = Lint::ALL
+                    .iter()
+                    .map(|lint| format!("`{}`", lint))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("Unknown lint `{}`. Supported lints are {}", s, names)
+            })
+    }
+}
+
+/// What happens when a lint finds a problem.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum LintLevel {
+    /// Ignore the problem.
+    Allow,
+    /// Report the problem as a warning.
+    Warn,
+    /// Report the problem as an error.
+    Deny,
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LintLevel::Allow => write!(f, "allow"),
+            LintLevel::Warn => write!(f, "warn"),
+            LintLevel::Deny => write!(f, "deny"),
+        }
+    }
+}
+
+impl FromStr for LintLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(LintLevel::Allow),
+            "warn" => Ok(LintLevel::Warn),
+            "deny" => Ok(LintLevel::Deny),
+            other => Err(format!(
+                "Unknown lint level `{}`. Supported levels are `allow`, `warn`, and `deny`",
+                other
+            )),
+        }
+    }
+}
+
+const DEFAULT_MAX_LIST_NESTING_DEPTH: usize = 1;
+
+// Names in the Rust 2018 prelude that the generated types could shadow.
+const RUST_PRELUDE_NAMES: &[&str] = &[
+    "AsMut",
+    "AsRef",
+    "Box",
+    "Clone",
+    "Copy",
+    "Default",
+    "DoubleEndedIterator",
+    "Drop",
+    "Eq",
+    "Err",
+    "ExactSizeIterator",
+    "Extend",
+    "Fn",
+    "FnMut",
+    "FnOnce",
+    "From",
+    "Into",
+    "IntoIterator",
+    "Iterator",
+    "None",
+    "Ok",
+    "Option",
+    "Ord",
+    "PartialEq",
+    "PartialOrd",
+    "Result",
+    "Send",
+    "Sized",
+    "Some",
+    "String",
+    "Sync",
+    "ToOwned",
+    "ToString",
+    "Unpin",
+    "Vec",
+];
+
+/// The level of each lint. Lints that haven't been configured use their default level.
+#[derive(Debug, Clone, Default)]
+pub struct LintConfig {
+    levels: HashMap<Lint, LintLevel>,
+    max_list_nesting_depth: Option<usize>,
+}
+
+impl LintConfig {
+    pub fn set_level(&mut self, lint: Lint, level: LintLevel) {
+        self.levels.insert(lint, level);
+    }
+
+    pub fn set_max_list_nesting_depth(&mut self, depth: usize) {
+        self.max_list_nesting_depth = Some(depth);
+    }
+
+    // Every lint is allowed unless configured otherwise, so upgrading doesn't add warnings to
+    // schemas that used to compile cleanly
+    fn level(&self, lint: Lint) -> LintLevel {
+        self.levels.get(&lint).copied().unwrap_or(LintLevel::Allow)
+    }
+
+    fn max_list_nesting_depth(&self) -> usize {
+        self.max_list_nesting_depth
+            .unwrap_or(DEFAULT_MAX_LIST_NESTING_DEPTH)
+    }
+
+    // `self` takes precedence over `other`.
+    fn merged_over(&self, other: LintConfig) -> LintConfig {
+        let mut merged = other;
+        for (lint, level) in &self.levels {
+            merged.set_level(*lint, *level);
+        }
+        if let Some(depth) = self.max_list_nesting_depth {
+            merged.set_max_list_nesting_depth(depth);
+        }
+        merged
+    }
+}
+
+/// Runs the lints on a schema. Lints set to `deny` produce errors and lints set to `warn` produce
+/// warnings.
+pub struct LintPass<'doc> {
+    config: LintConfig,
+    mutation_type_name: Option<&'doc str>,
+    pub errors: BTreeSet<Error>,
+    pub warnings: BTreeSet<Error>,
+}
+
+impl<'doc> LintPass<'doc> {
+    /// Lints configured by the `@lints` directive on the schema definition are overridden by
+    /// `config`.
+    pub fn new(
+        config: &LintConfig,
+        doc: &'doc Document<'doc, &'doc str>,
+        mutation_type_name: Option<&'doc str>,
+    ) -> Self {
+        let mut errors = BTreeSet::new();
+        let schema_config = schema_lint_config(doc, &mut errors);
+
+        Self {
+            config: config.merged_over(schema_config),
+            mutation_type_name,
+            errors,
+            warnings: BTreeSet::new(),
+        }
+    }
+
+    fn lint(&mut self, lint: Lint, pos: Pos, message: String) {
+        let level = self.config.level(lint);
+        let kind = ErrorKind::Lint {
+            lint,
+            level,
+            message,
+        };
+
+        match level {
+            LintLevel::Allow => {}
+            LintLevel::Warn => self.warnings.emit_error(pos, kind),
+            LintLevel::Deny => self.errors.emit_error(pos, kind),
+        }
+    }
+
+    fn lint_description(&mut self, kind: &str, name: &str, description: &Option<String>, pos: Pos) {
+        if description.is_none() {
+            self.lint(
+                Lint::MissingDescription,
+                pos,
+                format!("{} `{}` doesn't have a description", kind, name),
+            );
+        }
+    }
+
+    fn lint_fields(&mut self, type_name: &str, fields: &'doc [Field<'doc, &'doc str>]) {
+        let is_mutation = self.mutation_type_name == Some(type_name);
+
+        for field in fields {
+            let path = format!("{}.{}", type_name, field.name);
+
+            self.lint_deprecation(&path, &field.directives);
+            self.lint_list_nesting(&path, &field.field_type, field.position);
+
+            for argument in &field.arguments {
+                let path = format!("{}({}:)", path, argument.name);
+                self.lint_list_nesting(&path, &argument.value_type, argument.position);
+            }
+
+            if is_mutation && field.name.to_snake_case().split('_').count() < 2 {
+                self.lint(
+                    Lint::MutationName,
+                    field.position,
+                    format!(
+                        "Mutation `{}` should be named verb+noun, such as `create{}`",
+                        field.name,
+                        capitalize(field.name)
+                    ),
+                );
+            }
+        }
+    }
+
+    fn lint_deprecation(&mut self, path: &str, directives: &[Directive<'doc, &'doc str>]) {
+        for directive in directives {
+            if directive.name == "deprecated"
+                && !directive.arguments.iter().any(|(key, _)| *key == "reason")
+            {
+                self.lint(
+                    Lint::DeprecatedWithoutReason,
+                    directive.position,
+                    format!("`{}` is deprecated without a reason", path),
+                );
+            }
+        }
+    }
+
+    fn lint_type_names(&mut self, doc: &'doc Document<'doc, &'doc str>) {
+        for (name, pos) in type_definitions(doc).map(type_definition_name) {
+            if RUST_PRELUDE_NAMES.contains(&name) {
+                self.lint(
+                    Lint::PreludeShadowing,
+                    pos,
+                    format!("Type `{}` shadows `{}` from the Rust prelude", name, name),
+                );
+            }
+        }
+    }
+
+    fn lint_unused_types(&mut self, doc: &'doc Document<'doc, &'doc str>) {
+        let roots = doc
+            .definitions
+            .iter()
+            .filter_map(|def| match def {
+                Definition::SchemaDefinition(schema_def) => Some(schema_def),
+                _ => None,
+            })
+            .flat_map(|schema_def| {
+                vec![
+                    schema_def.query,
+                    schema_def.mutation,
+                    schema_def.subscription,
+                ]
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+
+        if roots.is_empty() {
+            return;
+        }
+
+        // Entities of federation subgraphs are reachable through `Query._entities`
+        let entities = type_definitions(doc).filter_map(|def| match def {
+            TypeDefinition::Object(obj) if obj.directives.iter().any(|dir| dir.name == "key") => {
+                Some(obj.name)
+            }
+            _ => None,
+        });
+        let roots = roots.into_iter().chain(entities).collect();
+
+        let reachable = reachable_types(doc, roots);
+
+        for (name, pos) in type_definitions(doc).map(type_definition_name) {
+            if !reachable.contains(name) {
+                self.lint(
+                    Lint::UnusedType,
+                    pos,
+                    format!(
+                        "Type `{}` is never used, it isn't reachable from the root types",
+                        name
+                    ),
+                );
+            }
+        }
+    }
+
+    fn lint_list_nesting(&mut self, path: &str, type_: &Type<'doc, &'doc str>, pos: Pos) {
+        let depth = list_nesting_depth(type_);
+        let max = self.config.max_list_nesting_depth();
+
+        if depth > max {
+            self.lint(
+                Lint::ListNestingDepth,
+                pos,
+                format!(
+                    "`{}` has lists nested {} levels deep, the maximum is {}",
+                    path, depth, max
+                ),
+            );
+        }
+    }
+}
+
+impl<'doc> SchemaVisitor<'doc> for LintPass<'doc> {
+    fn visit_document(&mut self, doc: &'doc Document<'doc, &'doc str>) {
+        self.lint_type_names(doc);
+        self.lint_unused_types(doc);
+    }
+
+    fn visit_scalar_type(&mut self, ty: &'doc schema::ScalarType<'doc, &'doc str>) {
+        self.lint_description("Scalar", ty.name, &ty.description, ty.position);
+    }
+
+    fn visit_object_type(&mut self, ty: &'doc schema::ObjectType<'doc, &'doc str>) {
+        self.lint_description("Type", ty.name, &ty.description, ty.position);
+        self.lint_fields(ty.name, &ty.fields);
+    }
+
+    fn visit_interface_type(&mut self, ty: &'doc schema::InterfaceType<'doc, &'doc str>) {
+        self.lint_description("Interface", ty.name, &ty.description, ty.position);
+        self.lint_fields(ty.name, &ty.fields);
+    }
+
+    fn visit_union_type(&mut self, ty: &'doc schema::UnionType<'doc, &'doc str>) {
+        self.lint_description("Union", ty.name, &ty.description, ty.position);
+    }
+
+    fn visit_enum_type(&mut self, ty: &'doc schema::EnumType<'doc, &'doc str>) {
+        self.lint_description("Enum", ty.name, &ty.description, ty.position);
+
+        for value in &ty.values {
+            let path = format!("{}.{}", ty.name, value.name);
+
+            self.lint_deprecation(&path, &value.directives);
+
+            let expected = value.name.to_shouty_snake_case();
+            if value.name != expected {
+                self.lint(
+                    Lint::EnumValueCase,
+                    value.position,
+                    format!(
+                        "Enum value `{}` should be in SCREAMING_SNAKE_CASE: `{}`",
+                        path, expected
+                    ),
+                );
+            }
+        }
+    }
+
+    fn visit_input_object_type(&mut self, ty: &'doc schema::InputObjectType<'doc, &'doc str>) {
+        self.lint_description("Input type", ty.name, &ty.description, ty.position);
+
+        if !ty.name.ends_with("Input") {
+            self.lint(
+                Lint::InputTypeSuffix,
+                ty.position,
+                format!(
+                    "Input type `{}` should end with `Input`, such as `{}Input`",
+                    ty.name, ty.name
+                ),
+            );
+        }
+
+        for field in &ty.fields {
+            let path = format!("{}.{}", ty.name, field.name);
+            self.lint_list_nesting(&path, &field.value_type, field.position);
+        }
+    }
+}
+
+// Read the lint config from `schema @lints(deny: ["..."], warn: ["..."], allow: ["..."])`.
+fn schema_lint_config<'doc>(
+    doc: &'doc Document<'doc, &'doc str>,
+    errors: &mut BTreeSet<Error>,
+) -> LintConfig {
+    let mut config = LintConfig::default();
+
+    let directives = doc.definitions.iter().flat_map(|def| match def {
+        Definition::SchemaDefinition(schema_def) => schema_def.directives.iter(),
+        _ => [].iter(),
+    });
+
+    for directive in directives.filter(|directive| directive.name == "lints") {
+        for (key, value) in &directive.arguments {
+            if let Err(message) = apply_lint_argument(&mut config, key, value) {
+                errors.emit_error(directive.position, ErrorKind::InvalidLintConfig(message));
+            }
+        }
+    }
+
+    config
+}
+
+fn apply_lint_argument<'doc>(
+    config: &mut LintConfig,
+    key: &str,
+    value: &Value<'doc, &'doc str>,
+) -> Result<(), String> {
+    if key == "max_list_nesting_depth" {
+        let depth = match value {
+            Value::Int(depth) => depth.as_i64().filter(|depth| *depth >= 0),
+            _ => None,
+        };
+        let depth = depth.ok_or_else(|| {
+            format!(
+                "`max_list_nesting_depth` must be a non-negative integer, got `{}`",
+                value
+            )
+        })?;
+        config.set_max_list_nesting_depth(depth as usize);
+        return Ok(());
+    }
+
+    let level = key.parse::<LintLevel>().map_err(|_| {
+        format!(
+            "Unknown argument `{}`. Supported arguments are `allow`, `warn`, `deny`, and `max_list_nesting_depth`",
+            key
+        )
+    })?;
+
+    let names = match value {
+        Value::List(names) => names,
+        other => {
+            return Err(format!(
+                "`{}` must be a list of strings, got `{}`",
+                key, other
+            ))
+        }
+    };
+
+    for name in names {
+        let name = match name {
+            Value::String(name) => name,
+            other => return Err(format!("Expected a lint name, got `{}`", other)),
+        };
+        config.set_level(name.parse()?, level);
+    }
+
+    Ok(())
+}
+
+fn type_definitions<'doc>(
+    doc: &'doc Document<'doc, &'doc str>,
+) -> impl Iterator<Item = &'doc TypeDefinition<'doc, &'doc str>> {
+    doc.definitions.iter().filter_map(|def| match def {
+        Definition::TypeDefinition(type_def) => Some(type_def),
+        _ => None,
+    })
+}
+
+fn type_definition_name<'doc>(type_def: &'doc TypeDefinition<'doc, &'doc str>) -> (&'doc str, Pos) {
+    match type_def {
+        TypeDefinition::Scalar(ty) => (ty.name, ty.position),
+        TypeDefinition::Object(ty) => (ty.name, ty.position),
+        TypeDefinition::Interface(ty) => (ty.name, ty.position),
+        TypeDefinition::Union(ty) => (ty.name, ty.position),
+        TypeDefinition::Enum(ty) => (ty.name, ty.position),
+        TypeDefinition::InputObject(ty) => (ty.name, ty.position),
+    }
+}
+
+// The names of the types reachable from `roots` through fields, arguments, interfaces, and union
+// members. Implementors of a reachable interface are reachable as well.
+fn reachable_types<'doc>(
+    doc: &'doc Document<'doc, &'doc str>,
+    roots: Vec<&'doc str>,
+) -> HashSet<&'doc str> {
+    let mut references = HashMap::<&str, Vec<&str>>::new();
+    let mut implements = Vec::new();
+
+    for type_def in type_definitions(doc) {
+        let (name, names) = match type_def {
+            TypeDefinition::Object(ty) => {
+                for interface in &ty.implements_interfaces {
+                    implements.push((ty.name, *interface));
+                }
+                (ty.name, field_type_names(&ty.fields))
+            }
+            TypeDefinition::Interface(ty) => (ty.name, field_type_names(&ty.fields)),
+            TypeDefinition::Union(ty) => (ty.name, ty.types.clone()),
+            TypeDefinition::InputObject(ty) => (
+                ty.name,
+                ty.fields
+                    .iter()
+                    .map(|field| type_name(&field.value_type))
+                    .collect(),
+            ),
+            TypeDefinition::Scalar(_) | TypeDefinition::Enum(_) => continue,
+        };
+        references.entry(name).or_default().extend(names);
+    }
+
+    for (object, interface) in implements {
+        let entry = references.entry(interface).or_default();
+        entry.push(object);
+        references.entry(object).or_default().push(interface);
+    }
+
+    let mut reachable = HashSet::new();
+    let mut queue = roots;
+    while let Some(name) = queue.pop() {
+        if reachable.insert(name) {
+            if let Some(names) = references.get(name) {
+                queue.extend(names.iter().copied());
+            }
+        }
+    }
+    reachable
+}
+
+fn field_type_names<'doc>(fields: &'doc [Field<'doc, &'doc str>]) -> Vec<&'doc str> {
+    fields
+        .iter()
+        .flat_map(|field| {
+            std::iter::once(type_name(&field.field_type)).chain(
+                field
+                    .arguments
+                    .iter()
+                    .map(|argument| type_name(&argument.value_type)),
+            )
+        })
+        .collect()
+}
+
+fn list_nesting_depth<'doc>(type_: &Type<'doc, &'doc str>) -> usize {
+    match type_ {
+        Type::NamedType(_) => 0,
+        Type::NonNullType(inner) => list_nesting_depth(inner),
+        Type::ListType(inner) => 1 + list_nesting_depth(inner),
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::CodeGen;
+
+    fn generate(schema: &str, lints: &[(Lint, LintLevel)]) -> Result<Vec<String>, String> {
+        let mut builder = CodeGen::build_from_schema_literal(schema.to_string());
+        for (lint, level) in lints {
+            builder = builder.lint(*lint, *level);
+        }
+
+        builder
+            .finish()
+            .generate_code_with_warnings()
+            .map(|(_, warnings)| warnings.iter().collect())
+            .map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn test_denied_lints_are_errors() {
+        let schema = r#"
+            schema {
+              query: Query
+              mutation: Mutation
+            }
+
+            "Root query"
+            type Query {
+              matrix: [[Int!]!]! @juniper(ownership: "owned")
+            }
+
+            "Root mutation"
+            type Mutation {
+              user(filter: UserFilter!): Boolean! @juniper(ownership: "owned")
+            }
+
+            input UserFilter {
+              status: Status
+            }
+
+            "Status"
+            enum Status {
+              Active
+            }
+        "#;
+
+        let lints = Lint::ALL
+            .iter()
+            .map(|lint| (*lint, LintLevel::Deny))
+            .collect::<Vec<_>>();
+        let error = generate(schema, &lints).unwrap_err();
+
+        assert!(error.contains("Input type `UserFilter` doesn't have a description"));
+        assert!(error
+            .contains("Enum value `Status.Active` should be in SCREAMING_SNAKE_CASE: `ACTIVE`"));
+        assert!(error.contains("Mutation `user` should be named verb+noun, such as `createUser`"));
+        assert!(error.contains(
+            "Input type `UserFilter` should end with `Input`, such as `UserFilterInput`"
+        ));
+        assert!(error.contains("`Query.matrix` has lists nested 2 levels deep, the maximum is 1"));
+        assert!(error.contains("The `missing_description` lint is set to `deny`"));
+        assert!(error.contains("aborting due to 5 errors"));
+    }
+
+    #[test]
+    fn test_deprecated_without_reason() {
+        let schema = r#"
+            schema {
+              query: Query
+            }
+
+            type Query {
+              old: Boolean! @deprecated
+              new: Boolean! @deprecated(reason: "Use `newer`")
+            }
+        "#;
+
+        // Allowed by default, so upgrading doesn't add warnings
+        assert!(generate(schema, &[]).unwrap().is_empty());
+
+        let warnings =
+            generate(schema, &[(Lint::DeprecatedWithoutReason, LintLevel::Warn)]).unwrap();
+
+        assert_eq!(1, warnings.len());
+        assert!(
+            warnings[0].contains("warning[JFS1006]: `Query.old` is deprecated without a reason")
+        );
+        assert!(warnings[0].contains("--> schema:7:29"));
+    }
+
+    #[test]
+    fn test_unused_and_shadowing_types() {
+        let schema = r#"
+            schema {
+              query: Query
+            }
+
+            type Query {
+              node: Node! @juniper(ownership: "owned")
+            }
+
+            interface Node {
+              id: ID!
+            }
+
+            type User implements Node {
+              id: ID!
+              result(filter: Filter): Result! @juniper(ownership: "owned")
+            }
+
+            input Filter {
+              id: ID
+            }
+
+            type Result {
+              ok: Boolean!
+            }
+
+            type Orphan {
+              id: ID!
+            }
+        "#;
+
+        assert!(generate(schema, &[]).unwrap().is_empty());
+
+        let warnings = generate(
+            schema,
+            &[
+                (Lint::UnusedType, LintLevel::Warn),
+                (Lint::PreludeShadowing, LintLevel::Warn),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(2, warnings.len());
+        assert!(warnings[0].contains("Type `Result` shadows `Result` from the Rust prelude"));
+        assert!(warnings[0].contains("The `prelude_shadowing` lint is set to `warn`"));
+        assert!(warnings[1]
+            .contains("Type `Orphan` is never used, it isn't reachable from the root types"));
+    }
+
+    #[test]
+    fn test_lints_configured_in_schema() {
+        let schema = r#"
+            schema @lints(deny: ["input_type_suffix"], allow: ["deprecated_without_reason"]) {
+              query: Query
+            }
+
+            type Query {
+              old(filter: Filter): Boolean! @deprecated
+            }
+
+            input Filter {
+              id: ID
+            }
+        "#;
+
+        let error = generate(schema, &[]).unwrap_err();
+        assert!(error.contains("Input type `Filter` should end with `Input`"));
+        assert!(!error.contains("deprecated without a reason"));
+
+        // The builder takes precedence over the schema
+        let warnings = generate(schema, &[(Lint::InputTypeSuffix, LintLevel::Warn)]).unwrap();
+        assert_eq!(1, warnings.len());
+        assert!(warnings[0].contains("Input type `Filter` should end with `Input`"));
+    }
+
+    #[test]
+    fn test_invalid_lint_directive() {
+        let error = generate(
+            r#"
+            schema @lints(deny: ["missing_docs"], max_list_nesting_depth: "two") {
+              query: Query
+            }
+
+            type Query {
+              ping: Boolean!
+            }
+            "#,
+            &[],
+        )
+        .unwrap_err();
+
+        assert!(error.contains("Invalid `@lints` directive: Unknown lint `missing_docs`"));
+        assert!(error.contains("`max_list_nesting_depth` must be a non-negative integer"));
+    }
+}
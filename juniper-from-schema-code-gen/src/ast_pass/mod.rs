@@ -1,6 +1,12 @@
+pub mod client_pass;
 pub mod code_gen_pass;
+pub mod connections;
 pub mod directive_parsing;
 pub mod error;
+pub mod error_codes;
+pub mod federation;
+pub mod lints;
+pub mod schema_diff;
 pub mod schema_visitor;
 pub mod validations;
 
@@ -41,12 +47,19 @@ impl EmitError for BTreeSet<Error> {
 pub struct AstData<'doc> {
     interface_implementors: HashMap<&'doc str, Vec<&'doc str>>,
     user_scalars: HashSet<&'doc str>,
-    enum_types: HashSet<&'doc str>,
+    enum_types: HashMap<&'doc str, Vec<&'doc str>>,
     union_types: HashSet<&'doc str>,
-    input_object_field_types: HashMap<&'doc str, HashMap<&'doc str, &'doc Type<'doc, &'doc str>>>,
+    input_object_field_types:
+        HashMap<&'doc str, HashMap<&'doc str, &'doc InputValue<'doc, &'doc str>>>,
     errors: BTreeSet<Error>,
     include_time_zone_on_date_time_scalar: bool,
     subscription_type_name: Option<&'doc str>,
+    query_type_name: Option<&'doc str>,
+    mutation_type_name: Option<&'doc str>,
+    output_fields: HashMap<&'doc str, Vec<&'doc Field<'doc, &'doc str>>>,
+    union_members: HashMap<&'doc str, Vec<&'doc str>>,
+    node_interface: Option<&'doc str>,
+    entities: Vec<&'doc str>,
 }
 
 impl<'doc> SchemaVisitor<'doc> for AstData<'doc> {
@@ -57,6 +70,23 @@ impl<'doc> SchemaVisitor<'doc> for AstData<'doc> {
                 .or_insert_with(Vec::new)
                 .push(&obj.name);
         }
+
+        self.output_fields
+            .insert(obj.name, obj.fields.iter().collect());
+
+        // The `@key` directives themselves are validated by `CodeGenPass`
+        if obj.directives.iter().any(|dir| dir.name == "key") {
+            self.entities.push(obj.name);
+        }
+    }
+
+    fn visit_interface_type(&mut self, interface: &'doc InterfaceType<'doc, &'doc str>) {
+        self.output_fields
+            .insert(interface.name, interface.fields.iter().collect());
+
+        if self.parse_directives(interface).value && self.node_interface.is_none() {
+            self.node_interface = Some(interface.name);
+        }
     }
 
     fn visit_scalar_type(&mut self, scalar: &'doc ScalarType<'doc, &'doc str>) {
@@ -77,11 +107,16 @@ impl<'doc> SchemaVisitor<'doc> for AstData<'doc> {
     }
 
     fn visit_enum_type(&mut self, enum_type: &'doc EnumType<'doc, &'doc str>) {
-        self.enum_types.insert(&enum_type.name);
+        self.enum_types.insert(
+            enum_type.name,
+            enum_type.values.iter().map(|value| value.name).collect(),
+        );
     }
 
     fn visit_union_type(&mut self, union_type: &'doc UnionType<'doc, &'doc str>) {
         self.union_types.insert(&union_type.name);
+        self.union_members
+            .insert(union_type.name, union_type.types.to_vec());
     }
 
     fn visit_input_object_type(&mut self, input_type: &'doc InputObjectType<'doc, &'doc str>) {
@@ -89,7 +124,7 @@ impl<'doc> SchemaVisitor<'doc> for AstData<'doc> {
             self.input_object_field_types
                 .entry(&input_type.name)
                 .or_insert_with(HashMap::new)
-                .insert(&field.name, &field.value_type);
+                .insert(&field.name, field);
         }
     }
 
@@ -97,6 +132,8 @@ impl<'doc> SchemaVisitor<'doc> for AstData<'doc> {
         if let Some(subscription_type_name) = node.subscription {
             self.subscription_type_name = Some(subscription_type_name);
         }
+        self.query_type_name = node.query;
+        self.mutation_type_name = node.mutation;
     }
 }
 
@@ -122,6 +159,12 @@ impl<'doc> AstData<'doc> {
             errors: Default::default(),
             include_time_zone_on_date_time_scalar: true,
             subscription_type_name: None,
+            query_type_name: None,
+            mutation_type_name: None,
+            output_fields: Default::default(),
+            union_members: Default::default(),
+            node_interface: None,
+            entities: Vec::new(),
         }
     }
 
@@ -162,7 +205,7 @@ impl<'doc> AstData<'doc> {
     }
 
     pub fn is_enum_type(&self, name: &str) -> bool {
-        self.enum_types.contains(name)
+        self.enum_types.contains_key(name)
     }
 
     pub fn is_union_type(&self, name: &str) -> bool {
@@ -182,8 +225,8 @@ impl<'doc> AstData<'doc> {
         use graphql_parser::query::Type::*;
 
         let field_map = self.input_object_field_types.get(input_type_name)?;
-        let type_ = field_map.get(field_name)?;
-        match type_ {
+        let field = field_map.get(field_name)?;
+        match field.value_type {
             NamedType(_) => Some(true),
             ListType(_) => Some(true),
             NonNullType(_) => Some(false),
@@ -208,8 +251,8 @@ impl<'doc> AstData<'doc> {
         field_name: &'doc str,
     ) -> Option<&'doc str> {
         let field_map = &self.input_object_field_types.get(input_type_name)?;
-        let type_ = field_map.get(field_name)?;
-        Some(type_name(&type_))
+        let field = field_map.get(field_name)?;
+        Some(type_name(&field.value_type))
     }
 
     pub fn is_subscription_type(&self, name: &'doc str) -> bool {
@@ -217,6 +260,61 @@ impl<'doc> AstData<'doc> {
             .map(|s| s == name)
             .unwrap_or(false)
     }
+
+    pub fn query_type_name(&self) -> Option<&'doc str> {
+        self.query_type_name
+    }
+
+    pub fn mutation_type_name(&self) -> Option<&'doc str> {
+        self.mutation_type_name
+    }
+
+    pub fn subscription_type_name(&self) -> Option<&'doc str> {
+        self.subscription_type_name
+    }
+
+    /// The interface marked with `@juniper(node_interface: true)`.
+    pub fn node_interface(&self) -> Option<&'doc str> {
+        self.node_interface
+    }
+
+    /// The Apollo Federation entities, the object types with a `@key` directive.
+    pub fn entities(&self) -> &[&'doc str] {
+        &self.entities
+    }
+
+    /// The fields of an object or interface type.
+    pub fn output_fields(&self, type_name: &str) -> Option<&Vec<&'doc Field<'doc, &'doc str>>> {
+        self.output_fields.get(type_name)
+    }
+
+    pub fn enum_values(&self, name: &str) -> Option<&Vec<&'doc str>> {
+        self.enum_types.get(name)
+    }
+
+    pub fn input_object_fields(
+        &self,
+        name: &str,
+    ) -> Option<&HashMap<&'doc str, &'doc InputValue<'doc, &'doc str>>> {
+        self.input_object_field_types.get(name)
+    }
+
+    pub fn is_input_object_type(&self, name: &str) -> bool {
+        self.input_object_field_types.contains_key(name)
+    }
+
+    /// The object types a value of type `type_name` can have at runtime.
+    pub fn possible_types(&self, type_name: &str) -> Vec<&'doc str> {
+        if let Some(members) = self.union_members.get(type_name) {
+            members.clone()
+        } else if let Some(implementors) = self.interface_implementors.get(type_name) {
+            implementors.clone()
+        } else if let Some((name, _)) = self.output_fields.get_key_value(type_name) {
+            vec![*name]
+        } else {
+            Vec::new()
+        }
+    }
 }
 
 impl<'doc> EmitError for AstData<'doc> {
@@ -0,0 +1,958 @@
+use super::{
+    error::write_diagnostic,
+    schema_visitor::{visit_document, SchemaVisitor},
+};
+use colored::*;
+use graphql_parser::{schema::*, Pos};
+use std::{collections::HashMap, fmt};
+
+/// How a change affects existing clients.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Severity {
+    /// Operations that were valid against the old schema might fail against the new one.
+    Breaking,
+    /// Existing operations remain valid, but clients might not handle the new responses. For
+    /// example a new enum value.
+    Dangerous,
+    /// Existing operations and clients are unaffected.
+    Safe,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Breaking => write!(f, "breaking"),
+            Severity::Dangerous => write!(f, "dangerous"),
+            Severity::Safe => write!(f, "safe"),
+        }
+    }
+}
+
+/// A single difference between two schemas.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Change {
+    severity: Severity,
+    location: Location,
+    pos: Pos,
+    message: String,
+}
+
+// Which schema a change points into. Removals point into the old schema, everything else into
+// the new one.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+enum Location {
+    Old,
+    New,
+}
+
+impl Change {
+    /// How the change affects existing clients.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// A description of the change, such as "Field `User.name` was removed".
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// The changes between two versions of a schema.
+#[derive(Debug)]
+pub struct SchemaDiff {
+    changes: Vec<Change>,
+    old_schema: String,
+    new_schema: String,
+}
+
+impl SchemaDiff {
+    /// All the changes, ordered with the most severe first.
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Whether any of the changes are breaking.
+    pub fn is_breaking(&self) -> bool {
+        self.changes
+            .iter()
+            .any(|change| change.severity == Severity::Breaking)
+    }
+}
+
+impl fmt::Display for SchemaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, change) in self.changes.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+
+            let level = match change.severity {
+                Severity::Breaking => "breaking".bright_red(),
+                Severity::Dangerous => "dangerous".yellow(),
+                Severity::Safe => "safe".green(),
+            };
+
+            let (source, source_name) = match change.location {
+                Location::Old => (&self.old_schema, "old schema"),
+                Location::New => (&self.new_schema, "new schema"),
+            };
+
+            write_diagnostic(
+                f,
+                level,
+                &change.message,
+                None,
+                change.pos,
+                source,
+                source_name,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn diff_schemas<'doc>(
+    old_doc: &'doc Document<'doc, &'doc str>,
+    new_doc: &'doc Document<'doc, &'doc str>,
+    old_schema: String,
+    new_schema: String,
+) -> SchemaDiff {
+    let old = SchemaIndex::new(old_doc);
+    let new = SchemaIndex::new(new_doc);
+
+    let mut diff = Diff {
+        changes: Vec::new(),
+    };
+    diff.diff_roots(&old, &new);
+    diff.diff_types(&old, &new);
+
+    let mut changes = diff.changes;
+    changes.sort();
+
+    SchemaDiff {
+        changes,
+        old_schema,
+        new_schema,
+    }
+}
+
+// The type definitions of a schema by name.
+struct SchemaIndex<'doc> {
+    schema_definition: Option<&'doc SchemaDefinition<'doc, &'doc str>>,
+    types: HashMap<&'doc str, &'doc TypeDefinition<'doc, &'doc str>>,
+}
+
+impl<'doc> SchemaIndex<'doc> {
+    fn new(doc: &'doc Document<'doc, &'doc str>) -> Self {
+        let mut index = SchemaIndex {
+            schema_definition: None,
+            types: HashMap::new(),
+        };
+        visit_document(&mut index, doc);
+        index
+    }
+
+    fn root(&self, operation: RootOperation) -> Option<&'doc str> {
+        let schema_definition = self.schema_definition?;
+        match operation {
+            RootOperation::Query => schema_definition.query,
+            RootOperation::Mutation => schema_definition.mutation,
+            RootOperation::Subscription => schema_definition.subscription,
+        }
+    }
+
+    fn root_pos(&self) -> Pos {
+        self.schema_definition
+            .map(|schema_definition| schema_definition.position)
+            .unwrap_or_default()
+    }
+}
+
+impl<'doc> SchemaVisitor<'doc> for SchemaIndex<'doc> {
+    fn visit_schema_definition(&mut self, node: &'doc SchemaDefinition<'doc, &'doc str>) {
+        self.schema_definition = Some(node);
+    }
+
+    fn visit_type_definition(&mut self, type_definition: &'doc TypeDefinition<'doc, &'doc str>) {
+        self.types
+            .insert(type_definition_name(type_definition), type_definition);
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum RootOperation {
+    Query,
+    Mutation,
+    Subscription,
+}
+
+impl fmt::Display for RootOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RootOperation::Query => write!(f, "query"),
+            RootOperation::Mutation => write!(f, "mutation"),
+            RootOperation::Subscription => write!(f, "subscription"),
+        }
+    }
+}
+
+struct Diff {
+    changes: Vec<Change>,
+}
+
+impl Diff {
+    fn change(&mut self, severity: Severity, location: Location, pos: Pos, message: String) {
+        self.changes.push(Change {
+            severity,
+            location,
+            pos,
+            message,
+        });
+    }
+
+    fn diff_roots<'doc>(&mut self, old: &SchemaIndex<'doc>, new: &SchemaIndex<'doc>) {
+        for operation in &[
+            RootOperation::Query,
+            RootOperation::Mutation,
+            RootOperation::Subscription,
+        ] {
+            match (old.root(*operation), new.root(*operation)) {
+                (Some(old_root), Some(new_root)) if old_root != new_root => self.change(
+                    Severity::Breaking,
+                    Location::New,
+                    new.root_pos(),
+                    format!(
+                        "The {} type changed from `{}` to `{}`",
+                        operation, old_root, new_root
+                    ),
+                ),
+                (Some(old_root), None) => self.change(
+                    Severity::Breaking,
+                    Location::Old,
+                    old.root_pos(),
+                    format!("The {} type `{}` was removed", operation, old_root),
+                ),
+                (None, Some(new_root)) => self.change(
+                    Severity::Safe,
+                    Location::New,
+                    new.root_pos(),
+                    format!("The {} type `{}` was added", operation, new_root),
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    fn diff_types<'doc>(&mut self, old: &SchemaIndex<'doc>, new: &SchemaIndex<'doc>) {
+        for (name, old_type) in &old.types {
+            let new_type = match new.types.get(name) {
+                Some(new_type) => new_type,
+                None => {
+                    self.change(
+                        Severity::Breaking,
+                        Location::Old,
+                        type_definition_pos(old_type),
+                        format!("Type `{}` was removed", name),
+                    );
+                    continue;
+                }
+            };
+
+            match (old_type, new_type) {
+                (TypeDefinition::Object(old_obj), TypeDefinition::Object(new_obj)) => {
+                    self.diff_interfaces(
+                        name,
+                        &old_obj.implements_interfaces,
+                        &new_obj.implements_interfaces,
+                        old_obj.position,
+                        new_obj.position,
+                    );
+                    self.diff_fields(name, &old_obj.fields, &new_obj.fields);
+                }
+                (
+                    TypeDefinition::Interface(old_interface),
+                    TypeDefinition::Interface(new_interface),
+                ) => {
+                    self.diff_fields(name, &old_interface.fields, &new_interface.fields);
+                }
+                (TypeDefinition::Union(old_union), TypeDefinition::Union(new_union)) => {
+                    self.diff_union(old_union, new_union);
+                }
+                (TypeDefinition::Enum(old_enum), TypeDefinition::Enum(new_enum)) => {
+                    self.diff_enum(old_enum, new_enum);
+                }
+                (
+                    TypeDefinition::InputObject(old_input),
+                    TypeDefinition::InputObject(new_input),
+                ) => {
+                    self.diff_input_fields(name, &old_input.fields, &new_input.fields);
+                }
+                (TypeDefinition::Scalar(_), TypeDefinition::Scalar(_)) => {}
+                (old_type, new_type) => self.change(
+                    Severity::Breaking,
+                    Location::New,
+                    type_definition_pos(new_type),
+                    format!(
+                        "Type `{}` changed from {} to {}",
+                        name,
+                        type_definition_kind(old_type),
+                        type_definition_kind(new_type),
+                    ),
+                ),
+            }
+        }
+
+        for (name, new_type) in &new.types {
+            if !old.types.contains_key(name) {
+                self.change(
+                    Severity::Safe,
+                    Location::New,
+                    type_definition_pos(new_type),
+                    format!("Type `{}` was added", name),
+                );
+            }
+        }
+    }
+
+    fn diff_interfaces(
+        &mut self,
+        type_name: &str,
+        old_interfaces: &[&str],
+        new_interfaces: &[&str],
+        old_pos: Pos,
+        new_pos: Pos,
+    ) {
+        for interface in old_interfaces {
+            if !new_interfaces.contains(interface) {
+                self.change(
+                    Severity::Breaking,
+                    Location::Old,
+                    old_pos,
+                    format!(
+                        "`{}` no longer implements interface `{}`",
+                        type_name, interface
+                    ),
+                );
+            }
+        }
+
+        for interface in new_interfaces {
+            if !old_interfaces.contains(interface) {
+                self.change(
+                    Severity::Dangerous,
+                    Location::New,
+                    new_pos,
+                    format!("`{}` now implements interface `{}`", type_name, interface),
+                );
+            }
+        }
+    }
+
+    fn diff_fields<'doc>(
+        &mut self,
+        type_name: &str,
+        old_fields: &[Field<'doc, &'doc str>],
+        new_fields: &[Field<'doc, &'doc str>],
+    ) {
+        for old_field in old_fields {
+            let new_field = match new_fields.iter().find(|field| field.name == old_field.name) {
+                Some(new_field) => new_field,
+                None => {
+                    self.change(
+                        Severity::Breaking,
+                        Location::Old,
+                        old_field.position,
+                        format!("Field `{}.{}` was removed", type_name, old_field.name),
+                    );
+                    continue;
+                }
+            };
+
+            if old_field.field_type != new_field.field_type {
+                let severity =
+                    if is_safe_output_type_change(&old_field.field_type, &new_field.field_type) {
+                        Severity::Safe
+                    } else {
+                        Severity::Breaking
+                    };
+                self.change(
+                    severity,
+                    Location::New,
+                    new_field.position,
+                    format!(
+                        "Field `{}.{}` changed type from `{}` to `{}`",
+                        type_name, old_field.name, old_field.field_type, new_field.field_type
+                    ),
+                );
+            }
+
+            if !is_deprecated(&old_field.directives) && is_deprecated(&new_field.directives) {
+                self.change(
+                    Severity::Safe,
+                    Location::New,
+                    new_field.position,
+                    format!("Field `{}.{}` was deprecated", type_name, old_field.name),
+                );
+            }
+
+            let field_path = format!("{}.{}", type_name, old_field.name);
+            self.diff_arguments(&field_path, &old_field.arguments, &new_field.arguments);
+        }
+
+        for new_field in new_fields {
+            if !old_fields.iter().any(|field| field.name == new_field.name) {
+                self.change(
+                    Severity::Safe,
+                    Location::New,
+                    new_field.position,
+                    format!("Field `{}.{}` was added", type_name, new_field.name),
+                );
+            }
+        }
+    }
+
+    fn diff_arguments<'doc>(
+        &mut self,
+        field_path: &str,
+        old_arguments: &[InputValue<'doc, &'doc str>],
+        new_arguments: &[InputValue<'doc, &'doc str>],
+    ) {
+        for old_argument in old_arguments {
+            let new_argument = match new_arguments
+                .iter()
+                .find(|argument| argument.name == old_argument.name)
+            {
+                Some(new_argument) => new_argument,
+                None => {
+                    self.change(
+                        Severity::Breaking,
+                        Location::Old,
+                        old_argument.position,
+                        format!(
+                            "Argument `{}` was removed from `{}`",
+                            old_argument.name, field_path
+                        ),
+                    );
+                    continue;
+                }
+            };
+
+            self.diff_input_value(
+                &format!("Argument `{}` of `{}`", old_argument.name, field_path),
+                old_argument,
+                new_argument,
+            );
+        }
+
+        for new_argument in new_arguments {
+            if old_arguments
+                .iter()
+                .any(|argument| argument.name == new_argument.name)
+            {
+                continue;
+            }
+
+            if is_required(new_argument) {
+                self.change(
+                    Severity::Breaking,
+                    Location::New,
+                    new_argument.position,
+                    format!(
+                        "Required argument `{}` was added to `{}`",
+                        new_argument.name, field_path
+                    ),
+                );
+            } else {
+                self.change(
+                    Severity::Dangerous,
+                    Location::New,
+                    new_argument.position,
+                    format!(
+                        "Optional argument `{}` was added to `{}`",
+                        new_argument.name, field_path
+                    ),
+                );
+            }
+        }
+    }
+
+    fn diff_input_fields<'doc>(
+        &mut self,
+        type_name: &str,
+        old_fields: &[InputValue<'doc, &'doc str>],
+        new_fields: &[InputValue<'doc, &'doc str>],
+    ) {
+        for old_field in old_fields {
+            let new_field = match new_fields.iter().find(|field| field.name == old_field.name) {
+                Some(new_field) => new_field,
+                None => {
+                    self.change(
+                        Severity::Breaking,
+                        Location::Old,
+                        old_field.position,
+                        format!("Input field `{}.{}` was removed", type_name, old_field.name),
+                    );
+                    continue;
+                }
+            };
+
+            self.diff_input_value(
+                &format!("Input field `{}.{}`", type_name, old_field.name),
+                old_field,
+                new_field,
+            );
+        }
+
+        for new_field in new_fields {
+            if old_fields.iter().any(|field| field.name == new_field.name) {
+                continue;
+            }
+
+            if is_required(new_field) {
+                self.change(
+                    Severity::Breaking,
+                    Location::New,
+                    new_field.position,
+                    format!(
+                        "Required input field `{}.{}` was added",
+                        type_name, new_field.name
+                    ),
+                );
+            } else {
+                self.change(
+                    Severity::Dangerous,
+                    Location::New,
+                    new_field.position,
+                    format!(
+                        "Optional input field `{}.{}` was added",
+                        type_name, new_field.name
+                    ),
+                );
+            }
+        }
+    }
+
+    // Arguments and input fields are both input values and change in the same ways.
+    fn diff_input_value<'doc>(
+        &mut self,
+        subject: &str,
+        old_value: &InputValue<'doc, &'doc str>,
+        new_value: &InputValue<'doc, &'doc str>,
+    ) {
+        if old_value.value_type != new_value.value_type {
+            let severity =
+                if is_safe_input_type_change(&old_value.value_type, &new_value.value_type) {
+                    Severity::Safe
+                } else {
+                    Severity::Breaking
+                };
+            self.change(
+                severity,
+                Location::New,
+                new_value.position,
+                format!(
+                    "{} changed type from `{}` to `{}`",
+                    subject, old_value.value_type, new_value.value_type
+                ),
+            );
+        }
+
+        if old_value.default_value != new_value.default_value {
+            self.change(
+                Severity::Dangerous,
+                Location::New,
+                new_value.position,
+                format!(
+                    "{} changed default value from `{}` to `{}`",
+                    subject,
+                    default_value_string(old_value),
+                    default_value_string(new_value)
+                ),
+            );
+        }
+    }
+
+    fn diff_union<'doc>(
+        &mut self,
+        old_union: &UnionType<'doc, &'doc str>,
+        new_union: &UnionType<'doc, &'doc str>,
+    ) {
+        for member in &old_union.types {
+            if !new_union.types.contains(member) {
+                self.change(
+                    Severity::Breaking,
+                    Location::New,
+                    new_union.position,
+                    format!(
+                        "Type `{}` was removed from union `{}`",
+                        member, old_union.name
+                    ),
+                );
+            }
+        }
+
+        for member in &new_union.types {
+            if !old_union.types.contains(member) {
+                self.change(
+                    Severity::Dangerous,
+                    Location::New,
+                    new_union.position,
+                    format!("Type `{}` was added to union `{}`", member, old_union.name),
+                );
+            }
+        }
+    }
+
+    fn diff_enum<'doc>(
+        &mut self,
+        old_enum: &EnumType<'doc, &'doc str>,
+        new_enum: &EnumType<'doc, &'doc str>,
+    ) {
+        for old_value in &old_enum.values {
+            match new_enum
+                .values
+                .iter()
+                .find(|value| value.name == old_value.name)
+            {
+                Some(new_value) => {
+                    if !is_deprecated(&old_value.directives) && is_deprecated(&new_value.directives)
+                    {
+                        self.change(
+                            Severity::Safe,
+                            Location::New,
+                            new_value.position,
+                            format!(
+                                "Enum value `{}.{}` was deprecated",
+                                old_enum.name, old_value.name
+                            ),
+                        );
+                    }
+                }
+                None => self.change(
+                    Severity::Breaking,
+                    Location::Old,
+                    old_value.position,
+                    format!(
+                        "Enum value `{}.{}` was removed",
+                        old_enum.name, old_value.name
+                    ),
+                ),
+            }
+        }
+
+        for new_value in &new_enum.values {
+            if !old_enum
+                .values
+                .iter()
+                .any(|value| value.name == new_value.name)
+            {
+                self.change(
+                    Severity::Dangerous,
+                    Location::New,
+                    new_value.position,
+                    format!(
+                        "Enum value `{}.{}` was added",
+                        new_enum.name, new_value.name
+                    ),
+                );
+            }
+        }
+    }
+}
+
+// Clients can handle a field that used to be nullable becoming non-null, but not the reverse.
+fn is_safe_output_type_change<'a, 'b>(
+    old_type: &Type<'a, &'a str>,
+    new_type: &Type<'b, &'b str>,
+) -> bool {
+    match (old_type, new_type) {
+        (Type::NonNullType(old_inner), Type::NonNullType(new_inner)) => {
+            is_safe_output_type_change(old_inner, new_inner)
+        }
+        (_, Type::NonNullType(new_inner)) => is_safe_output_type_change(old_type, new_inner),
+        (Type::ListType(old_inner), Type::ListType(new_inner)) => {
+            is_safe_output_type_change(old_inner, new_inner)
+        }
+        (Type::NamedType(old_name), Type::NamedType(new_name)) => old_name == new_name,
+        _ => false,
+    }
+}
+
+// Clients can keep sending values for an input that used to be non-null but became nullable, but
+// not the reverse.
+fn is_safe_input_type_change<'a, 'b>(
+    old_type: &Type<'a, &'a str>,
+    new_type: &Type<'b, &'b str>,
+) -> bool {
+    match (old_type, new_type) {
+        (Type::NonNullType(old_inner), Type::NonNullType(new_inner)) => {
+            is_safe_input_type_change(old_inner, new_inner)
+        }
+        (Type::NonNullType(old_inner), _) => is_safe_input_type_change(old_inner, new_type),
+        (Type::ListType(old_inner), Type::ListType(new_inner)) => {
+            is_safe_input_type_change(old_inner, new_inner)
+        }
+        (Type::NamedType(old_name), Type::NamedType(new_name)) => old_name == new_name,
+        _ => false,
+    }
+}
+
+fn default_value_string<'doc>(input_value: &InputValue<'doc, &'doc str>) -> String {
+    match &input_value.default_value {
+        Some(default_value) => default_value.to_string(),
+        None => "none".to_string(),
+    }
+}
+
+fn is_required<'doc>(input_value: &InputValue<'doc, &'doc str>) -> bool {
+    matches!(input_value.value_type, Type::NonNullType(_)) && input_value.default_value.is_none()
+}
+
+fn is_deprecated<'doc>(directives: &[Directive<'doc, &'doc str>]) -> bool {
+    directives
+        .iter()
+        .any(|directive| directive.name == "deprecated")
+}
+
+fn type_definition_name<'doc>(type_definition: &TypeDefinition<'doc, &'doc str>) -> &'doc str {
+    match type_definition {
+        TypeDefinition::Scalar(inner) => inner.name,
+        TypeDefinition::Object(inner) => inner.name,
+        TypeDefinition::Interface(inner) => inner.name,
+        TypeDefinition::Union(inner) => inner.name,
+        TypeDefinition::Enum(inner) => inner.name,
+        TypeDefinition::InputObject(inner) => inner.name,
+    }
+}
+
+fn type_definition_pos<'doc>(type_definition: &TypeDefinition<'doc, &'doc str>) -> Pos {
+    match type_definition {
+        TypeDefinition::Scalar(inner) => inner.position,
+        TypeDefinition::Object(inner) => inner.position,
+        TypeDefinition::Interface(inner) => inner.position,
+        TypeDefinition::Union(inner) => inner.position,
+        TypeDefinition::Enum(inner) => inner.position,
+        TypeDefinition::InputObject(inner) => inner.position,
+    }
+}
+
+fn type_definition_kind<'doc>(type_definition: &TypeDefinition<'doc, &'doc str>) -> &'static str {
+    match type_definition {
+        TypeDefinition::Scalar(_) => "a scalar",
+        TypeDefinition::Object(_) => "an object type",
+        TypeDefinition::Interface(_) => "an interface",
+        TypeDefinition::Union(_) => "a union",
+        TypeDefinition::Enum(_) => "an enum",
+        TypeDefinition::InputObject(_) => "an input object",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::diff_schemas;
+
+    const OLD: &str = r#"
+        schema {
+          query: Query
+        }
+
+        type Query {
+          user(id: ID!): User
+          users(first: Int): [User!]!
+        }
+
+        type User {
+          id: ID!
+          name: String!
+          email: String
+          status: Status!
+        }
+
+        type Post {
+          title: String!
+        }
+
+        union SearchResult = User | Post
+
+        enum Status {
+          ACTIVE
+          DISABLED
+        }
+
+        input UserFilter {
+          name: String
+        }
+    "#;
+
+    fn changes(new: &str) -> Vec<(Severity, String)> {
+        diff_schemas(OLD, new)
+            .unwrap()
+            .changes()
+            .iter()
+            .map(|change| (change.severity(), change.message().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_classifies_changes() {
+        let changes = changes(
+            r#"
+            schema {
+              query: Query
+            }
+
+            type Query {
+              user(id: ID!, includeDeleted: Boolean!): User
+              users(first: Int, after: String): [User!]!
+            }
+
+            type User {
+              id: ID!
+              name: String
+              email: String!
+              status: Status!
+              age: Int
+            }
+
+            type Post {
+              title: String!
+            }
+
+            union SearchResult = User
+
+            enum Status {
+              ACTIVE
+              BANNED
+            }
+
+            input UserFilter {
+              name: String
+              minAge: Int!
+            }
+            "#,
+        );
+
+        let breaking = vec![
+            "Field `User.name` changed type from `String!` to `String`",
+            "Required argument `includeDeleted` was added to `Query.user`",
+            "Required input field `UserFilter.minAge` was added",
+            "Type `Post` was removed from union `SearchResult`",
+            "Enum value `Status.DISABLED` was removed",
+        ];
+        for message in breaking {
+            assert!(
+                changes.contains(&(Severity::Breaking, message.to_string())),
+                "missing breaking change {:?} in {:#?}",
+                message,
+                changes
+            );
+        }
+
+        let dangerous = vec![
+            "Optional argument `after` was added to `Query.users`",
+            "Enum value `Status.BANNED` was added",
+        ];
+        for message in dangerous {
+            assert!(
+                changes.contains(&(Severity::Dangerous, message.to_string())),
+                "missing dangerous change {:?} in {:#?}",
+                message,
+                changes
+            );
+        }
+
+        let safe = vec![
+            "Field `User.email` changed type from `String` to `String!`",
+            "Field `User.age` was added",
+        ];
+        for message in safe {
+            assert!(
+                changes.contains(&(Severity::Safe, message.to_string())),
+                "missing safe change {:?} in {:#?}",
+                message,
+                changes
+            );
+        }
+
+        assert_eq!(Severity::Breaking, changes[0].0);
+        assert_eq!(Severity::Safe, changes[changes.len() - 1].0);
+    }
+
+    #[test]
+    fn test_removals_point_into_old_schema() {
+        let diff = diff_schemas(
+            OLD,
+            r#"
+            schema {
+              query: Query
+            }
+
+            type Query {
+              user(id: ID!): User
+              users(first: Int): [User!]!
+            }
+
+            type User {
+              id: ID!
+              name: String!
+              status: Status!
+            }
+
+            type Post {
+              title: String!
+            }
+
+            union SearchResult = User | Post
+
+            enum Status {
+              ACTIVE
+              DISABLED
+            }
+
+            input UserFilter {
+              name: String
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert!(diff.is_breaking());
+        assert_eq!(1, diff.changes().len());
+
+        let out = diff.to_string();
+        assert!(out.contains("Field `User.email` was removed"));
+        assert!(out.contains("--> old schema:14:11"));
+    }
+
+    #[test]
+    fn test_compares_expanded_connections() {
+        let diff = diff_schemas(
+            r#"
+            schema { query: Query }
+            type Query { users: [User!]! @connection }
+            type User { id: ID! }
+            "#,
+            r#"
+            schema { query: Query }
+            type Query { users: [User!]! }
+            type User { id: ID! }
+            "#,
+        )
+        .unwrap();
+
+        assert!(diff.is_breaking());
+
+        let out = diff.to_string();
+        assert!(out.contains("`UserConnection` was removed"), "{}", out);
+        assert!(out.contains("`Query.users`"), "{}", out);
+    }
+
+    #[test]
+    fn test_identical_schemas_have_no_changes() {
+        let diff = diff_schemas(OLD, OLD).unwrap();
+        assert!(diff.changes().is_empty());
+        assert!(!diff.is_breaking());
+    }
+}
@@ -0,0 +1,277 @@
+use graphql_parser::Pos;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fmt;
+
+/// A problem found in a schema or an operation, in a form that tools such as editors and CI can
+/// consume.
+///
+/// Serializes to JSON with [`diagnostics_to_json`](fn.diagnostics_to_json.html) or to
+/// [SARIF](https://sarifweb.azurewebsites.net) with
+/// [`diagnostics_to_sarif`](fn.diagnostics_to_sarif.html).
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub severity: DiagnosticSeverity,
+    /// The stable code of the problem, such as `JFS0015`. Problems that don't come from checking
+    /// the schema or an operation, such as parse errors, don't have one.
+    pub code: Option<String>,
+    pub message: String,
+    pub notes: Vec<String>,
+    /// The file the problem is in. `schema` for schemas that weren't read from a file.
+    pub file: Option<String>,
+    pub range: Option<Range>,
+    /// Text that would fix the problem if written at `range`.
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for DiagnosticSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DiagnosticSeverity::Error => write!(f, "error"),
+            DiagnosticSeverity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// The part of a file a diagnostic points at. `end` is exclusive.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// Lines and columns start at 1.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Diagnostic {
+    /// A diagnostic for a problem that isn't tied to a location, such as a parse error.
+    pub(crate) fn without_location(message: String) -> Self {
+        Diagnostic {
+            severity: DiagnosticSeverity::Error,
+            code: None,
+            message,
+            notes: Vec::new(),
+            file: None,
+            range: None,
+            suggestions: Vec::new(),
+        }
+    }
+}
+
+impl Range {
+    /// The range of the name starting at `pos`, such as `deprecated` in `@deprecated(...)`.
+    ///
+    /// Nodes added by the code generation, such as the types of connections, don't have a
+    /// position in the source. They have the default position, which has no range.
+    pub(crate) fn of_name_at(pos: Pos, source: &str) -> Option<Self> {
+        if pos.line == 0 || pos.column == 0 {
+            return None;
+        }
+
+        let start = Position {
+            line: pos.line,
+            column: pos.column,
+        };
+
+        let line = source.lines().nth(pos.line - 1).unwrap_or("");
+        let rest = line.chars().skip(pos.column - 1);
+
+        let mut len = 0;
+        for (idx, c) in rest.enumerate() {
+            let is_name_char =
+                c.is_alphanumeric() || c == '_' || (idx == 0 && (c == '@' || c == '$'));
+            if !is_name_char {
+                break;
+            }
+            len += 1;
+        }
+
+        let end = Position {
+            line: pos.line,
+            column: pos.column + len.max(1),
+        };
+
+        Some(Range { start, end })
+    }
+}
+
+/// Serialize diagnostics as a JSON array.
+pub fn diagnostics_to_json(diagnostics: &[Diagnostic]) -> String {
+    serde_json::to_string_pretty(diagnostics).expect("diagnostics are always valid JSON")
+}
+
+/// Serialize diagnostics as a [SARIF](https://sarifweb.azurewebsites.net) 2.1.0 log, which is
+/// understood by code scanning tools such as GitHub's.
+pub fn diagnostics_to_sarif(diagnostics: &[Diagnostic]) -> String {
+    let results = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let mut text = diagnostic.message.clone();
+            for note in &diagnostic.notes {
+                text.push_str("\n\n");
+                text.push_str(note);
+            }
+
+            let locations = match (&diagnostic.file, &diagnostic.range) {
+                (Some(file), Some(range)) => vec![json!({
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": file },
+                        "region": {
+                            "startLine": range.start.line,
+                            "startColumn": range.start.column,
+                            "endLine": range.end.line,
+                            "endColumn": range.end.column,
+                        },
+                    },
+                })],
+                (Some(file), None) => vec![json!({
+                    "physicalLocation": { "artifactLocation": { "uri": file } },
+                })],
+                _ => vec![],
+            };
+
+            let mut result = json!({
+                "level": diagnostic.severity.to_string(),
+                "message": { "text": text },
+                "locations": locations,
+            });
+            if let Some(code) = &diagnostic.code {
+                result["ruleId"] = json!(code);
+            }
+            result
+        })
+        .collect::<Vec<_>>();
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "juniper-from-schema",
+                    "informationUri": "https://github.com/davidpdrsn/juniper-from-schema",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            },
+            // Columns are counted in chars, like the positions of graphql-parser, rather than
+            // the UTF-16 code units SARIF defaults to
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    });
+
+    serde_json::to_string_pretty(&log).expect("diagnostics are always valid JSON")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CodeGen, Lint, LintLevel};
+
+    #[test]
+    fn test_errors_as_diagnostics() {
+        let schema = r#"
+            schema { query: Query }
+
+            type Query {
+              createdAt: DateTimeUtc! @juniper(ownership: "owned")
+            }
+        "#;
+
+        let error = CodeGen::build_from_schema_literal(schema.to_string())
+            .finish()
+            .generate_code()
+            .unwrap_err();
+
+        let diagnostics = error.diagnostics();
+        assert_eq!(
+            vec![Diagnostic {
+                severity: DiagnosticSeverity::Error,
+                code: Some("JFS0001".to_string()),
+                message: "You have to define a custom scalar called `DateTimeUtc` to use this type"
+                    .to_string(),
+                notes: vec!["Insert `scalar DateTimeUtc` into your schema".to_string()],
+                file: Some("schema".to_string()),
+                range: Some(Range {
+                    start: Position {
+                        line: 5,
+                        column: 15
+                    },
+                    end: Position {
+                        line: 5,
+                        column: 24
+                    },
+                }),
+                suggestions: vec!["scalar DateTimeUtc".to_string()],
+            }],
+            diagnostics
+        );
+
+        let json = diagnostics_to_json(&diagnostics);
+        let parsed: Vec<Diagnostic> = serde_json::from_str(&json).unwrap();
+        assert_eq!(diagnostics, parsed);
+
+        let sarif: serde_json::Value =
+            serde_json::from_str(&diagnostics_to_sarif(&diagnostics)).unwrap();
+        assert_eq!("unicodeCodePoints", sarif["runs"][0]["columnKind"]);
+        let result = &sarif["runs"][0]["results"][0];
+        assert_eq!("error", result["level"]);
+        let region = &result["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(5, region["startLine"]);
+        assert_eq!(24, region["endColumn"]);
+    }
+
+    #[test]
+    fn test_warnings_as_diagnostics() {
+        let schema = r#"
+            schema { query: Query }
+
+            type Query {
+              ping: Boolean! @deprecated
+            }
+        "#;
+
+        let (_, warnings) = CodeGen::build_from_schema_literal(schema.to_string())
+            .lint(Lint::DeprecatedWithoutReason, LintLevel::Warn)
+            .finish()
+            .generate_code_with_warnings()
+            .unwrap();
+
+        let diagnostics = warnings.diagnostics();
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(DiagnosticSeverity::Warning, diagnostics[0].severity);
+        assert_eq!(Some("JFS1006"), diagnostics[0].code.as_deref());
+        let range = diagnostics[0].range.unwrap();
+        assert_eq!((5, 30), (range.start.line, range.start.column));
+        assert_eq!(41, range.end.column);
+    }
+
+    #[test]
+    fn test_default_positions_have_no_range() {
+        let schema = "type Query { ping: Boolean! }";
+
+        assert_eq!(None, Range::of_name_at(Pos::default(), schema));
+
+        let range = Range::of_name_at(
+            Pos {
+                line: 1,
+                column: 14,
+            },
+            schema,
+        )
+        .unwrap();
+        assert_eq!((1, 14), (range.start.line, range.start.column));
+        assert_eq!(18, range.end.column);
+    }
+}
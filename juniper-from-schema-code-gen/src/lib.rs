@@ -5,13 +5,31 @@
 #![doc(html_root_url = "https://docs.rs/juniper-from-schema-code-gen/0.5.2")]
 
 mod ast_pass;
+mod diagnostic;
 
-use ast_pass::{code_gen_pass::CodeGenPass, error, AstData};
+use ast_pass::{
+    client_pass::ClientPass,
+    code_gen_pass::{render_scaffold, CodeGenPass},
+    connections::Connections,
+    error,
+    lints::LintConfig,
+    AstData,
+};
+
+pub use ast_pass::{
+    lints::{Lint, LintLevel},
+    schema_diff::{Change, SchemaDiff, Severity},
+};
+pub use diagnostic::{
+    diagnostics_to_json, diagnostics_to_sarif, Diagnostic, DiagnosticSeverity, Position, Range,
+};
 
-use graphql_parser::parse_schema;
+use graphql_parser::{parse_query, parse_schema, schema::Document};
 use proc_macro2::Span;
 use quote::quote;
+use sha2::{Digest, Sha256};
 use std::{
+    collections::BTreeSet,
     fmt,
     path::{Path, PathBuf},
 };
@@ -21,11 +39,44 @@ const DATE_SCALAR_NAME: &str = "Date";
 const UUID_SCALAR_NAME: &str = "Uuid";
 const URL_SCALAR_NAME: &str = "Url";
 
+/// The definition of the `@juniper` directive that schemas are allowed to include.
+pub const JUNIPER_DIRECTIVE_DEFINITION: &str = r#"directive @juniper(
+    ownership: String = "borrowed",
+    infallible: Boolean = false,
+    with_time_zone: Boolean = true,
+    async: Boolean = false,
+    stream_item_infallible: Boolean = true,
+    stream_type: String = null,
+    generate_struct: Boolean = false,
+    batch: Boolean = false,
+    node_interface: Boolean = false
+) on FIELD_DEFINITION | SCALAR | OBJECT | INTERFACE"#;
+
+/// The definition of the `@connection` directive that schemas are allowed to include.
+pub const CONNECTION_DIRECTIVE_DEFINITION: &str = "directive @connection on FIELD_DEFINITION";
+
+/// The definition of the `@cost` directive that schemas are allowed to include.
+pub const COST_DIRECTIVE_DEFINITION: &str = r#"directive @cost(
+    complexity: Int,
+    multipliers: [String!]
+) on FIELD_DEFINITION | OBJECT"#;
+
 #[derive(Debug)]
 pub struct CodeGen {
     schema: SchemaLocation,
     context_type: syn::Type,
     error_type: syn::Type,
+    module: Option<syn::Ident>,
+    visibility: Option<syn::Visibility>,
+    persisted_queries: Vec<PersistedQuery>,
+    lints: LintConfig,
+}
+
+// An operation document and the name errors in it are reported with, usually its file path.
+#[derive(Debug)]
+struct PersistedQuery {
+    name: String,
+    document: String,
 }
 
 impl CodeGen {
@@ -34,6 +85,10 @@ impl CodeGen {
             schema: SchemaLocation::File(path),
             context_type: None,
             error_type: None,
+            module: None,
+            visibility: None,
+            persisted_queries: Vec::new(),
+            lints: LintConfig::default(),
         }
     }
 
@@ -42,57 +97,248 @@ impl CodeGen {
             schema: SchemaLocation::Literal(schema),
             context_type: None,
             error_type: None,
+            module: None,
+            visibility: None,
+            persisted_queries: Vec::new(),
+            lints: LintConfig::default(),
         }
     }
 
     pub fn generate_code(self) -> Result<proc_macro2::TokenStream, Error> {
-        let (schema, schema_path) = match self.schema {
-            SchemaLocation::File(path) => (
-                std::fs::read_to_string(&path).map_err(Error::Io)?,
-                Some(path),
-            ),
-            SchemaLocation::Literal(schema) => (schema, None),
-        };
+        let (tokens, _warnings) = self.generate_code_with_warnings()?;
+        Ok(tokens)
+    }
 
-        let doc = match parse_schema(&schema) {
-            Ok(doc) => doc,
-            Err(parse_error) => return Err(Error::SchemaParseError(parse_error)),
-        };
+    /// Like [`generate_code`](#method.generate_code) but also returns the warnings, such as
+    /// lints set to `warn`.
+    pub fn generate_code_with_warnings(
+        self,
+    ) -> Result<(proc_macro2::TokenStream, Warnings), Error> {
+        let (schema, schema_path) = self.schema.read()?;
 
-        let ast_data = match AstData::new_from_doc(&doc) {
-            Ok(x) => x,
-            Err(code_gen_errors) => {
-                let errors = Error::CodeGenErrors {
-                    errors: code_gen_errors.into_iter().collect(),
-                    schema,
-                };
-                return Err(errors);
+        let (mut tokens, warnings) = self.with_schema_doc(&schema, |code_gen, doc, ast_data| {
+            let output = CodeGenPass::new(
+                &code_gen.error_type,
+                &code_gen.context_type,
+                &code_gen.lints,
+                ast_data,
+            );
+            let (mut tokens, warnings) = output
+                .gen_juniper_code(doc)
+                .map_err(|errors| code_gen_errors(errors, &schema))?;
+
+            if !code_gen.persisted_queries.is_empty() {
+                tokens.extend(persisted_queries(doc, &code_gen.persisted_queries)?);
             }
+
+            Ok((tokens, warnings))
+        })?;
+
+        if let Some(path) = schema_path {
+            include_literal_schema(&mut tokens, path.as_path());
+        }
+
+        if let Some(visibility) = &self.visibility {
+            tokens = set_visibility(tokens, visibility);
+        }
+
+        if let Some(module) = self.module {
+            tokens = wrap_in_module(tokens, &module);
+        }
+
+        let warnings = Warnings {
+            warnings: warnings.into_iter().collect(),
+            schema,
         };
 
-        let output = CodeGenPass::new(&self.error_type, &self.context_type, ast_data);
+        Ok((tokens, warnings))
+    }
 
-        match output.gen_juniper_code(&doc) {
-            Ok(mut tokens) => {
-                if debugging_enabled() {
-                    eprintln!("{}", tokens);
-                }
+    /// Like [`generate_code`](#method.generate_code) but formats the code as a Rust file.
+    pub fn generate_formatted_code(self) -> Result<String, Error> {
+        let (code, _warnings) = self.generate_formatted_code_with_warnings()?;
+        Ok(code)
+    }
 
-                if let Some(path) = schema_path {
-                    include_literal_schema(&mut tokens, path.as_path());
-                }
+    /// Like [`generate_formatted_code`](#method.generate_formatted_code) but also returns the
+    /// warnings.
+    pub fn generate_formatted_code_with_warnings(self) -> Result<(String, Warnings), Error> {
+        let (tokens, warnings) = self.generate_code_with_warnings()?;
+        let file = syn::parse2(tokens).expect("generated invalid code");
+        Ok((prettyplease::unparse(&file), warnings))
+    }
+
+    /// Generate typed client code for the GraphQL operations in `operations`.
+    ///
+    /// The operations are validated against the schema. Each named query and mutation gets a
+    /// module containing the operation text, a `Variables` struct, and a `ResponseData` struct.
+    pub fn generate_client_code(self, operations: &str) -> Result<proc_macro2::TokenStream, Error> {
+        let (schema, _) = self.schema.read()?;
 
-                Ok(tokens)
+        self.with_schema_doc(&schema, |_, doc, ast_data| {
+            let operations_doc = match parse_query::<&str>(operations) {
+                Ok(doc) => doc,
+                Err(parse_error) => return Err(Error::OperationsParseError(parse_error)),
+            };
+
+            match ClientPass::new(ast_data).gen_client_code(doc, &operations_doc) {
+                Ok(tokens) => Ok(tokens),
+                Err(errors) => Err(Error::OperationErrors {
+                    errors: errors.into_iter().collect(),
+                    operations: operations.to_string(),
+                    source_name: "operations".to_string(),
+                }),
             }
-            Err(code_gen_errors) => {
-                let errors = Error::CodeGenErrors {
-                    errors: code_gen_errors.into_iter().collect(),
-                    schema,
-                };
-                Err(errors)
+        })
+    }
+
+    /// Like [`generate_client_code`](#method.generate_client_code) but formats the code as a
+    /// Rust file.
+    pub fn generate_formatted_client_code(self, operations: &str) -> Result<String, Error> {
+        let tokens = self.generate_client_code(operations)?;
+        let file = syn::parse2(tokens).expect("generated invalid code");
+        Ok(prettyplease::unparse(&file))
+    }
+
+    /// Generate a formatted Rust file containing `todo!()` implementations of every `*Fields`,
+    /// `*Resolvers`, `*ReferenceResolver`, and `NodeResolver` trait.
+    pub fn generate_scaffold(self) -> Result<String, Error> {
+        self.scaffold(None)
+    }
+
+    /// Like [`generate_scaffold`](#method.generate_scaffold) but only includes the methods that
+    /// are missing from `existing_code`.
+    ///
+    /// Methods of traits that `existing_code` already implements are rendered without their
+    /// `impl` block, after a comment naming the block to merge them into.
+    pub fn generate_missing_scaffold(self, existing_code: &str) -> Result<String, Error> {
+        let existing_code = syn::parse_file(existing_code).map_err(Error::InvalidExistingCode)?;
+        self.scaffold(Some(&existing_code))
+    }
+
+    fn scaffold(self, existing_code: Option<&syn::File>) -> Result<String, Error> {
+        let (schema, _) = self.schema.read()?;
+
+        self.with_schema_doc(&schema, |code_gen, doc, ast_data| {
+            let output = CodeGenPass::new(
+                &code_gen.error_type,
+                &code_gen.context_type,
+                &code_gen.lints,
+                ast_data,
+            );
+
+            match output.gen_scaffold(doc) {
+                Ok(impls) => Ok(render_scaffold(impls, existing_code)),
+                Err(errors) => Err(code_gen_errors(errors, &schema)),
             }
+        })
+    }
+
+    // Parses the schema, expands the fields marked with `@connection`, and collects the data
+    // shared by the passes, before handing the document and the data to `pass`. `self` is passed
+    // along so the pass can borrow the configuration for as long as the document.
+    fn with_schema_doc<T>(
+        &self,
+        schema: &str,
+        pass: impl for<'doc> FnOnce(
+            &'doc Self,
+            &'doc Document<'doc, &'doc str>,
+            AstData<'doc>,
+        ) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let connections = find_connections(schema)?;
+
+        let doc = match parse_schema(schema) {
+            Ok(doc) => connections.expand(doc),
+            Err(parse_error) => return Err(Error::SchemaParseError(parse_error)),
+        };
+
+        let ast_data =
+            AstData::new_from_doc(&doc).map_err(|errors| code_gen_errors(errors, schema))?;
+
+        pass(self, &doc, ast_data)
+    }
+}
+
+fn code_gen_errors(errors: BTreeSet<error::Error>, schema: &str) -> Error {
+    Error::CodeGenErrors {
+        errors: errors.into_iter().collect(),
+        schema: schema.to_string(),
+    }
+}
+
+// The connection types of the fields marked with `@connection`. The code is generated from the
+// document they're added to.
+fn find_connections(schema: &str) -> Result<Connections, Error> {
+    Connections::new(schema).map_err(|errors| code_gen_errors(errors, schema))
+}
+
+/// Compare two versions of a schema and classify each change as breaking, dangerous, or safe.
+///
+/// The schemas are compared after the fields marked with `@connection` are expanded, so changes
+/// to the generated connection types are included. Apart from the connections neither schema is
+/// validated.
+pub fn diff_schemas(old_schema: &str, new_schema: &str) -> Result<SchemaDiff, Error> {
+    let old_connections = find_connections(old_schema)?;
+    let new_connections = find_connections(new_schema)?;
+
+    let old_doc = parse_schema::<&str>(old_schema).map_err(Error::SchemaParseError)?;
+    let new_doc = parse_schema::<&str>(new_schema).map_err(Error::SchemaParseError)?;
+    let old_doc = old_connections.expand(old_doc);
+    let new_doc = new_connections.expand(new_doc);
+
+    Ok(ast_pass::schema_diff::diff_schemas(
+        &old_doc,
+        &new_doc,
+        old_schema.to_string(),
+        new_schema.to_string(),
+    ))
+}
+
+// Validate every persisted query against the schema and generate the `PERSISTED_QUERIES` allowlist
+// mapping the SHA-256 hash of each document to the document itself.
+fn persisted_queries<'doc>(
+    doc: &'doc graphql_parser::schema::Document<'doc, &'doc str>,
+    queries: &[PersistedQuery],
+) -> Result<proc_macro2::TokenStream, Error> {
+    let mut errors = Vec::new();
+    let mut entries = Vec::new();
+
+    for query in queries {
+        let operations_doc = match parse_query::<&str>(&query.document) {
+            Ok(operations_doc) => operations_doc,
+            Err(parse_error) => {
+                errors.push(Error::OperationsParseError(parse_error));
+                continue;
+            }
+        };
+
+        // The schema has already been validated so this can't fail
+        let ast_data = AstData::new_from_doc(doc).expect("invalid schema");
+
+        if let Err(operation_errors) = ClientPass::new(ast_data).validate(&operations_doc) {
+            errors.push(Error::OperationErrors {
+                errors: operation_errors.into_iter().collect(),
+                operations: query.document.clone(),
+                source_name: query.name.clone(),
+            });
+            continue;
         }
+
+        let hash = format!("{:x}", Sha256::digest(query.document.as_bytes()));
+        let document = &query.document;
+        entries.push(quote! { (#hash, #document) });
     }
+
+    if !errors.is_empty() {
+        return Err(Error::PersistedQueryErrors(errors));
+    }
+
+    Ok(quote! {
+        /// The persisted queries as `(hash, document)` pairs, where `hash` is the hex encoded
+        /// SHA-256 hash of the document.
+        pub static PERSISTED_QUERIES: &[(&str, &str)] = &[#(#entries),*];
+    })
 }
 
 // This should cause the Rust schema to be rebuild even if the user only changes the GraphQL schema
@@ -110,6 +356,54 @@ fn include_literal_schema(tokens: &mut proc_macro2::TokenStream, schema_path: &P
     });
 }
 
+// Wrapping the code in a module allows several schemas to be used in the same crate without their
+// generated items, such as `Schema` and `query_trails`, clashing.
+fn wrap_in_module(
+    tokens: proc_macro2::TokenStream,
+    module: &syn::Ident,
+) -> proc_macro2::TokenStream {
+    quote! {
+        pub mod #module {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #tokens
+        }
+    }
+}
+
+// Replace `pub` on the generated top level items. Items nested inside them, such as struct fields
+// and the contents of `query_trails`, keep their visibility as they can't be reached from outside
+// anyway.
+fn set_visibility(
+    tokens: proc_macro2::TokenStream,
+    visibility: &syn::Visibility,
+) -> proc_macro2::TokenStream {
+    let mut file: syn::File = syn::parse2(tokens).expect("generated invalid code");
+
+    for item in &mut file.items {
+        let vis = match item {
+            syn::Item::Const(item) => &mut item.vis,
+            syn::Item::Enum(item) => &mut item.vis,
+            syn::Item::Fn(item) => &mut item.vis,
+            syn::Item::Mod(item) => &mut item.vis,
+            syn::Item::Static(item) => &mut item.vis,
+            syn::Item::Struct(item) => &mut item.vis,
+            syn::Item::Trait(item) => &mut item.vis,
+            syn::Item::Type(item) => &mut item.vis,
+            syn::Item::Union(item) => &mut item.vis,
+            syn::Item::Use(item) => &mut item.vis,
+            _ => continue,
+        };
+
+        if let syn::Visibility::Public(_) = vis {
+            *vis = visibility.clone();
+        }
+    }
+
+    quote! { #file }
+}
+
 #[derive(Debug)]
 pub enum Error {
     SchemaParseError(graphql_parser::schema::ParseError),
@@ -118,44 +412,183 @@ pub enum Error {
         schema: String,
     },
     Io(std::io::Error),
+    InvalidExistingCode(syn::Error),
+    OperationsParseError(graphql_parser::query::ParseError),
+    OperationErrors {
+        errors: Vec<error::Error>,
+        operations: String,
+        source_name: String,
+    },
+    PersistedQueryErrors(Vec<Error>),
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Error::SchemaParseError(inner) => write!(f, "{}", inner),
-            Error::CodeGenErrors { errors, schema } => {
-                assert!(
-                    !errors.is_empty(),
-                    "`print_and_panic_if_errors` called without any errors"
-                );
-
-                let count = errors.len();
-
-                let out = errors
-                    .iter()
-                    .map(|error| error.display(&schema).to_string())
-                    .collect::<Vec<_>>()
-                    .join("\n\n");
-
-                if count == 1 {
-                    write!(f, "\n\n{}\n\naborting due to previous error\n", out)
-                } else {
-                    write!(f, "\n\n{}\n\naborting due to {} errors\n", out, count)
+            Error::CodeGenErrors { errors, schema } => write_errors(f, errors, schema, "schema"),
+            Error::Io(inner) => write!(f, "{}", inner),
+            Error::InvalidExistingCode(inner) => write!(f, "{}", inner),
+            Error::OperationsParseError(inner) => write!(f, "{}", inner),
+            Error::OperationErrors {
+                errors,
+                operations,
+                source_name,
+            } => write_errors(f, errors, operations, source_name),
+            Error::PersistedQueryErrors(errors) => {
+                for error in errors {
+                    write!(f, "{}", error)?;
                 }
+                Ok(())
             }
-            Error::Io(inner) => write!(f, "{}", inner),
+        }
+    }
+}
+
+fn write_errors(
+    f: &mut fmt::Formatter,
+    errors: &[error::Error],
+    source: &str,
+    source_name: &str,
+) -> fmt::Result {
+    assert!(
+        !errors.is_empty(),
+        "`print_and_panic_if_errors` called without any errors"
+    );
+
+    let count = errors.len();
+
+    let out = errors
+        .iter()
+        .map(|error| error.display_in(source, source_name).to_string())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    if count == 1 {
+        write!(f, "\n\n{}\n\naborting due to previous error\n", out)?;
+    } else {
+        write!(f, "\n\n{}\n\naborting due to {} errors\n", out, count)?;
+    }
+
+    let mut codes = errors.iter().map(|error| error.code()).collect::<Vec<_>>();
+    codes.sort_unstable();
+    codes.dedup();
+
+    if let [code] = codes.as_slice() {
+        writeln!(
+            f,
+            "For more information about this error, try `juniper-from-schema explain {}`",
+            code
+        )
+    } else {
+        writeln!(
+            f,
+            "Some errors have detailed explanations: {}",
+            codes.join(", ")
+        )?;
+        writeln!(
+            f,
+            "For more information about an error, try `juniper-from-schema explain {}`",
+            codes[0]
+        )
+    }
+}
+
+/// The long-form explanation of an error code, such as `JFS0015`. Codes are printed with each
+/// error and never change meaning between releases.
+pub fn explain(code: &str) -> Option<&'static str> {
+    ast_pass::error_codes::EXPLANATIONS
+        .iter()
+        .find(|(candidate, _)| candidate.eq_ignore_ascii_case(code))
+        .map(|(_, explanation)| *explanation)
+}
+
+impl Error {
+    /// The errors as structured diagnostics. Errors that don't point into the schema or an
+    /// operation, such as parse errors, don't have a file or range.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        match self {
+            Error::CodeGenErrors { errors, schema } => errors
+                .iter()
+                .map(|error| error.to_diagnostic(DiagnosticSeverity::Error, schema, "schema"))
+                .collect(),
+            Error::OperationErrors {
+                errors,
+                operations,
+                source_name,
+            } => errors
+                .iter()
+                .map(|error| {
+                    error.to_diagnostic(DiagnosticSeverity::Error, operations, source_name)
+                })
+                .collect(),
+            Error::PersistedQueryErrors(errors) => {
+                errors.iter().flat_map(Error::diagnostics).collect()
+            }
+            other => vec![Diagnostic::without_location(other.to_string())],
         }
     }
 }
 
 impl std::error::Error for Error {}
 
+/// Problems found while generating code that don't prevent the code from being generated.
+#[derive(Debug)]
+pub struct Warnings {
+    warnings: Vec<error::Error>,
+    schema: String,
+}
+
+impl Warnings {
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.warnings.len()
+    }
+
+    /// The warnings as structured diagnostics.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.warnings
+            .iter()
+            .map(|warning| {
+                warning.to_diagnostic(DiagnosticSeverity::Warning, &self.schema, "schema")
+            })
+            .collect()
+    }
+
+    /// Each warning rendered like the errors, pointing into the schema.
+    pub fn iter(&self) -> impl Iterator<Item = String> + '_ {
+        self.warnings.iter().map(move |warning| {
+            warning
+                .display_as_warning(&self.schema, "schema")
+                .to_string()
+        })
+    }
+}
+
+impl fmt::Display for Warnings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, warning) in self.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", warning)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct CodeGenBuilder {
     schema: SchemaLocation,
     context_type: Option<syn::Type>,
     error_type: Option<syn::Type>,
+    module: Option<syn::Ident>,
+    visibility: Option<syn::Visibility>,
+    persisted_queries: Vec<PersistedQuery>,
+    lints: LintConfig,
 }
 
 impl CodeGenBuilder {
@@ -169,11 +602,48 @@ impl CodeGenBuilder {
         self
     }
 
+    /// Generate the code inside `pub mod #module { ... }`. Items from the parent module, such as
+    /// the context type and the types implementing the `*Fields` traits, are in scope.
+    pub fn module(mut self, module: syn::Ident) -> Self {
+        self.module = Some(module);
+        self
+    }
+
+    /// Use `visibility` instead of `pub` for the generated items, for example `pub(crate)`.
+    pub fn visibility(mut self, visibility: syn::Visibility) -> Self {
+        self.visibility = Some(visibility);
+        self
+    }
+
+    /// Validate the operations in `document` against the schema and include it in the generated
+    /// `PERSISTED_QUERIES` static. Errors are reported as coming from `name`.
+    pub fn persisted_query(mut self, name: String, document: String) -> Self {
+        self.persisted_queries
+            .push(PersistedQuery { name, document });
+        self
+    }
+
+    /// Set the level of `lint`. Takes precedence over the `@lints` directive in the schema.
+    pub fn lint(mut self, lint: Lint, level: LintLevel) -> Self {
+        self.lints.set_level(lint, level);
+        self
+    }
+
+    /// Set how deeply lists can be nested before the `list_nesting_depth` lint triggers.
+    pub fn max_list_nesting_depth(mut self, depth: usize) -> Self {
+        self.lints.set_max_list_nesting_depth(depth);
+        self
+    }
+
     pub fn finish(self) -> CodeGen {
         CodeGen {
             schema: self.schema,
             context_type: self.context_type.unwrap_or_else(default_context_type),
             error_type: self.error_type.unwrap_or_else(default_error_type),
+            module: self.module,
+            visibility: self.visibility,
+            persisted_queries: self.persisted_queries,
+            lints: self.lints,
         }
     }
 }
@@ -184,6 +654,18 @@ enum SchemaLocation {
     Literal(String),
 }
 
+impl SchemaLocation {
+    fn read(&self) -> Result<(String, Option<PathBuf>), Error> {
+        match self {
+            SchemaLocation::File(path) => Ok((
+                std::fs::read_to_string(path).map_err(Error::Io)?,
+                Some(path.clone()),
+            )),
+            SchemaLocation::Literal(schema) => Ok((schema.clone(), None)),
+        }
+    }
+}
+
 pub fn default_error_type() -> syn::Type {
     syn::parse_str("juniper::FieldError").expect("Failed to parse default error type")
 }
@@ -192,8 +674,380 @@ pub fn default_context_type() -> syn::Type {
     syn::parse_str("Context").expect("Failed to parse default context type")
 }
 
-fn debugging_enabled() -> bool {
-    std::env::var("JUNIPER_FROM_SCHEMA_DEBUG")
-        .map(|val| val == "1")
-        .unwrap_or(false)
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_juniper_directive_definition_is_valid() {
+        let schema = format!(
+            "schema {{ query: Query }} type Query {{ ping: Boolean! }} {}",
+            JUNIPER_DIRECTIVE_DEFINITION
+        );
+
+        let result = CodeGen::build_from_schema_literal(schema)
+            .finish()
+            .generate_code();
+
+        assert!(result.is_ok(), "{}", result.unwrap_err());
+    }
+
+    #[test]
+    fn test_connection_directive_definition_is_valid() {
+        let schema = format!(
+            "schema {{ query: Query }} type Query {{ ping: Boolean! }} {}",
+            CONNECTION_DIRECTIVE_DEFINITION
+        );
+
+        let result = CodeGen::build_from_schema_literal(schema)
+            .finish()
+            .generate_code();
+
+        assert!(result.is_ok(), "{}", result.unwrap_err());
+    }
+
+    #[test]
+    fn test_cost_directive_definition_is_valid() {
+        let schema = format!(
+            "schema {{ query: Query }} type Query {{ ping: Boolean! }} {}",
+            COST_DIRECTIVE_DEFINITION
+        );
+
+        let result = CodeGen::build_from_schema_literal(schema)
+            .finish()
+            .generate_code();
+
+        assert!(result.is_ok(), "{}", result.unwrap_err());
+    }
+
+    #[test]
+    fn test_invalid_cost_directives() {
+        let error = CodeGen::build_from_schema_literal(
+            r#"
+            schema { query: Query }
+
+            type Query {
+              users(first: String): [User!]! @cost(multipliers: ["first", "last"])
+              user: User! @cost(complexity: -1)
+            }
+
+            type User @cost(complexity: 2, multipliers: ["first"]) {
+              id: ID!
+            }
+
+            directive @cost(complexity: Int = 1) on FIELD_DEFINITION
+            "#
+            .to_string(),
+        )
+        .finish()
+        .generate_code()
+        .unwrap_err()
+        .to_string();
+
+        assert!(error.contains("Cost multiplier `first` isn't an `Int` argument of the field"));
+        assert!(error.contains("Cost multiplier `last` isn't an `Int` argument of the field"));
+        assert!(error.contains("Invalid complexity. Expected a non-negative `Int`, got `-1`"));
+        assert!(error.contains("`multipliers` is only supported on fields"));
+        assert!(error.contains("Missing `OBJECT` directive location for @cost directive"));
+        assert!(error.contains("`complexity` argument must not have a default value"));
+        assert!(error.contains("Missing argument `multipliers`"));
+    }
+
+    #[test]
+    fn test_invalid_batch_fields() {
+        let error = CodeGen::build_from_schema_literal(
+            r#"
+            schema { query: Query subscription: Subscription }
+
+            type Query {
+              users: [User!]! @juniper(batch: true, ownership: "owned")
+              user: User! @juniper(batch: true, async: true)
+              admin: User! @juniper(batch: true, async: true, ownership: "owned", infallible: true)
+            }
+
+            type Subscription {
+              users: [User!]! @juniper(batch: true, async: true, ownership: "owned")
+            }
+
+            interface Entity {
+              id: ID! @juniper(batch: true, async: true, ownership: "owned")
+              name: String!
+            }
+
+            type User implements Entity {
+              id: ID! @juniper(ownership: "owned")
+              name: String! @juniper(batch: true, async: true, ownership: "owned")
+            }
+            "#
+            .to_string(),
+        )
+        .finish()
+        .generate_code()
+        .unwrap_err()
+        .to_string();
+
+        assert!(error.contains("Batched fields must be async"));
+        assert!(error.contains("Batched fields must be owned"));
+        assert!(error.contains("Batched fields can't be infallible"));
+        assert!(error.contains("Fields of subscriptions can't be batched"));
+        assert!(error.contains("Fields of interfaces can't be batched"));
+        assert!(error.contains("Fields declared by interfaces can't be batched"));
+        assert!(error.contains("error[JFS0044]"));
+    }
+
+    #[test]
+    fn test_invalid_connection_fields() {
+        let generate = |schema: &str| {
+            CodeGen::build_from_schema_literal(schema.to_string())
+                .finish()
+                .generate_code()
+                .unwrap_err()
+                .to_string()
+        };
+
+        let error = generate(
+            r#"
+            schema { query: Query }
+
+            type Query {
+              user: User! @connection
+              users(first: Int): [User!]! @connection
+              admins: [User] @connection
+              posts: [Post!]! @connection
+            }
+
+            type User { id: ID! }
+            type Post { id: ID! }
+            type PostEdge { id: ID! }
+            "#,
+        );
+
+        assert!(error.contains("Connection fields must return a list of objects"));
+        assert!(error.contains("Connection fields can't declare the `first` argument"));
+        assert!(error.contains("Connections of `User` have different item types"));
+        assert!(error.contains("The type `PostEdge` is already defined"));
+        assert!(error.contains("error[JFS0045]"));
+
+        let error = generate(
+            r#"
+            schema { query: Query subscription: Subscription }
+
+            type Query {
+              users: [User!]! @connection @juniper(batch: true, async: true, ownership: "owned")
+            }
+
+            type Subscription {
+              users: [User!]! @connection @juniper(ownership: "owned")
+            }
+
+            interface Entity {
+              friends: [User!]! @connection
+            }
+
+            type User implements Entity {
+              friends: [User!]! @connection
+            }
+
+            directive @connection(first: Int) on FIELD_DEFINITION
+            "#,
+        );
+
+        assert!(error.contains("Connection fields can't be batched"));
+        assert!(error.contains("Fields of subscriptions can't be connections"));
+        assert!(error.contains("Fields of interfaces can't be connections"));
+        assert!(error.contains("Invalid definition of the @connection directive"));
+    }
+
+    #[test]
+    fn test_errors_point_into_the_schema_with_connections() {
+        let schema = r#"
+schema { query: Query }
+
+type Query {
+  users(role: String): [User!]! @connection @juniper(ownership: "invalid")
+  posts:
+    [Post!]! @connection
+}
+
+type User { id: ID! }
+type Post { id: ID! }
+"#;
+
+        let error = CodeGen::build_from_schema_literal(schema.to_string())
+            .finish()
+            .generate_code()
+            .unwrap_err();
+
+        let diagnostics = error.diagnostics();
+        assert_eq!(1, diagnostics.len(), "{}", error);
+        assert_eq!(
+            Position {
+                line: 5,
+                column: 45
+            },
+            diagnostics[0].range.unwrap().start
+        );
+
+        // The snippet shows the schema as it was written
+        let error = error.to_string();
+        assert!(
+            error.contains(
+                r#"users(role: String): [User!]! @connection @juniper(ownership: "invalid")"#
+            ),
+            "{}",
+            error
+        );
+        assert!(!error.contains("UserConnection"), "{}", error);
+    }
+
+    #[test]
+    fn test_invalid_node_interfaces() {
+        let error = CodeGen::build_from_schema_literal(
+            r#"
+            schema { query: Query }
+
+            type Query {
+              node(id: ID): Node @juniper(async: true)
+              nodes(ids: [ID!]!): [Node]! @juniper(infallible: true)
+            }
+
+            interface Node @juniper(node_interface: true) {
+              id: ID
+            }
+
+            interface Entity @juniper(node_interface: true) {
+              id: ID!
+            }
+
+            type User implements Node & Entity {
+              id: ID
+            }
+            "#
+            .to_string(),
+        )
+        .finish()
+        .generate_code()
+        .unwrap_err()
+        .to_string();
+
+        assert!(error.contains("Node lookup fields must be `node(id: ID!): Node`"));
+        assert!(error.contains("Node lookup fields must be async"));
+        assert!(error.contains("Node lookup fields can't be infallible"));
+        assert!(error.contains("The node interface `Node` must have an `id: ID!` field"));
+        assert!(error.contains("Only one interface can have `node_interface: true`"));
+        assert!(error.contains("error[JFS0046]"));
+    }
+
+    #[test]
+    fn test_invalid_federation_directives() {
+        let error = CodeGen::build_from_schema_literal(
+            r#"
+            schema { query: Query }
+
+            type Query @key(fields: "me") {
+              me: User!
+            }
+
+            type User @key(fields: "id organization { id }") @key(fields: "email") @extends(all: true) {
+              id: ID! @external(reason: "owned by the accounts service")
+              organization: Organization! @requires(fields: 1)
+            }
+
+            type Organization @key(name: "id") {
+              id: ID! @provides
+              name: String! @external
+            }
+
+            type Product @extends {
+              upc: String! @external
+              price: Int! @external
+              weight: Int!
+              shippingCost: Int! @requires(fields: "price weight")
+              manufacturer: Organization! @provides(fields: "name country")
+            }
+            "#
+            .to_string(),
+        )
+        .finish()
+        .generate_code()
+        .unwrap_err()
+        .to_string();
+
+        assert!(error.contains("The root type `Query` can't be an entity"));
+        assert!(error.contains("`User` doesn't have the key field `email`"));
+        assert!(!error.contains("`User` doesn't have the key field `organization`"));
+        assert!(error.contains("`@extends` doesn't take any arguments"));
+        assert!(error.contains("`@external` doesn't take any arguments"));
+        assert!(error.contains("`@requires` takes a single `fields` argument"));
+        assert!(error.contains("`@key` takes a single `fields` argument"));
+        assert!(error.contains("`@provides` takes a single `fields` argument"));
+        assert!(error.contains(
+            "`Organization.name` is `@external` but `Organization` doesn't have `@extends`"
+        ));
+        assert!(error.contains(
+            "`@requires` of `Product.shippingCost` selects `weight`, which isn't an `@external` field"
+        ));
+        assert!(!error.contains("selects `price`"));
+        assert!(error.contains(
+            "`@provides` of `Product.manufacturer` selects `country`, which isn't a field of `Organization`"
+        ));
+        assert!(!error.contains("selects `name`"));
+        assert!(error.contains("error[JFS0047]"));
+    }
+
+    #[test]
+    fn test_errors_are_printed_with_their_code() {
+        let error = CodeGen::build_from_schema_literal(
+            "schema { query: Query } type Query { ping_pong: Boolean! }".to_string(),
+        )
+        .finish()
+        .generate_code()
+        .unwrap_err()
+        .to_string();
+
+        assert!(error.contains("error[JFS0015]: Field names must be camelCase, not snake_case"));
+        assert!(error.contains(
+            "For more information about this error, try `juniper-from-schema explain JFS0015`"
+        ));
+
+        assert!(explain("JFS0015")
+            .unwrap()
+            .contains("Juniper always converts field names"));
+        assert_eq!(explain("JFS0015"), explain("jfs0015"));
+        assert_eq!(None, explain("JFS9999"));
+    }
+
+    #[test]
+    fn test_wraps_code_in_module() {
+        let code = CodeGen::build_from_schema_literal(
+            "schema { query: Query } type Query { ping: Boolean! }".to_string(),
+        )
+        .module(syn::parse_str("admin").unwrap())
+        .finish()
+        .generate_code()
+        .unwrap();
+
+        let file: syn::File = syn::parse2(code).unwrap();
+        assert_eq!(1, file.items.len());
+        match &file.items[0] {
+            syn::Item::Mod(item_mod) => assert_eq!("admin", item_mod.ident.to_string()),
+            _ => panic!("expected a module"),
+        }
+    }
+
+    #[test]
+    fn test_sets_visibility_of_generated_items() {
+        let code = CodeGen::build_from_schema_literal(
+            "schema { query: Query } type Query { ping: Boolean! }".to_string(),
+        )
+        .visibility(syn::parse_str("pub(crate)").unwrap())
+        .finish()
+        .generate_code()
+        .unwrap()
+        .to_string();
+
+        assert!(code.contains("pub (crate) trait QueryFields"));
+        assert!(code.contains("pub (crate) type Schema"));
+        assert!(code.contains("pub (crate) mod query_trails"));
+    }
 }
@@ -6,8 +6,10 @@
 
 mod parse_input;
 
-use juniper_from_schema_code_gen::CodeGen;
-use parse_input::GraphqlSchemaFromFileInput;
+use juniper_from_schema_code_gen::{CodeGen, Warnings};
+use parse_input::{GraphqlSchemaFromFileInput, GraphqlSchemaInput, SchemaAttributeInput};
+use proc_macro2::TokenStream;
+use quote::quote;
 
 /// Read a GraphQL schema file and generate corresponding Juniper macro calls.
 ///
@@ -18,6 +20,7 @@ pub fn graphql_schema_from_file(input: proc_macro::TokenStream) -> proc_macro::T
         schema_path,
         context_type,
         error_type,
+        lints,
     } = match syn::parse::<GraphqlSchemaFromFileInput>(input) {
         Ok(p) => p,
         Err(e) => return e.to_compile_error().into(),
@@ -30,10 +33,13 @@ pub fn graphql_schema_from_file(input: proc_macro::TokenStream) -> proc_macro::T
     if let Some(error_type) = error_type {
         builder = builder.error_type(error_type);
     }
-    let code_gen = builder.finish();
+    let code_gen = lints.apply(builder).finish();
 
-    match code_gen.generate_code() {
-        Ok(tokens) => tokens.into(),
+    match code_gen.generate_code_with_warnings() {
+        Ok((mut tokens, warnings)) => {
+            tokens.extend(warnings_to_tokens(&warnings));
+            tokens.into()
+        }
         Err(errors) => panic!("{}", errors),
     }
 }
@@ -69,14 +75,148 @@ pub fn graphql_schema_from_file(input: proc_macro::TokenStream) -> proc_macro::T
 ///     }
 /// }
 /// ```
+///
+/// The context and error types can be customized before the schema:
+///
+/// ```ignore
+/// graphql_schema! {
+///     context_type: MyContext,
+///     error_type: MyError,
+///
+///     schema {
+///         query: Query
+///     }
+///
+///     type Query {
+///         helloWorld: String! @juniper(ownership: "owned")
+///     }
+/// }
+/// ```
 #[proc_macro]
 pub fn graphql_schema(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let schema = input.to_string();
+    let GraphqlSchemaInput {
+        schema,
+        context_type,
+        error_type,
+        lints,
+    } = match syn::parse::<GraphqlSchemaInput>(input) {
+        Ok(p) => p,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
-    let code_gen = CodeGen::build_from_schema_literal(schema).finish();
+    let mut builder = CodeGen::build_from_schema_literal(schema);
+    if let Some(context_type) = context_type {
+        builder = builder.context_type(context_type);
+    }
+    if let Some(error_type) = error_type {
+        builder = builder.error_type(error_type);
+    }
+    let code_gen = lints.apply(builder).finish();
 
-    match code_gen.generate_code() {
-        Ok(tokens) => tokens.into(),
+    match code_gen.generate_code_with_warnings() {
+        Ok((mut tokens, warnings)) => {
+            tokens.extend(warnings_to_tokens(&warnings));
+            tokens.into()
+        }
         Err(errors) => panic!("{}", errors),
     }
 }
+
+/// Read a GraphQL schema file and generate the code inside the annotated module.
+///
+/// Items from the parent module are in scope inside the module, as are any items already in it.
+///
+/// # Example
+///
+/// ```ignore
+/// #[juniper_from_schema::schema(
+///     file = "schema.graphql",
+///     context_type = Context,
+///     visibility = pub(crate),
+/// )]
+/// pub mod api {}
+/// ```
+#[proc_macro_attribute]
+pub fn schema(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let SchemaAttributeInput {
+        schema_path,
+        context_type,
+        error_type,
+        visibility,
+        lints,
+    } = match syn::parse::<SchemaAttributeInput>(attr) {
+        Ok(p) => p,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let mut item_mod = match syn::parse::<syn::ItemMod>(item) {
+        Ok(item_mod) => item_mod,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    if item_mod.content.is_none() {
+        return syn::Error::new_spanned(
+            &item_mod,
+            "`#[schema]` must be used on an inline module, such as `mod api {}`",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let mut builder = CodeGen::build_from_schema_file(schema_path);
+    if let Some(context_type) = context_type {
+        builder = builder.context_type(context_type);
+    }
+    if let Some(error_type) = error_type {
+        builder = builder.error_type(error_type);
+    }
+    if let Some(visibility) = visibility {
+        builder = builder.visibility(visibility);
+    }
+    let code_gen = lints.apply(builder).finish();
+
+    let generated = match code_gen.generate_code_with_warnings() {
+        Ok((mut tokens, warnings)) => {
+            tokens.extend(warnings_to_tokens(&warnings));
+            syn::parse2::<syn::File>(tokens).expect("generated invalid code")
+        }
+        Err(errors) => panic!("{}", errors),
+    };
+
+    let items = &mut item_mod
+        .content
+        .as_mut()
+        .expect("checked for inline module above")
+        .1;
+    items.insert(
+        0,
+        syn::parse_quote! {
+            #[allow(unused_imports)]
+            use super::*;
+        },
+    );
+    items.extend(generated.items);
+
+    (quote! { #item_mod }).into()
+}
+
+// Proc macros can't emit warnings on stable Rust, so each warning becomes the use of a deprecated
+// item whose note is the warning. The compiler then reports it without failing the build.
+fn warnings_to_tokens(warnings: &Warnings) -> TokenStream {
+    let warnings = warnings.iter().map(|warning| {
+        quote! {
+            const _: () = {
+                #[deprecated(note = #warning)]
+                struct JuniperFromSchemaWarning;
+
+                #[allow(path_statements)]
+                JuniperFromSchemaWarning;
+            };
+        }
+    });
+
+    quote! { #(#warnings)* }
+}
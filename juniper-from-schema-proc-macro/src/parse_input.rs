@@ -1,8 +1,10 @@
+use juniper_from_schema_code_gen::{CodeGenBuilder, Lint, LintLevel};
 use std::{fmt::Write, path::PathBuf};
 use syn::{
     self,
     parse::{Parse, ParseStream},
-    Ident, Token, Type,
+    punctuated::Punctuated,
+    Ident, Token, Type, Visibility,
 };
 
 #[derive(Debug)]
@@ -10,15 +12,12 @@ pub struct GraphqlSchemaFromFileInput {
     pub schema_path: PathBuf,
     pub error_type: Option<Type>,
     pub context_type: Option<Type>,
+    pub lints: LintsInput,
 }
 
 impl Parse for GraphqlSchemaFromFileInput {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let file = input.parse::<syn::LitStr>()?.value();
-        let cargo_dir =
-            std::env::var("CARGO_MANIFEST_DIR").expect("Env var `CARGO_MANIFEST_DIR` was missing");
-        let pwd = PathBuf::from(cargo_dir);
-        let schema_path = pwd.join(file);
+        let schema_path = parse_schema_path(input)?;
 
         if input.peek(Token![,]) {
             input.parse::<Token![,]>()?;
@@ -26,6 +25,7 @@ impl Parse for GraphqlSchemaFromFileInput {
 
         let mut error_type = None::<Type>;
         let mut context_type = None::<Type>;
+        let mut lints = LintsInput::default();
 
         loop {
             if input.is_empty() {
@@ -34,6 +34,10 @@ impl Parse for GraphqlSchemaFromFileInput {
 
             let key = input.parse::<Ident>()?;
             match &*key.to_string() {
+                name if LintsInput::is_key(name) => {
+                    input.parse::<Token![:]>()?;
+                    lints.parse_value(&key, input)?;
+                }
                 "error_type" => {
                     input.parse::<Token![:]>()?;
                     error_type = Some(input.parse()?);
@@ -42,10 +46,106 @@ impl Parse for GraphqlSchemaFromFileInput {
                     input.parse::<Token![:]>()?;
                     context_type = Some(input.parse()?);
                 }
+                other => return Err(unknown_config(&key, other, "graphql_schema_from_file")),
+            }
+
+            if input.peek(Token![,]) {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(GraphqlSchemaFromFileInput {
+            schema_path,
+            error_type,
+            context_type,
+            lints,
+        })
+    }
+}
+
+/// Input to `graphql_schema!`. The GraphQL schema can be preceded by `error_type: ...`,
+/// `context_type: ...`, and lint config. GraphQL definitions never start with `identifier:` so
+/// there is no ambiguity.
+#[derive(Debug)]
+pub struct GraphqlSchemaInput {
+    pub schema: String,
+    pub error_type: Option<Type>,
+    pub context_type: Option<Type>,
+    pub lints: LintsInput,
+}
+
+impl Parse for GraphqlSchemaInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut error_type = None::<Type>;
+        let mut context_type = None::<Type>;
+        let mut lints = LintsInput::default();
+
+        while input.peek(Ident) && input.peek2(Token![:]) {
+            let key = input.parse::<Ident>()?;
+            input.parse::<Token![:]>()?;
+
+            match &*key.to_string() {
+                name if LintsInput::is_key(name) => lints.parse_value(&key, input)?,
+                "error_type" => error_type = Some(input.parse()?),
+                "context_type" => context_type = Some(input.parse()?),
+                other => return Err(unknown_config(&key, other, "graphql_schema")),
+            }
+
+            input.parse::<Token![,]>()?;
+        }
+
+        let schema = input.parse::<proc_macro2::TokenStream>()?.to_string();
+
+        Ok(GraphqlSchemaInput {
+            schema,
+            error_type,
+            context_type,
+            lints,
+        })
+    }
+}
+
+/// Arguments to the `#[schema(...)]` attribute, such as
+/// `file = "schema.graphql", context_type = Context, visibility = pub(crate)`.
+#[derive(Debug)]
+pub struct SchemaAttributeInput {
+    pub schema_path: PathBuf,
+    pub error_type: Option<Type>,
+    pub context_type: Option<Type>,
+    pub visibility: Option<Visibility>,
+    pub lints: LintsInput,
+}
+
+impl Parse for SchemaAttributeInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut schema_path = None::<PathBuf>;
+        let mut error_type = None::<Type>;
+        let mut context_type = None::<Type>;
+        let mut visibility = None::<Visibility>;
+        let mut lints = LintsInput::default();
+
+        loop {
+            if input.is_empty() {
+                break;
+            }
+
+            let key = input.parse::<Ident>()?;
+            input.parse::<Token![=]>()?;
+
+            match &*key.to_string() {
+                "file" => schema_path = Some(parse_schema_path(input)?),
+                "error_type" => error_type = Some(input.parse()?),
+                "context_type" => context_type = Some(input.parse()?),
+                "visibility" => visibility = Some(input.parse()?),
+                name if LintsInput::is_key(name) => lints.parse_value(&key, input)?,
                 other => {
                     let mut msg = String::new();
-                    writeln!(msg, "Unknown `graphql_schema_from_file` config `{}`", other).unwrap();
-                    writeln!(msg, "Supported configs are `error_type` and `context_type`").unwrap();
+                    writeln!(msg, "Unknown `schema` config `{}`", other).unwrap();
+                    writeln!(
+                        msg,
+                        "Supported configs are `file`, `error_type`, `context_type`, `visibility`, `deny`, `warn`, `allow`, and `max_list_nesting_depth`"
+                    )
+                    .unwrap();
                     return Err(syn::parse::Error::new(key.span(), msg));
                 }
             }
@@ -55,10 +155,90 @@ impl Parse for GraphqlSchemaFromFileInput {
             }
         }
 
-        Ok(GraphqlSchemaFromFileInput {
+        let schema_path = schema_path.ok_or_else(|| {
+            syn::parse::Error::new(
+                proc_macro2::Span::call_site(),
+                "Missing `file` config. Example: `#[schema(file = \"schema.graphql\")]`",
+            )
+        })?;
+
+        Ok(SchemaAttributeInput {
             schema_path,
             error_type,
             context_type,
+            visibility,
+            lints,
         })
     }
 }
+
+fn parse_schema_path(input: ParseStream) -> syn::Result<PathBuf> {
+    let file = input.parse::<syn::LitStr>()?.value();
+    let cargo_dir =
+        std::env::var("CARGO_MANIFEST_DIR").expect("Env var `CARGO_MANIFEST_DIR` was missing");
+    let pwd = PathBuf::from(cargo_dir);
+    Ok(pwd.join(file))
+}
+
+fn unknown_config(key: &Ident, name: &str, macro_name: &str) -> syn::parse::Error {
+    let mut msg = String::new();
+    writeln!(msg, "Unknown `{}` config `{}`", macro_name, name).unwrap();
+    writeln!(
+        msg,
+        "Supported configs are `error_type`, `context_type`, `deny`, `warn`, `allow`, and `max_list_nesting_depth`"
+    )
+    .unwrap();
+    syn::parse::Error::new(key.span(), msg)
+}
+
+/// Lint config such as `deny: [missing_description, enum_value_case]`. The attribute uses `=`
+/// instead of `:`.
+#[derive(Debug, Default)]
+pub struct LintsInput {
+    levels: Vec<(Lint, LintLevel)>,
+    max_list_nesting_depth: Option<usize>,
+}
+
+impl LintsInput {
+    fn is_key(key: &str) -> bool {
+        matches!(key, "deny" | "warn" | "allow" | "max_list_nesting_depth")
+    }
+
+    fn parse_value(&mut self, key: &Ident, input: ParseStream) -> syn::Result<()> {
+        let key_name = key.to_string();
+
+        if key_name == "max_list_nesting_depth" {
+            let depth = input.parse::<syn::LitInt>()?;
+            self.max_list_nesting_depth = Some(depth.base10_parse()?);
+            return Ok(());
+        }
+
+        let level = key_name
+            .parse::<LintLevel>()
+            .map_err(|msg| syn::Error::new(key.span(), msg))?;
+
+        let content;
+        syn::bracketed!(content in input);
+        let names = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+
+        for name in names {
+            let lint = name
+                .to_string()
+                .parse::<Lint>()
+                .map_err(|msg| syn::Error::new(name.span(), msg))?;
+            self.levels.push((lint, level));
+        }
+
+        Ok(())
+    }
+
+    pub fn apply(self, mut builder: CodeGenBuilder) -> CodeGenBuilder {
+        for (lint, level) in self.levels {
+            builder = builder.lint(lint, level);
+        }
+        if let Some(depth) = self.max_list_nesting_depth {
+            builder = builder.max_list_nesting_depth(depth);
+        }
+        builder
+    }
+}
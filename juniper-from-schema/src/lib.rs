@@ -25,16 +25,33 @@
 //!     - [Customizing ownership](#customizing-ownership)
 //!     - [Infallible fields](#infallible-fields)
 //!     - [Async resolvers](#async-resolvers)
+//!     - [Generating structs](#generating-structs)
+//!     - [Batch loading](#batch-loading)
+//!     - [Relay connections](#relay-connections)
+//!     - [Relay node interface](#relay-node-interface)
+//!     - [Apollo Federation](#apollo-federation)
 //! - [GraphQL to Rust types](#graphql-to-rust-types)
 //! - [Query trails](#query-trails)
 //!     - [Abbreviated example](#abbreviated-example)
 //!     - [Types](#types)
 //!     - [Downcasting for interface and union `QueryTrail`s](#downcasting-for-interface-and-union-querytrails)
 //!     - [`QueryTrail`s for fields that take arguments](#querytrails-for-fields-that-take-arguments)
+//!     - [Fields selected more than once](#fields-selected-more-than-once)
+//!     - [Listing the selected fields](#listing-the-selected-fields)
+//!     - [`QueryTrail`s for mutations and subscriptions](#querytrails-for-mutations-and-subscriptions)
+//!     - [Sending selections to other services](#sending-selections-to-other-services)
+//! - [Query cost analysis](#query-cost-analysis)
 //! - [Customizing the error type](#customizing-the-error-type)
 //! - [Customizing the context type](#customizing-the-context-type)
+//! - [Generating code inside a module](#generating-code-inside-a-module)
+//! - [Lints](#lints)
+//! - [Error codes](#error-codes)
 //! - [Inspecting the generated code](#inspecting-the-generated-code)
 //! - [Generating code in "build.rs"](#generating-code-in-buildrs)
+//! - [Scaffolding resolvers](#scaffolding-resolvers)
+//! - [Generating client code](#generating-client-code)
+//! - [Persisted queries](#persisted-queries)
+//! - [Detecting breaking changes](#detecting-breaking-changes)
 //!
 //! # Example
 //!
@@ -929,8 +946,22 @@
 //! async. The default is sync. More info [here](#async-resolvers).
 //! - `@juniper(stream_item_infallible: true|false)`. For choosing whether the stream produces
 //! `Result`s or plain values. Default is `true` meaning the stream does not produce `Result`s.
+//! - `@juniper(generate_struct: true|false)`. For generating the struct and `*Fields`
+//! implementation of an object type. The default is `false`. More info
+//! [here](#generating-structs).
+//! - `@juniper(batch: true|false)`. For loading a field for all the objects in a list with one
+//!   call. The default is `false`. More info [here](#batch-loading).
 //! - `@deprecated`. For deprecating types in your schema. Also supports supplying a reason with
 //! `@deprecated(reason: "...")`
+//! - `@cost(complexity: Int, multipliers: [String!])`. For computing the cost of queries before
+//!   executing them. More info [here](#query-cost-analysis).
+//! - `@connection`. For generating [Relay connection][] types for fields that return lists. More
+//!   info [here](#relay-connections).
+//! - `@juniper(node_interface: true|false)`. For generating global ids and the `Query.node`
+//!   lookups of the Relay `Node` interface. The default is `false`. More info
+//!   [here](#relay-node-interface).
+//! - `@key`, `@extends`, `@external`, `@requires` and `@provides`. For running the schema as an
+//!   [Apollo Federation][] subgraph. More info [here](#apollo-federation).
 //!
 //! [schema directives]: https://www.apollographql.com/docs/apollo-server/schema/directives/
 //!
@@ -946,8 +977,11 @@
 //!     with_time_zone: Boolean = true,
 //!     async: Boolean = false,
 //!     stream_item_infallible: Boolean = true,
-//!     stream_type: String = null
-//! ) on FIELD_DEFINITION | SCALAR
+//!     stream_type: String = null,
+//!     generate_struct: Boolean = false,
+//!     batch: Boolean = false,
+//!     node_interface: Boolean = false
+//! ) on FIELD_DEFINITION | SCALAR | OBJECT | INTERFACE
 //! ```
 //!
 //! This directive definition is allowed in your schema, as well as any other directive definition.
@@ -958,6 +992,9 @@
 //! juniper-from-schema doesn't require to put this in your schema, so you only need to include it
 //! if some other tool requires it.
 //!
+//! The definition can also be printed with `juniper-from-schema print-directive` from
+//! [juniper-from-schema-cli].
+//!
 //! [changelog]: https://github.com/davidpdrsn/juniper-from-schema/blob/master/CHANGELOG.md
 //!
 //! ## Customizing ownership
@@ -1146,6 +1183,433 @@
 //! }
 //! ```
 //!
+//! ## Generating structs
+//!
+//! Object types that are plain data containers can have their struct generated as well by adding
+//! `@juniper(generate_struct: true)` to the type. This generates a `pub struct` with one public
+//! field per GraphQL field, a `new` constructor that takes the fields in schema order, and an
+//! implementation of the `*Fields` trait.
+//!
+//! The struct fields always hold owned values. The generated resolvers respect the `ownership`
+//! and `infallible` arguments of each field, so `ownership: "owned"` requires the field type to
+//! implement `Clone`.
+//!
+//! Fields that take arguments cannot be resolved from a struct field. Instead they are collected
+//! in a `{Type}Resolvers` trait that you have to implement yourself.
+//!
+//! Example:
+//!
+//! ```
+//! # #[macro_use]
+//! # extern crate juniper;
+//! # use juniper_from_schema::*;
+//! # use juniper::*;
+//! # pub struct Context;
+//! # impl juniper::Context for Context {}
+//! # fn main() {}
+//! graphql_schema! {
+//!     schema {
+//!         query: Query
+//!     }
+//!
+//!     type Query {
+//!         user: User! @juniper(ownership: "owned")
+//!     }
+//!
+//!     type User @juniper(generate_struct: true) {
+//!         id: ID!
+//!         name: String @juniper(ownership: "as_ref")
+//!         friends(first: Int!): [User!]! @juniper(ownership: "owned")
+//!     }
+//! }
+//!
+//! pub struct Query;
+//!
+//! impl QueryFields for Query {
+//!     fn field_user(
+//!         &self,
+//!         _: &Executor<Context>,
+//!         _: &QueryTrail<User, Walked>,
+//!     ) -> FieldResult<User> {
+//!         Ok(User::new(ID::new("1"), Some("Bob".to_string())))
+//!     }
+//! }
+//!
+//! // `User` and `impl UserFields for User` are generated, only fields with arguments remain
+//! impl UserResolvers for User {
+//!     fn field_friends(
+//!         &self,
+//!         _: &Executor<Context>,
+//!         _: &QueryTrail<User, Walked>,
+//!         first: i32,
+//!     ) -> FieldResult<Vec<User>> {
+//!         // ...
+//!         # unimplemented!()
+//!     }
+//! }
+//! ```
+//!
+//! `generate_struct` is not supported on subscription types.
+//!
+//! ## Batch loading
+//!
+//! Resolving a field for every object in a list usually means one database query per object.
+//! Fields marked with `@juniper(batch: true)` are instead loaded for all the objects in the list
+//! with one call.
+//!
+//! Batched fields aren't part of the `{Type}Fields` trait. Instead a `{Type}{Field}Batch` trait is
+//! generated for each of them, with
+//!
+//! - an associated `Key` type and a `batch_key` method that returns the key of an object, and
+//! - an async `load_many` function that receives the keys of all the objects in the batch, along
+//!   with the `QueryTrail` and arguments of the field, and returns the values for each key.
+//!
+//! Objects whose key is missing from the returned map resolve to `null` if the field is nullable
+//! and to an error otherwise. The values are cloned for objects with the same key, so they must
+//! implement `Clone`.
+//!
+//! Batches are kept in a [`BatchLoaders`] which the context type must provide by implementing
+//! [`BatchContext`]. Create a new `BatchLoaders` for each request.
+//!
+//! Example:
+//!
+//! ```
+//! # #[macro_use]
+//! # extern crate juniper;
+//! # use juniper_from_schema::*;
+//! # use juniper::*;
+//! # fn main() {}
+//! use async_trait::async_trait;
+//! use std::collections::HashMap;
+//!
+//! graphql_schema! {
+//!     schema {
+//!         query: Query
+//!     }
+//!
+//!     type Query {
+//!         users: [User!]! @juniper(ownership: "owned")
+//!     }
+//!
+//!     type User {
+//!         id: Int! @juniper(ownership: "owned")
+//!         posts(first: Int!): [Post!]! @juniper(batch: true, async: true, ownership: "owned")
+//!     }
+//!
+//!     type Post {
+//!         title: String!
+//!     }
+//! }
+//!
+//! pub struct Context {
+//!     batch_loaders: BatchLoaders,
+//! }
+//!
+//! impl juniper::Context for Context {}
+//!
+//! impl BatchContext for Context {
+//!     fn batch_loaders(&self) -> &BatchLoaders {
+//!         &self.batch_loaders
+//!     }
+//! }
+//!
+//! pub struct Query;
+//!
+//! impl QueryFields for Query {
+//!     fn field_users(
+//!         &self,
+//!         _: &Executor<Context>,
+//!         _: &QueryTrail<User, Walked>,
+//!     ) -> FieldResult<Vec<User>> {
+//!         // ...
+//!         # unimplemented!()
+//!     }
+//! }
+//!
+//! pub struct User {
+//!     id: i32,
+//! }
+//!
+//! impl UserFields for User {
+//!     fn field_id(&self, _: &Executor<Context>) -> FieldResult<i32> {
+//!         Ok(self.id)
+//!     }
+//! }
+//!
+//! #[async_trait]
+//! impl UserPostsBatch for User {
+//!     type Key = i32;
+//!
+//!     fn batch_key(&self) -> i32 {
+//!         self.id
+//!     }
+//!
+//!     // Called once with the ids of all the users in the list
+//!     async fn load_many<'r, 'a>(
+//!         _: &Executor<'r, 'a, Context>,
+//!         _: &QueryTrail<'r, Post, Walked>,
+//!         user_ids: &[i32],
+//!         first: i32,
+//!     ) -> FieldResult<HashMap<i32, Vec<Post>>> {
+//!         // ...
+//!         # unimplemented!()
+//!     }
+//! }
+//!
+//! #[derive(Clone)]
+//! pub struct Post {
+//!     title: String,
+//! }
+//!
+//! impl PostFields for Post {
+//!     fn field_title(&self, _: &Executor<Context>) -> FieldResult<&String> {
+//!         Ok(&self.title)
+//!     }
+//! }
+//! ```
+//!
+//! A batch contains the objects that select the field at the same place in the query with the
+//! same arguments, so aliases of the field are loaded in separate batches with their own
+//! arguments. `load_many` receives the executor and `QueryTrail` of the first object in the
+//! batch, which only differ from the others in their path through the query.
+//!
+//! Batched fields must be async and owned, and can't be infallible. They aren't supported on
+//! interfaces and subscriptions. Batch traits aren't included in generated scaffolds.
+//!
+//! ## Relay connections
+//!
+//! Fields that return lists can be paginated with [Relay connections][Relay connection] by marking
+//! them with `@connection`. For a field like `users: [User!]! @connection` the schema is expanded
+//! with
+//!
+//! - the `first: Int, after: String, last: Int, before: String` arguments on the field,
+//! - `UserConnection` and `UserEdge` types, and a `PageInfo` type shared by all connections, and
+//! - the field returning `UserConnection!` instead of `[User!]!`.
+//!
+//! The connection types are generated with `@juniper(generate_struct: true)`, so you don't have to
+//! write them. Instead the resolver returns a [`Connection`] with the items and their cursors,
+//! which is converted to the generated types.
+//!
+//! A schema that defines its own `PageInfo` type keeps it, as long as its fields are exactly
+//! `hasNextPage: Boolean!`, `hasPreviousPage: Boolean!`, `startCursor: String`, and
+//! `endCursor: String`, in that order. Its struct is built with
+//! `PageInfo::new(has_next_page, has_previous_page, start_cursor, end_cursor)`, which
+//! `@juniper(generate_struct: true)` generates. The directive is defined as
+//!
+//! ```graphql
+//! directive @connection on FIELD_DEFINITION
+//! ```
+//!
+//! Example:
+//!
+//! ```
+//! # #[macro_use]
+//! # extern crate juniper;
+//! # use juniper_from_schema::*;
+//! # use juniper::*;
+//! # fn main() {}
+//! # pub struct Context;
+//! # impl juniper::Context for Context {}
+//! # pub struct User { id: i32 }
+//! # impl UserFields for User {
+//! #     fn field_id(&self, _: &Executor<Context>) -> FieldResult<i32> { Ok(self.id) }
+//! # }
+//! graphql_schema! {
+//!     schema {
+//!         query: Query
+//!     }
+//!
+//!     type Query {
+//!         users: [User!]! @connection
+//!     }
+//!
+//!     type User {
+//!         id: Int! @juniper(ownership: "owned")
+//!     }
+//! }
+//!
+//! pub struct Query;
+//!
+//! impl QueryFields for Query {
+//!     fn field_users(
+//!         &self,
+//!         _: &Executor<Context>,
+//!         trail: &QueryTrail<UserConnection, Walked>,
+//!         first: Option<i32>,
+//!         after: Option<String>,
+//!         last: Option<i32>,
+//!         before: Option<String>,
+//!     ) -> FieldResult<Connection<User>> {
+//!         // Check if the users themselves were selected or only the page info
+//!         let users_selected = trail.edges().node().walk().is_some();
+//!
+//!         Ok(Connection::new()
+//!             .edge("cursor-1", User { id: 1 })
+//!             .edge("cursor-2", User { id: 2 })
+//!             .has_next_page(true))
+//!     }
+//! }
+//! ```
+//!
+//! The start and end cursors of the page are taken from the first and last edges. Query trails
+//! for connection fields walk through the generated types, so `trail.edges().node()` selects the
+//! items and `trail.page_info()` the page info.
+//!
+//! `@connection` isn't supported on interfaces and subscriptions, and connection fields can't be
+//! batched. The `ownership` of connection fields is ignored since the generated types are always
+//! owned.
+//!
+//! [Relay connection]: https://relay.dev/graphql/connections.htm
+//!
+//! ## Relay node interface
+//!
+//! Marking an interface with `@juniper(node_interface: true)` makes it the [Relay `Node`
+//! interface][]. It must have an `id: ID!` field, and it generates
+//!
+//! - an implementation of [`NodeType`] for each implementor, so [`GlobalId`] can encode and
+//!   decode their global ids, and
+//! - a `NodeResolver` trait with an async `resolve_{type}` method for each implementor.
+//!
+//! The `node(id: ID!): Node` and `nodes(ids: [ID!]!): [Node]!` fields of the query type, if
+//! present, are resolved by decoding the type from the global id and calling the method of that
+//! type on the `NodeResolver` implementation of the query type. They aren't part of the
+//! `QueryFields` trait and must be async.
+//!
+//! Example:
+//!
+//! ```
+//! # #[macro_use]
+//! # extern crate juniper;
+//! # use juniper_from_schema::*;
+//! # use juniper::*;
+//! # fn main() {}
+//! # pub struct Context;
+//! # impl juniper::Context for Context {}
+//! graphql_schema! {
+//!     schema {
+//!         query: Query
+//!     }
+//!
+//!     type Query {
+//!         node(id: ID!): Node @juniper(async: true)
+//!     }
+//!
+//!     interface Node @juniper(node_interface: true) {
+//!         id: ID! @juniper(ownership: "owned")
+//!     }
+//!
+//!     type User implements Node {
+//!         id: ID! @juniper(ownership: "owned")
+//!     }
+//! }
+//!
+//! pub struct Query;
+//!
+//! #[juniper_from_schema::juniper::async_trait]
+//! impl NodeResolver for Query {
+//!     async fn resolve_user<'s, 'r, 'a>(
+//!         &'s self,
+//!         _: &Executor<'r, 'a, Context>,
+//!         _: &QueryTrail<'r, User, Walked>,
+//!         id: GlobalId<User>,
+//!     ) -> FieldResult<Option<User>> {
+//!         let id = id.id().parse()?;
+//!         Ok(Some(User { id }))
+//!     }
+//! }
+//!
+//! pub struct User {
+//!     id: i32,
+//! }
+//!
+//! impl UserFields for User {
+//!     fn field_id(&self, _: &Executor<Context>) -> FieldResult<ID> {
+//!         Ok(GlobalId::<User>::new(self.id.to_string()).encode())
+//!     }
+//! }
+//! ```
+//!
+//! Global ids are the base64 encoding of `{Type}:{id}`. Malformed ids are errors and ids of
+//! unknown types resolve to `null`.
+//!
+//! [Relay `Node` interface]: https://relay.dev/graphql/objectidentification.htm
+//!
+//! ## Apollo Federation
+//!
+//! Schemas that use `@key` on an object type are [Apollo Federation][] subgraphs. The federation
+//! directives `@key(fields: "...")`, `@extends`, `@external`, `@requires(fields: "...")` and
+//! `@provides(fields: "...")` don't have to be defined in the schema, and for each type with a
+//! `@key` the generated code contains
+//!
+//! - a variant of the `_Entity` union, and
+//! - a `{Type}ReferenceResolver` trait with an async `resolve_reference` method that looks up an
+//!   entity from its [`Representation`].
+//!
+//! The query type also gets the `_service { sdl }` and `_entities(representations: [_Any!]!)`
+//! fields. `_service` returns the schema without the `@juniper`, `@cost` and `@connection`
+//! directives, and `_entities` calls `resolve_reference` for the `__typename` of each
+//! representation. Neither field is part of the `QueryFields` trait.
+//!
+//! Example:
+//!
+//! ```
+//! # #[macro_use]
+//! # extern crate juniper;
+//! # use juniper_from_schema::*;
+//! # use juniper::*;
+//! # fn main() {}
+//! # pub struct Context;
+//! # impl juniper::Context for Context {}
+//! graphql_schema! {
+//!     schema {
+//!         query: Query
+//!     }
+//!
+//!     type Query {
+//!         me: User! @juniper(ownership: "owned")
+//!     }
+//!
+//!     type User @key(fields: "id") {
+//!         id: ID! @juniper(ownership: "owned")
+//!     }
+//! }
+//!
+//! pub struct Query;
+//!
+//! impl QueryFields for Query {
+//!     fn field_me(&self, _: &Executor<Context>, _: &QueryTrail<User, Walked>) -> FieldResult<User> {
+//!         Ok(User { id: ID::new("1") })
+//!     }
+//! }
+//!
+//! pub struct User {
+//!     id: ID,
+//! }
+//!
+//! impl UserFields for User {
+//!     fn field_id(&self, _: &Executor<Context>) -> FieldResult<ID> {
+//!         Ok(self.id.clone())
+//!     }
+//! }
+//!
+//! #[juniper_from_schema::juniper::async_trait]
+//! impl UserReferenceResolver for User {
+//!     async fn resolve_reference<'r, 'a>(
+//!         _: &Executor<'r, 'a, Context>,
+//!         _: &QueryTrail<'r, User, Walked>,
+//!         representation: &Representation,
+//!     ) -> FieldResult<Option<User>> {
+//!         Ok(representation.get("id").map(|id| User { id }))
+//!     }
+//! }
+//! ```
+//!
+//! Gateways send the representations as objects, which juniper doesn't accept for scalars, so
+//! requests must go through [`prepare_federation_request`] before they are executed. Unknown
+//! entity types are errors.
+//!
+//! [Apollo Federation]: https://www.apollographql.com/docs/federation/
+//!
 //! # GraphQL to Rust types
 //!
 //! This is how the standard GraphQL types will be mapped to Rust:
@@ -1453,6 +1917,12 @@
 //! This can be done by calling `.downcast()` which automatically gets implemented for interface and
 //! union query trails. See above for an example.
 //!
+//! Downcasting is also how you reach fields that the members of a union declare with different
+//! types or arguments. If `User.country` is a `Country!` but `Company.country` is a
+//! `CountryOfOperation!`, then `QueryTrail<'r, Entity, Walked>` has no `country` method, since it
+//! would have to return a different type for each member. Fields that agree on every member, such as
+//! `id: Int!` on both, get methods on the union trail as usual.
+//!
 //! ## `QueryTrail`s for fields that take arguments
 //!
 //! Sometimes you have GraphQL fields that take arguments that impact which things your resolvers
@@ -1596,6 +2066,24 @@
 //! The name of the arguments struct will always be `{name of type}{name of field}Args` (e.g.
 //! `CountryUsersArgs`). The method names will always be the name of the arguments in snake case.
 //!
+//! Arguments are coerced the same way juniper coerces them before calling your resolver, following
+//! the [input coercion rules](https://spec.graphql.org/June2018/#sec-Input-Values) of the spec. So
+//! an `Int` passed for a `Float` argument is converted, a single value passed for a list becomes a
+//! list with one item, an omitted nullable argument is `None`, and an omitted or `null` argument
+//! with a default value gets the default.
+//!
+//! The argument methods panic if the field isn't selected in the query, or if the argument can't
+//! be converted to its Rust type. Each one has a `try_` version, such as `try_active_since`, that
+//! returns a `Result<_, QueryTrailError>` instead:
+//!
+//! ```ignore
+//! let active_since: DateTime<Utc> = trail.users_args().try_active_since()?;
+//! ```
+//!
+//! [`QueryTrailError`] implements `Display`, so `?` converts it into a `juniper::FieldError`.
+//!
+//! [`QueryTrailError`]: enum.QueryTrailError.html
+//!
 //! The `*_args` method is only defined on `Walked` query trails so if you get an error like:
 //!
 //! ```text
@@ -1615,6 +2103,304 @@
 //! Remember that you can always run `cargo doc` to get a high level overview of the generated
 //! code.
 //!
+//! ## Fields selected more than once
+//!
+//! A query can select the same field several times using aliases, for example with different
+//! arguments:
+//!
+//! ```graphql
+//! query {
+//!     user {
+//!         small: avatar(size: 32) { url }
+//!         big: avatar(size: 256) { url }
+//!     }
+//! }
+//! ```
+//!
+//! Methods such as `trail.avatar()` and `trail.avatar_args()` only look at the first occurrence.
+//! To see all of them use `trail.avatar_selections()`, which returns an iterator of
+//! [`Selection`][]s. Each has the alias, the arguments struct, and for fields that return objects,
+//! interfaces, or unions a walked `QueryTrail`:
+//!
+//! ```
+//! # #[macro_use]
+//! # extern crate juniper;
+//! # use juniper_from_schema::*;
+//! # pub struct Context;
+//! # impl juniper::Context for Context {}
+//! # fn main() {}
+//! # pub struct User {}
+//! # impl UserFields for User {
+//! #     fn field_avatar(
+//! #         &self,
+//! #         executor: &juniper::Executor<Context>,
+//! #         trail: &QueryTrail<Image, Walked>,
+//! #         size: i32,
+//! #     ) -> juniper::FieldResult<Image> {
+//! #         unimplemented!()
+//! #     }
+//! # }
+//! # pub struct Image {}
+//! # impl ImageFields for Image {
+//! #     fn field_url(
+//! #         &self,
+//! #         executor: &juniper::Executor<Context>,
+//! #     ) -> juniper::FieldResult<String> {
+//! #         unimplemented!()
+//! #     }
+//! # }
+//! graphql_schema! {
+//!     schema {
+//!         query: Query
+//!     }
+//!
+//!     type Query {
+//!         user: User! @juniper(ownership: "owned")
+//!     }
+//!
+//!     type User {
+//!         avatar(size: Int!): Image! @juniper(ownership: "owned")
+//!     }
+//!
+//!     type Image {
+//!         url: String! @juniper(ownership: "owned")
+//!     }
+//! }
+//!
+//! pub struct Query;
+//!
+//! impl QueryFields for Query {
+//!     fn field_user(
+//!         &self,
+//!         executor: &juniper::Executor<Context>,
+//!         trail: &QueryTrail<User, Walked>,
+//!     ) -> juniper::FieldResult<User> {
+//!         for selection in trail.avatar_selections() {
+//!             // `Some("small")` and `Some("big")` for the query above
+//!             let _: Option<&str> = selection.alias();
+//!             let _: i32 = selection.args().size();
+//!             let _: bool = selection.trail().url();
+//!         }
+//!
+//!         # unimplemented!()
+//!         // ...
+//!     }
+//! }
+//! ```
+//!
+//! Fields that are only selected once, without an alias, give a single `Selection` whose alias is
+//! `None`.
+//!
+//! [`Selection`]: struct.Selection.html
+//!
+//! ## Listing the selected fields
+//!
+//! Every object, interface, and union gets an enum with a variant for each of its fields. The enums
+//! live in the `fields` module and are named after their type (e.g. `fields::User`), so they don't
+//! clash with the types in your schema. `trail.selected_fields()` returns the fields selected in
+//! the query, in the order they are selected. Variants for fields that return objects, interfaces,
+//! or unions hold the walked `QueryTrail` of the field.
+//!
+//! This is useful for building things like SQL `SELECT` lists. Matching on the enum without a
+//! wildcard makes the compiler tell you when new fields are added to the schema:
+//!
+//! ```ignore
+//! let mut columns = vec![];
+//! for field in trail.selected_fields() {
+//!     match field {
+//!         fields::User::Name => columns.push("users.name"),
+//!         fields::User::Email => columns.push("users.email"),
+//!         fields::User::Posts(posts) => {
+//!             for field in posts.selected_fields() {
+//!                 match field {
+//!                     fields::Post::Title => columns.push("posts.title"),
+//!                 }
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! A field selected several times under different aliases is only included once. Use
+//! [`*_selections`](#fields-selected-more-than-once) to see every occurrence.
+//!
+//! ## `QueryTrail`s for mutations and subscriptions
+//!
+//! Mutation and subscription fields get a `QueryTrail` just like query fields. For mutations it is
+//! the trail of the payload the mutation returns, so the resolver knows which parts of the payload
+//! the client selected.
+//!
+//! For subscriptions it is the trail of the stream items. The same selection applies to every item,
+//! so the resolver can decide up front which data sources to subscribe to:
+//!
+//! ```ignore
+//! #[async_trait]
+//! impl SubscriptionFields for Subscription {
+//!     async fn field_user_events<'s, 'r, 'a>(
+//!         &'s self,
+//!         executor: &Executor<'r, 'a, Context>,
+//!         trail: &QueryTrail<'r, UserEvent, Walked>,
+//!     ) -> FieldResult<Pin<Box<dyn Stream<Item = UserEvent> + Send>>> {
+//!         // The trail borrows the executor, so read what you need before returning the stream
+//!         let include_avatars = trail.user().walk().map_or(false, |user| user.avatar());
+//!
+//!         Ok(Box::pin(user_events(include_avatars)))
+//!     }
+//! }
+//! ```
+//!
+//! Each item is then resolved with the fields selected on it, and fields of the items that return
+//! objects get their own `QueryTrail` as usual.
+//!
+//! ## Sending selections to other services
+//!
+//! A `QueryTrail` borrows from the query being executed, so it can't be stored or sent to another
+//! service. `trail.to_selection_tree()` takes an owned snapshot of the selected fields and their
+//! arguments, as a [`SelectionTree`]. It implements `serde::Serialize` and `serde::Deserialize`.
+//!
+//! Every object, interface, and union gets a `SelectionTree{name of type}Extensions` trait with
+//! methods for inspecting the tree the same way as a `QueryTrail`. Walking into a field returns an
+//! `Option` since the field might not be selected, and the arguments structs are the same as for
+//! `QueryTrail`s:
+//!
+//! ```ignore
+//! // In the GraphQL server
+//! let request = serde_json::to_string(&trail.to_selection_tree())?;
+//!
+//! // In the service receiving the request, which includes the same schema
+//! let tree: SelectionTree<User> = serde_json::from_str(&request)?;
+//!
+//! if tree.avatar() {
+//!     let size = tree.avatar_args().size();
+//! }
+//!
+//! if let Some(posts) = tree.posts() {
+//!     let include_title = posts.title();
+//! }
+//! ```
+//!
+//! Interface and union trees are downcast with `DowncastSelectionTree`, like with
+//! [`QueryTrail`s](#downcasting-for-interface-and-union-querytrails).
+//!
+//! [`SelectionTree::iter`] returns the raw [`SelectedField`]s, including every alias a field is
+//! selected under. That is useful for services that don't include the schema.
+//!
+//! # Query cost analysis
+//!
+//! The `@cost` directive assigns costs to fields, which can be used to reject expensive queries
+//! before resolving them:
+//!
+//! - `@cost(complexity: 2)` on a field makes the field cost 2. Every field costs 1 by default.
+//! - `@cost(complexity: 5)` on an object type makes fields that return the type cost 5, unless
+//!   the field has a complexity of its own.
+//! - `@cost(multipliers: ["first"])` on a field multiplies the cost of the field, including the
+//!   fields selected on it, by the value of the `first` argument. Multipliers must name `Int`
+//!   arguments of the field. Arguments that aren't passed, and don't have a default value, don't
+//!   change the cost.
+//!
+//! A field with `@cost(complexity: 2, multipliers: ["first"])` selected as `users(first: 10)`,
+//! with two fields selected on it that cost 1 each, costs `(2 + 1 + 1) * 10 = 40`.
+//!
+//! `query_cost` computes the cost of a field of the query type from the `juniper::LookAheadSelection`
+//! of the field, along with the depth of the deepest selection. It reads the arguments the same way
+//! `QueryTrail` does, so variables, aliases, and default values are taken into account:
+//!
+//! ```
+//! # #[macro_use]
+//! # extern crate juniper;
+//! # use juniper::*;
+//! # use juniper_from_schema::graphql_schema;
+//! # fn main() {}
+//! # pub struct Context;
+//! # impl juniper::Context for Context {}
+//! # pub struct User;
+//! # impl UserFields for User {
+//! #     fn field_name(&self, executor: &Executor<Context>) -> FieldResult<&String> {
+//! #         unimplemented!()
+//! #     }
+//! #     fn field_friends(
+//! #         &self,
+//! #         executor: &Executor<Context>,
+//! #         trail: &QueryTrail<User, Walked>,
+//! #         first: i32,
+//! #     ) -> FieldResult<Vec<User>> {
+//! #         unimplemented!()
+//! #     }
+//! # }
+//! graphql_schema! {
+//!     type Query {
+//!         users(first: Int = 10): [User!]!
+//!             @juniper(ownership: "owned")
+//!             @cost(complexity: 2, multipliers: ["first"])
+//!     }
+//!
+//!     type User @cost(complexity: 3) {
+//!         name: String!
+//!         friends(first: Int = 10): [User!]!
+//!             @juniper(ownership: "owned")
+//!             @cost(multipliers: ["first"])
+//!     }
+//!
+//!     schema { query: Query }
+//! }
+//!
+//! pub struct Query;
+//!
+//! impl QueryFields for Query {
+//!     fn field_users(
+//!         &self,
+//!         executor: &Executor<Context>,
+//!         trail: &QueryTrail<User, Walked>,
+//!         first: i32,
+//!     ) -> FieldResult<Vec<User>> {
+//!         let cost = query_cost(&executor.look_ahead());
+//!
+//!         if cost.cost() > 1000 || cost.depth() > 5 {
+//!             return Err(FieldError::from("Query is too expensive"));
+//!         }
+//!
+//!         // ...
+//!         # unimplemented!()
+//!     }
+//! }
+//! ```
+//!
+//! `trail.query_cost()`, from the `QueryTrailCostExtensions` trait, computes the cost of the fields
+//! selected on any `QueryTrail`. Fields of interfaces and unions that are selected with fragments
+//! are costed by the first possible type that has them.
+//!
+//! juniper only gives resolvers the look-ahead of their own field, so `query_cost` can't see the
+//! other root fields of the query. To limit the cost of the whole query, parse it with juniper's
+//! parser and check it with `schema.query_cost(..)`, from the `SchemaCostExtensions` trait, before
+//! executing it. That sums the costs of all the root fields selected by the operation and reports
+//! the depth of the deepest one:
+//!
+//! ```ignore
+//! let schema = Schema::new(Query, EmptyMutation::new(), EmptySubscription::new());
+//! let document = juniper::parser::parse_document_source(query, &schema.schema)?;
+//!
+//! let cost = schema.query_cost(&document, operation_name, &variables)?;
+//! if cost.cost() > 1000 || cost.depth() > 5 {
+//!     return Err("Query is too expensive".into());
+//! }
+//!
+//! let (value, errors) = juniper::execute(query, operation_name, &schema, &variables, &context).await?;
+//! ```
+//!
+//! See [`QueryCost`] for more details.
+//!
+//! The definition of `@cost`, which schemas are allowed to include for the benefit of other tools,
+//! is:
+//!
+//! ```graphql
+//! directive @cost(
+//!     complexity: Int,
+//!     multipliers: [String!]
+//! ) on FIELD_DEFINITION | OBJECT
+//! ```
+//!
+//! [`QueryCost`]: struct.QueryCost.html
+//!
 //! # Customizing the error type
 //!
 //! By default the return type of the generated field methods will be [`juniper::FieldResult<T>`].
@@ -1665,7 +2451,7 @@
 //! }
 //! ```
 //!
-//! [`graphql_schema!`] does not support changing the error type.
+//! [`graphql_schema!`] accepts the same config before the schema, as `error_type: YourType,`.
 //!
 //! [`graphql_schema!`]: macro.graphql_schema.html
 //! [`graphql_schema_from_file!`]: macro.graphql_schema_from_file.html
@@ -1710,24 +2496,142 @@
 //! }
 //! ```
 //!
-//! [`graphql_schema!`] does not support changing the context type.
+//! [`graphql_schema!`] accepts the same config before the schema, as `context_type: NewName,`.
 //!
 //! [`graphql_schema!`]: macro.graphql_schema.html
 //! [`graphql_schema_from_file!`]: macro.graphql_schema_from_file.html
 //!
+//! # Generating code inside a module
+//!
+//! [`graphql_schema_from_file!`] generates the types, traits, `query_trails` module, and `Schema`
+//! type alias where it is called. To keep them apart from the rest of your code, use the
+//! [`schema`] attribute on an empty module instead:
+//!
+//! ```ignore
+//! #[juniper_from_schema::schema(
+//!     file = "schema.graphql",
+//!     context_type = Context,
+//!     error_type = MyError,
+//!     visibility = pub(crate),
+//! )]
+//! pub mod api {
+//!     pub(crate) use crate::resolvers::Query;
+//! }
+//! ```
+//!
+//! The code is generated inside `api`. Everything from the parent module is in scope there, along
+//! with any items already in the module, which is useful for bringing in types defined elsewhere.
+//! The `*Fields` traits can then be implemented in other modules:
+//!
+//! ```ignore
+//! mod resolvers {
+//!     use crate::api;
+//!
+//!     pub struct Query;
+//!
+//!     impl api::QueryFields for Query {
+//!         // ...
+//!     }
+//! }
+//! ```
+//!
+//! `visibility` sets the visibility of the generated items. It defaults to `pub`.
+//!
+//! [`schema`]: attr.schema.html
+//! [`graphql_schema_from_file!`]: macro.graphql_schema_from_file.html
+//!
+//! # Lints
+//!
+//! Besides the checks that are always errors, such as snake cased field names, a number of
+//! checks can be turned on. They are all set to `allow` by default:
+//!
+//! | Lint | Code | Checks that |
+//! |---|---|---|
+//! | `missing_description` | `JFS1001` | Types have descriptions |
+//! | `enum_value_case` | `JFS1002` | Enum values are in SCREAMING_SNAKE_CASE |
+//! | `mutation_name` | `JFS1003` | Mutations are named verb+noun, such as `createUser`, rather than just `user` |
+//! | `input_type_suffix` | `JFS1004` | Input object names end with `Input` |
+//! | `list_nesting_depth` | `JFS1005` | Lists aren't nested deeper than `max_list_nesting_depth`, which defaults to 1 |
+//! | `deprecated_without_reason` | `JFS1006` | `@deprecated` has a reason |
+//! | `unused_type` | `JFS1007` | Types are reachable from the `query`, `mutation`, or `subscription` types |
+//! | `prelude_shadowing` | `JFS1008` | Type names don't shadow the Rust prelude, such as `Result` or `Option` |
+//!
+//! Lints set to `deny` fail the compilation and lints set to `warn` are reported as warnings.
+//! Warnings don't fail the build. Since procedural macros can't emit warnings on stable Rust, the
+//! macros report each warning as the use of a deprecated item, so it shows up as a `deprecated`
+//! warning pointing at the macro call. The levels can be set with a directive on the schema
+//! definition:
+//!
+//! ```graphql
+//! schema @lints(
+//!   deny: ["missing_description", "enum_value_case"]
+//!   warn: ["list_nesting_depth"]
+//!   max_list_nesting_depth: 2
+//! ) {
+//!   query: Query
+//! }
+//! ```
+//!
+//! Or with the macros, which take precedence over the directive:
+//!
+//! ```ignore
+//! juniper_from_schema::graphql_schema_from_file!(
+//!     "schema.graphql",
+//!     deny: [missing_description, enum_value_case],
+//!     warn: [deprecated_without_reason],
+//! );
+//!
+//! #[juniper_from_schema::schema(file = "schema.graphql", deny = [input_type_suffix])]
+//! mod api {}
+//! ```
+//!
+//! Or with [juniper-from-schema-build], which prints the warnings as `cargo:warning`:
+//!
+//! ```ignore
+//! juniper_from_schema_build::configure_for_file("schema.graphql")
+//!     .deny("missing_description")
+//!     .warn("mutation_name")
+//!     .compile()?;
+//! ```
+//!
+//! Lints can also be referred to by their code, such as `deny: [JFS1001]`.
+//!
+//! # Error codes
+//!
+//! Every error and warning has a code, such as `JFS0015`, which is printed with the message:
+//!
+//! ```text
+//! error[JFS0015]: Field names must be camelCase, not snake_case
+//!  --> schema:6:3
+//! ```
+//!
+//! Codes never change meaning between releases. A longer explanation, usually with an example of
+//! how to fix the problem, is printed by [juniper-from-schema-cli]:
+//!
+//! ```bash
+//! juniper-from-schema explain JFS0015
+//! ```
+//!
 //! # Inspecting the generated code
 //!
-//! If you wish to see exactly what code gets generated you can set the env var
-//! `JUNIPER_FROM_SCHEMA_DEBUG` to `1` when compiling. For example:
+//! If you wish to see exactly what code gets generated you can use the `juniper-from-schema`
+//! command line tool from [juniper-from-schema-cli]. For example:
 //!
 //! ```bash
-//! JUNIPER_FROM_SCHEMA_DEBUG=1 cargo build
+//! cargo install juniper-from-schema-cli
+//! juniper-from-schema generate schema.graphql --context-type Context
 //! ```
 //!
-//! The code will not be formatted so it might be tricky to read. The easiest way to fix this is to
-//! copy the printed code to a file and run it through [rustfmt].
+//! The code is formatted and printed to stdout, or written to a file with `--output`. The tool
+//! also supports `check` for validating a schema, `scaffold` for generating stub resolvers (see
+//! [Scaffolding resolvers](#scaffolding-resolvers)), and `print-directive` for printing the
+//! definitions of [`@juniper`](#definition-for-juniper) and [`@cost`](#query-cost-analysis).
 //!
-//! [rustfmt]: https://github.com/rust-lang/rustfmt
+//! `check --message-format json` prints the errors and warnings as JSON, with the file and the
+//! line and column range of each problem, which is useful for editor plugins and CI annotations.
+//! `--message-format sarif` prints a [SARIF](https://sarifweb.azurewebsites.net) log instead.
+//!
+//! [juniper-from-schema-cli]: https://crates.io/crates/juniper-from-schema-cli
 //!
 //! # Generating code in "build.rs"
 //!
@@ -1735,73 +2639,1670 @@
 //! from a "build.rs" file. Add [juniper-from-schema-build] as a build dependency and call the
 //! appropriate function. See its docs for examples and more info.
 //!
+//! Several schemas can be compiled from the same "build.rs" file by giving each of them an output
+//! name, and included with `juniper_from_schema::include_schema!("<name>")`.
+//!
 //! [juniper-from-schema-build]: https://crates.io/crates/juniper-from-schema-build
-
-#![deny(
-    missing_docs,
-    unused_imports,
-    dead_code,
-    unused_variables,
-    unused_must_use
-)]
-#![doc(html_root_url = "https://docs.rs/juniper-from-schema/0.5.2")]
-
-use juniper::{DefaultScalarValue, LookAheadSelection};
-use std::marker::PhantomData;
-
-// re-export juniper here so we're sure to use the same version everywhere
-#[doc(hidden)]
-pub use futures;
-#[doc(hidden)]
-pub use juniper;
-
-pub use juniper_from_schema_proc_macro::{graphql_schema, graphql_schema_from_file};
-
-/// A type used to parameterize `QueryTrail` to know that `walk` has been called.
-pub struct Walked;
-
-/// A type used to parameterize `QueryTrail` to know that `walk` has *not* been called.
-pub struct NotWalked;
-
-/// A wrapper around a `juniper::LookAheadSelection` with methods for each possible child.
-pub struct QueryTrail<'r, T, K> {
-    // These fields are required by the macros but you shouldn't rely them. They might change
-    // without a major version increase.
-    #[doc(hidden)]
-    pub look_ahead: Option<&'r LookAheadSelection<'r, DefaultScalarValue>>,
-    #[doc(hidden)]
-    pub node_type: PhantomData<T>,
-    #[doc(hidden)]
-    pub walked: K,
-}
-
-impl<'r, T> QueryTrail<'r, T, NotWalked> {
-    /// Check if the trail is present in the query being executed
-    pub fn walk(self) -> Option<QueryTrail<'r, T, Walked>> {
-        match self.look_ahead {
-            Some(inner) => Some(QueryTrail {
-                look_ahead: Some(inner),
-                node_type: self.node_type,
-                walked: Walked,
-            }),
-            None => None,
-        }
-    }
-}
-
-impl<'r, T, K> QueryTrail<'r, T, K> {
-    #[allow(clippy::new_ret_no_self)]
+//!
+//! # Scaffolding resolvers
+//!
+//! Writing the `*Fields` implementations by hand means getting the lifetimes, `QueryTrail`
+//! parameters, and return types exactly right. [juniper-from-schema-build] can generate a
+//! formatted Rust file with `todo!()` implementations of every `*Fields` trait for you. The
+//! `*Resolvers`, `*ReferenceResolver`, and `NodeResolver` traits are included too, but the traits
+//! of batched fields aren't, since you have to choose their `Key` type:
+//!
+//! ```ignore
+//! let code = juniper_from_schema_build::configure_for_file("schema.graphql")
+//!     .context_type("crate::Context")
+//!     .scaffold()?;
+//!
+//! std::fs::write("src/resolvers.rs", code)?;
+//! ```
+//!
+//! Use `scaffold_missing(&existing_code)` instead to only get the methods that aren't already
+//! implemented in `existing_code`. That is useful after adding new fields to the schema. Traits
+//! that aren't implemented at all get a complete `impl` block, but for traits that are already
+//! implemented only the missing methods are included, since a second `impl` block wouldn't
+//! compile. They follow a comment naming the `impl` block to copy them into:
+//!
+//! ```ignore
+//! // Add these methods to the existing `impl QueryFields for Query` block
+//! fn field_ping<'s, 'r, 'a>(
+//!     &'s self,
+//!     executor: &juniper_from_schema::juniper::Executor<'r, 'a, crate::Context>,
+//! ) -> std::result::Result<&bool, juniper::FieldError> {
+//!     todo!()
+//! }
+//! ```
+//!
+//! The same is available from the command line with `juniper-from-schema scaffold` and its
+//! `--missing-from` option.
+//!
+//! # Generating client code
+//!
+//! [juniper-from-schema-build] can also generate typed client code for GraphQL operations, which
+//! is useful for services and tests consuming the API from Rust:
+//!
+//! ```ignore
+//! juniper_from_schema_build::configure_for_file("schema.graphql")
+//!     .output_name("client")
+//!     .compile_client("operations.graphql")?;
+//! ```
+//!
+//! The operations are validated against the schema, and the build fails with the usual errors if
+//! they select fields that don't exist, leave out required arguments, use undefined variables, and
+//! so on.
+//!
+//! Each named query and mutation gets a module named after it. Given this operation:
+//!
+//! ```graphql
+//! query GetUser($id: ID!) {
+//!   user(id: $id) {
+//!     id
+//!     displayName: name
+//!   }
+//! }
+//! ```
+//!
+//! The module `get_user` contains:
+//!
+//! - `QUERY` and `OPERATION_NAME` constants.
+//! - A `Variables` struct with a field for each variable.
+//! - `request_body(variables)` which returns a serializable `RequestBody` to send to the server.
+//! - A `ResponseData` struct to deserialize the `data` of the response into. Nested selections get
+//!   their own structs, such as `ResponseDataUser`, named after the path to them.
+//!
+//! Nullable fields become `Option`s, lists become `Vec`s, and scalars map to the same Rust types as
+//! in the server code. Enums, input objects, and custom scalars from the schema are generated next
+//! to the operation modules, deriving `Serialize` and `Deserialize`. Selections on interfaces and
+//! unions with type conditions become enums with a variant per type. They must select
+//! `__typename`, which decides the variant.
+//!
+//! Since the client code contains types with the same names as the server code, include it in its
+//! own module:
+//!
+//! ```ignore
+//! pub mod client {
+//!     juniper_from_schema::include_schema!("client");
+//! }
+//! ```
+//!
+//! The same is available from the command line with `juniper-from-schema client`.
+//!
+//! # Persisted queries
+//!
+//! If your clients ship persisted queries, [juniper-from-schema-build] can check them against the
+//! schema so changes that break a shipped operation fail the build:
+//!
+//! ```ignore
+//! juniper_from_schema_build::configure_for_file("schema.graphql")
+//!     .persisted_queries("persisted")
+//!     .compile()?;
+//! ```
+//!
+//! Every `.graphql` file in the directory is validated like the operations used for client code,
+//! except that operations don't need names. That includes checking that arguments have the right
+//! types and that variables are compatible with the arguments they're used for.
+//!
+//! The generated code then contains an allowlist of the documents, keyed by the hex encoded
+//! SHA-256 hash of the file contents:
+//!
+//! ```ignore
+//! pub static PERSISTED_QUERIES: &[(&str, &str)] = &[(hash, document), ...];
+//! ```
+//!
+//! # Detecting breaking changes
+//!
+//! Changes to a schema are classified as:
+//!
+//! - Breaking: Operations that used to be valid might fail. For example removing a field, making a
+//!   field nullable, adding a required argument, or removing an enum value or a union member.
+//! - Dangerous: Operations remain valid but clients might not expect the responses. For example
+//!   adding an enum value, a union member, or an optional argument.
+//! - Safe: For example adding a type or a field.
+//!
+//! To fail the build when the schema changes incompatibly from a committed baseline, such as the
+//! schema currently deployed, use [juniper-from-schema-build]:
+//!
+//! ```ignore
+//! juniper_from_schema_build::configure_for_file("schema.graphql")
+//!     .baseline("baseline.graphql")
+//!     .compile()?;
+//! ```
+//!
+//! Or from the command line, which also lists the dangerous and safe changes:
+//!
+//! ```text
+//! juniper-from-schema diff baseline.graphql schema.graphql
+//! ```
+
+#![deny(
+    missing_docs,
+    unused_imports,
+    dead_code,
+    unused_variables,
+    unused_must_use
+)]
+#![doc(html_root_url = "https://docs.rs/juniper-from-schema/0.5.2")]
+
+use futures::future::poll_fn;
+use juniper::{
+    executor::get_operation, http::GraphQLRequest, DefaultScalarValue, Definition, Document,
+    FieldError, GraphQLError, InputValue, LookAheadMethods, LookAheadSelection, LookAheadValue,
+    OperationType, Variables,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    any::Any,
+    collections::{BTreeMap, HashMap, HashSet},
+    convert::TryFrom,
+    error::Error,
+    fmt,
+    future::Future,
+    hash::Hash,
+    marker::PhantomData,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Context, Poll, Waker},
+};
+
+// re-export juniper here so we're sure to use the same version everywhere
+#[doc(hidden)]
+pub use futures;
+#[doc(hidden)]
+pub use juniper;
+// used by the generated client code
+#[doc(hidden)]
+pub use serde;
+
+pub use juniper_from_schema_proc_macro::{graphql_schema, graphql_schema_from_file, schema};
+
+/// A type used to parameterize `QueryTrail` to know that `walk` has been called.
+pub struct Walked;
+
+/// A type used to parameterize `QueryTrail` to know that `walk` has *not* been called.
+pub struct NotWalked;
+
+/// A wrapper around a `juniper::LookAheadSelection` with methods for each possible child.
+pub struct QueryTrail<'r, T, K> {
+    // These fields are required by the macros but you shouldn't rely them. They might change
+    // without a major version increase.
+    #[doc(hidden)]
+    pub look_ahead: Option<&'r LookAheadSelection<'r, DefaultScalarValue>>,
+    #[doc(hidden)]
+    pub node_type: PhantomData<T>,
+    #[doc(hidden)]
+    pub walked: K,
+}
+
+impl<'r, T> QueryTrail<'r, T, NotWalked> {
+    /// Check if the trail is present in the query being executed
+    pub fn walk(self) -> Option<QueryTrail<'r, T, Walked>> {
+        match self.look_ahead {
+            Some(inner) => Some(QueryTrail {
+                look_ahead: Some(inner),
+                node_type: self.node_type,
+                walked: Walked,
+            }),
+            None => None,
+        }
+    }
+}
+
+impl<'r, T, K> QueryTrail<'r, T, K> {
+    #[allow(clippy::new_ret_no_self)]
+    #[doc(hidden)]
+    #[allow(missing_docs)]
+    // This method is required by the macros but you shouldn't rely them. They might change
+    // without a major version increase.
+    pub fn new(lh: &'r LookAheadSelection<'r, DefaultScalarValue>) -> QueryTrail<'r, T, Walked> {
+        QueryTrail {
+            look_ahead: Some(lh),
+            node_type: PhantomData,
+            walked: Walked,
+        }
+    }
+}
+
+impl<'r, T> QueryTrail<'r, T, Walked> {
+    /// Take an owned snapshot of the fields selected in the query, together with their
+    /// arguments.
+    ///
+    /// Unlike the `QueryTrail` the snapshot doesn't borrow from the query being executed, so it
+    /// can be kept around or serialized and sent to another service.
+    pub fn to_selection_tree(&self) -> SelectionTree<T> {
+        SelectionTree {
+            fields: selected_fields(self.look_ahead),
+            node_type: PhantomData,
+        }
+    }
+}
+
+fn selected_fields(
+    look_ahead: Option<&LookAheadSelection<'_, DefaultScalarValue>>,
+) -> Vec<SelectedField> {
+    look_ahead_children(look_ahead)
+        .into_iter()
+        .map(|(name, child)| selected_field(name, child))
+        .collect()
+}
+
+fn selected_field(
+    name: String,
+    look_ahead: &LookAheadSelection<'_, DefaultScalarValue>,
+) -> SelectedField {
+    let alias = if look_ahead.field_name() == name {
+        None
+    } else {
+        Some(look_ahead.field_name().to_string())
+    };
+
+    SelectedField {
+        alias,
+        arguments: look_ahead_arguments(look_ahead),
+        fields: selected_fields(Some(look_ahead)),
+        name,
+    }
+}
+
+#[doc(hidden)]
+#[allow(missing_docs)]
+// This function is required by the macros but you shouldn't rely on it. It might change without a
+// major version increase.
+pub fn look_ahead_arguments(
+    look_ahead: &LookAheadSelection<'_, DefaultScalarValue>,
+) -> BTreeMap<String, ArgumentValue> {
+    look_ahead
+        .arguments()
+        .iter()
+        .map(|arg| (arg.name().to_string(), ArgumentValue::from(arg.value())))
+        .collect()
+}
+
+/// The error returned by the `try_*` methods on the arguments structs of `QueryTrail`s.
+///
+/// The methods without `try_` panic with this error instead.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum QueryTrailError {
+    /// The field isn't selected in the query, so it has no arguments.
+    FieldNotSelected(String),
+    /// The argument isn't passed in the query and has no default value.
+    MissingArgument(String),
+    /// The value passed doesn't have the type of the argument, for example `null` for an enum.
+    UnexpectedValue {
+        /// The type the argument has, such as "enum type" or "`i32`".
+        expected: String,
+        /// The kind of value that was passed, such as "null" or "Int".
+        found: String,
+    },
+    /// The value isn't one of the values of the enum.
+    UnknownEnumValue(String),
+    /// The key isn't one of the fields of the input object.
+    UnknownInputObjectKey(String),
+    /// A field of the input object isn't set.
+    MissingInputObjectField(String),
+    /// The value couldn't be parsed, for example a `Url` that isn't a valid URL.
+    InvalidValue(String),
+}
+
+impl QueryTrailError {
+    #[doc(hidden)]
+    #[allow(missing_docs)]
+    // This method is required by the macros but you shouldn't rely on it. It might change without
+    // a major version increase.
+    pub fn unexpected_value(
+        expected: &str,
+        found: &LookAheadValue<'_, DefaultScalarValue>,
+    ) -> Self {
+        let found = match found {
+            LookAheadValue::Null => "null",
+            LookAheadValue::Scalar(_) => "scalar",
+            LookAheadValue::Enum(_) => "enum",
+            LookAheadValue::List(_) => "list",
+            LookAheadValue::Object(_) => "object",
+        };
+
+        QueryTrailError::UnexpectedValue {
+            expected: expected.to_string(),
+            found: found.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for QueryTrailError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QueryTrailError::FieldNotSelected(field) => {
+                write!(f, "The field `{}` isn't selected in the query", field)
+            }
+            QueryTrailError::MissingArgument(name) => write!(f, "No argument with name `{}`", name),
+            QueryTrailError::UnexpectedValue { expected, found } => write!(
+                f,
+                "Failed converting look ahead value. Expected {} got `{}`",
+                expected, found
+            ),
+            QueryTrailError::UnknownEnumValue(name) => write!(f, "Invalid enum name: {}", name),
+            QueryTrailError::UnknownInputObjectKey(key) => {
+                write!(f, "Invalid input object key: {}", key)
+            }
+            QueryTrailError::MissingInputObjectField(field) => {
+                write!(f, "Field `{}` was not set", field)
+            }
+            QueryTrailError::InvalidValue(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for QueryTrailError {}
+
+/// One occurrence of a field in the query being executed.
+///
+/// A field can be selected several times under different aliases, such as
+/// `small: avatar(size: 32) big: avatar(size: 256)`. The `*_selections` methods generated for
+/// `QueryTrail` return a `Selection` for each of them.
+///
+/// `N` is the `QueryTrail` of the field if it returns an object, interface, or union, and `()`
+/// otherwise. `A` is the arguments struct of the field, or `()` if it doesn't take arguments.
+pub struct Selection<'r, N, A> {
+    alias: Option<&'r str>,
+    response_key: &'r str,
+    node: N,
+    args: A,
+}
+
+impl<'r, N, A> Selection<'r, N, A> {
+    #[doc(hidden)]
+    #[allow(missing_docs)]
+    // This method is required by the macros but you shouldn't rely them. They might change
+    // without a major version increase.
+    pub fn new(
+        lh: &'r LookAheadSelection<'r, DefaultScalarValue>,
+        field_name: &str,
+        node: N,
+        args: A,
+    ) -> Self {
+        let response_key = lh.field_name();
+        let alias = if response_key == field_name {
+            None
+        } else {
+            Some(response_key)
+        };
+
+        Selection {
+            alias,
+            response_key,
+            node,
+            args,
+        }
+    }
+
+    /// The alias of the field, if it has one. `small` in `small: avatar(size: 32)`.
+    pub fn alias(&self) -> Option<&'r str> {
+        self.alias
+    }
+
+    /// The key the field will have in the response. That is the alias if there is one and the
+    /// name of the field otherwise.
+    pub fn response_key(&self) -> &'r str {
+        self.response_key
+    }
+
+    /// The arguments passed to this occurrence of the field.
+    pub fn args(&self) -> &A {
+        &self.args
+    }
+}
+
+impl<'r, T, A> Selection<'r, QueryTrail<'r, T, Walked>, A> {
+    /// The `QueryTrail` for the fields selected on this occurrence of the field.
+    pub fn trail(&self) -> &QueryTrail<'r, T, Walked> {
+        &self.node
+    }
+}
+
+/// An iterator over every occurrence of a field in the query being executed.
+///
+/// Returned by the `*_selections` methods generated for `QueryTrail`.
+pub struct Selections<'r, N, A>(std::vec::IntoIter<Selection<'r, N, A>>);
+
+impl<'r, N, A> Selections<'r, N, A> {
+    #[doc(hidden)]
+    #[allow(missing_docs)]
+    // This method is required by the macros but you shouldn't rely them. They might change
+    // without a major version increase.
+    pub fn new(selections: Vec<Selection<'r, N, A>>) -> Self {
+        Selections(selections.into_iter())
+    }
+}
+
+impl<'r, N, A> Iterator for Selections<'r, N, A> {
+    type Item = Selection<'r, N, A>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'r, N, A> DoubleEndedIterator for Selections<'r, N, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+
+impl<'r, N, A> ExactSizeIterator for Selections<'r, N, A> {}
+
+/// The cost of a query, computed from the `@cost` directives in the schema.
+///
+/// Returned by the generated `query_cost` function, `QueryTrailCostExtensions::query_cost`, and
+/// `SchemaCostExtensions::query_cost`.
+///
+/// Summing the costs of sibling selections adds up their costs and keeps the largest depth.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct QueryCost {
+    cost: u64,
+    depth: usize,
+}
+
+impl QueryCost {
+    /// The total cost of the selected fields.
+    pub fn cost(&self) -> u64 {
+        self.cost
+    }
+
+    /// The number of nested fields in the deepest selection.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    #[doc(hidden)]
+    #[allow(missing_docs)]
+    // This method is required by the macros but you shouldn't rely on it. It might change without
+    // a major version increase.
+    //
+    // The cost of a field with the fields selected on it costing `children`. Multipliers that
+    // aren't passed count as 1 and negative ones as 0.
+    pub fn field(complexity: u64, multipliers: &[Option<i32>], children: QueryCost) -> Self {
+        let multiplier = multipliers.iter().flatten().fold(1_u64, |acc, multiplier| {
+            acc.saturating_mul(u64::try_from(*multiplier).unwrap_or(0))
+        });
+
+        QueryCost {
+            cost: complexity
+                .saturating_add(children.cost)
+                .saturating_mul(multiplier),
+            depth: children.depth + 1,
+        }
+    }
+}
+
+impl std::iter::Sum for QueryCost {
+    fn sum<I: Iterator<Item = QueryCost>>(iter: I) -> Self {
+        iter.fold(QueryCost::default(), |acc, cost| QueryCost {
+            cost: acc.cost.saturating_add(cost.cost),
+            depth: acc.depth.max(cost.depth),
+        })
+    }
+}
+
+/// An owned snapshot of the fields selected on a `T` in a query.
+///
+/// Returned by `QueryTrail::to_selection_tree`. It can be serialized with `serde` and sent to
+/// another service, which can inspect it with the generated `SelectionTree*Extensions` traits in
+/// the same way as a `QueryTrail`.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct SelectionTree<T> {
+    fields: Vec<SelectedField>,
+    #[serde(skip)]
+    node_type: PhantomData<fn() -> T>,
+}
+
+impl<T> SelectionTree<T> {
+    #[doc(hidden)]
+    #[allow(missing_docs)]
+    // This method is required by the macros but you shouldn't rely on it. It might change without
+    // a major version increase.
+    pub fn new(fields: Vec<SelectedField>) -> Self {
+        SelectionTree {
+            fields,
+            node_type: PhantomData,
+        }
+    }
+
+    /// The selected fields, in the order they are selected.
+    ///
+    /// A field selected several times under different aliases is included once for each alias.
+    pub fn iter(&self) -> std::slice::Iter<'_, SelectedField> {
+        self.fields.iter()
+    }
+
+    #[doc(hidden)]
+    #[allow(missing_docs)]
+    // This method is required by the macros but you shouldn't rely on it. It might change without
+    // a major version increase.
+    //
+    // The first selection of the field `name`, like `look_ahead_children_named`.
+    pub fn field_named(&self, name: &str) -> Option<&SelectedField> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SelectionTree<T> {
+    type Item = &'a SelectedField;
+    type IntoIter = std::slice::Iter<'a, SelectedField>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// Implemented by hand since deriving would require `T` to implement the traits
+impl<T> fmt::Debug for SelectionTree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SelectionTree")
+            .field("fields", &self.fields)
+            .finish()
+    }
+}
+
+impl<T> Clone for SelectionTree<T> {
+    fn clone(&self) -> Self {
+        SelectionTree::new(self.fields.clone())
+    }
+}
+
+impl<T> PartialEq for SelectionTree<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.fields == other.fields
+    }
+}
+
+/// One occurrence of a field in a `SelectionTree`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelectedField {
+    name: String,
+    alias: Option<String>,
+    arguments: BTreeMap<String, ArgumentValue>,
+    fields: Vec<SelectedField>,
+}
+
+impl SelectedField {
+    /// The name of the field in the schema.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The alias of the field, if it has one.
+    pub fn alias(&self) -> Option<&str> {
+        self.alias.as_deref()
+    }
+
+    /// The key the field will have in the response. That is the alias if there is one and the
+    /// name of the field otherwise.
+    pub fn response_key(&self) -> &str {
+        self.alias().unwrap_or(&self.name)
+    }
+
+    /// The arguments passed to the field, with variables substituted.
+    ///
+    /// Arguments that aren't passed in the query are left out, even if they have default values.
+    /// The generated arguments structs fill those in.
+    pub fn arguments(&self) -> &BTreeMap<String, ArgumentValue> {
+        &self.arguments
+    }
+
+    /// The fields selected on this field. Empty for fields that return scalars or enums.
+    pub fn fields(&self) -> &[SelectedField] {
+        &self.fields
+    }
+
+    #[doc(hidden)]
+    #[allow(missing_docs)]
+    // This method is required by the macros but you shouldn't rely on it. It might change without
+    // a major version increase.
+    pub fn to_selection_tree<T>(&self) -> SelectionTree<T> {
+        SelectionTree::new(self.fields.clone())
+    }
+}
+
+/// The value of an argument in a `SelectionTree`.
+///
+/// It mirrors `juniper::LookAheadValue` but owns its data.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ArgumentValue {
+    /// `null`.
+    Null,
+    /// An `Int`, `Float`, `String`, `Boolean`, or `ID`, or a custom scalar.
+    Scalar(#[serde(with = "DefaultScalarValueDef")] DefaultScalarValue),
+    /// An enum value.
+    Enum(String),
+    /// A list.
+    List(Vec<ArgumentValue>),
+    /// An input object.
+    Object(BTreeMap<String, ArgumentValue>),
+}
+
+impl ArgumentValue {
+    /// Borrow the value as a `juniper::LookAheadValue`.
+    pub fn as_look_ahead_value(&self) -> LookAheadValue<'_, DefaultScalarValue> {
+        match self {
+            ArgumentValue::Null => LookAheadValue::Null,
+            ArgumentValue::Scalar(scalar) => LookAheadValue::Scalar(scalar),
+            ArgumentValue::Enum(value) => LookAheadValue::Enum(value),
+            ArgumentValue::List(values) => LookAheadValue::List(
+                values
+                    .iter()
+                    .map(ArgumentValue::as_look_ahead_value)
+                    .collect(),
+            ),
+            ArgumentValue::Object(fields) => LookAheadValue::Object(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.as_look_ahead_value()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl<'a> From<&LookAheadValue<'a, DefaultScalarValue>> for ArgumentValue {
+    fn from(value: &LookAheadValue<'a, DefaultScalarValue>) -> Self {
+        match value {
+            LookAheadValue::Null => ArgumentValue::Null,
+            LookAheadValue::Scalar(scalar) => ArgumentValue::Scalar((*scalar).clone()),
+            LookAheadValue::Enum(value) => ArgumentValue::Enum(value.to_string()),
+            LookAheadValue::List(values) => {
+                ArgumentValue::List(values.iter().map(ArgumentValue::from).collect())
+            }
+            LookAheadValue::Object(fields) => ArgumentValue::Object(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), ArgumentValue::from(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "DefaultScalarValue")]
+enum DefaultScalarValueDef {
+    Int(i32),
+    Float(f64),
+    String(String),
+    Boolean(bool),
+}
+
+#[doc(hidden)]
+#[allow(missing_docs)]
+// This type is required by the macros but you shouldn't rely on it. It might change without a
+// major version increase.
+//
+// Where the arguments structs of `QueryTrail`s and `SelectionTree`s read the arguments from.
+#[derive(Clone, Copy)]
+pub enum ArgumentSource<'r> {
+    LookAhead(&'r LookAheadSelection<'r, DefaultScalarValue>),
+    SelectionTree(&'r SelectedField),
+}
+
+impl<'r> ArgumentSource<'r> {
+    #[allow(missing_docs)]
+    pub fn get(&self, name: &str) -> Option<LookAheadValue<'r, DefaultScalarValue>> {
+        match self {
+            ArgumentSource::LookAhead(look_ahead) => look_ahead
+                .arguments()
+                .iter()
+                .find(|arg| arg.name() == name)
+                .map(|arg| arg.value().clone()),
+            ArgumentSource::SelectionTree(field) => field
+                .arguments
+                .get(name)
+                .map(ArgumentValue::as_look_ahead_value),
+        }
+    }
+}
+
+/// The batches of the fields marked with `@juniper(batch: true)` that are being loaded.
+///
+/// Batches must not outlive the request they were created for, so create a new `BatchLoaders`
+/// together with the context of each request.
+#[derive(Default)]
+pub struct BatchLoaders {
+    batches: Mutex<HashMap<BatchId, Vec<(BatchArguments, AnyBatch)>>>,
+}
+
+impl fmt::Debug for BatchLoaders {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchLoaders").finish()
+    }
+}
+
+/// Gives the generated resolvers of batched fields access to the `BatchLoaders` of the current
+/// request.
+///
+/// Must be implemented by the context type of schemas that contain fields marked with
+/// `@juniper(batch: true)`.
+pub trait BatchContext {
+    /// The batch loaders of the current request.
+    fn batch_loaders(&self) -> &BatchLoaders;
+}
+
+// The name of a field and the position it's selected at in the query.
+type BatchId = (&'static str, usize);
+
+// The arguments the field is selected with, with variables substituted. Only objects selecting the
+// field with the same arguments are loaded together.
+type BatchArguments = BTreeMap<String, ArgumentValue>;
+
+type SharedBatch<K, V> = Arc<Mutex<Batch<K, V>>>;
+
+// A `SharedBatch` whose key and value types are only known to the resolver of the field.
+type AnyBatch = Box<dyn Any + Send>;
+
+struct Batch<K, V> {
+    keys: Vec<K>,
+    state: BatchState<K, V>,
+}
+
+enum BatchState<K, V> {
+    Open,
+    Loading(Vec<Waker>),
+    Loaded(Result<HashMap<K, V>, FieldError>),
+}
+
+impl<K, V> Batch<K, V> {
+    fn finish(&mut self, result: Result<HashMap<K, V>, FieldError>) {
+        let state = std::mem::replace(&mut self.state, BatchState::Loaded(result));
+        if let BatchState::Loading(wakers) = state {
+            wakers.into_iter().for_each(Waker::wake);
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[doc(hidden)]
+#[allow(missing_docs)]
+// This function is required by the macros but you shouldn't rely on it. It might change without a
+// major version increase.
+//
+// Adds `key` to the open batch of the field `field` selected at `location` in the query with
+// `arguments`. After every sibling has been given the chance to join, the first one to be polled
+// again calls `load_many` with the keys of all of them, and the others wait for its result.
+pub async fn load_batched<K, V, F, Fut>(
+    loaders: &BatchLoaders,
+    field: &'static str,
+    location: usize,
+    arguments: BatchArguments,
+    key: K,
+    load_many: F,
+) -> Result<Option<V>, FieldError>
+where
+    K: Hash + Eq + Clone + Send + 'static,
+    V: Clone + Send + 'static,
+    F: FnOnce(Vec<K>) -> Fut + Send,
+    Fut: Future<Output = Result<HashMap<K, V>, FieldError>> + Send,
+{
+    let batch = join_batch::<K, V>(loaders, (field, location), arguments, key.clone());
+
+    YieldNow(false).await;
+
+    let keys = {
+        let mut batch = lock(&batch);
+        if let BatchState::Open = batch.state {
+            batch.state = BatchState::Loading(Vec::new());
+            Some(std::mem::take(&mut batch.keys))
+        } else {
+            None
+        }
+    };
+
+    if let Some(keys) = keys {
+        let mut seen = HashSet::new();
+        let keys = keys
+            .into_iter()
+            .filter(|key| seen.insert(key.clone()))
+            .collect();
+
+        let guard = CancelOnDrop(Some(&batch));
+        let result = load_many(keys).await;
+        guard.disarm();
+        lock(&batch).finish(result);
+    }
+
+    poll_fn(|cx| match &mut lock(&batch).state {
+        BatchState::Loaded(Ok(values)) => Poll::Ready(Ok(values.get(&key).cloned())),
+        BatchState::Loaded(Err(err)) => Poll::Ready(Err(FieldError::new(
+            err.message(),
+            err.extensions().clone(),
+        ))),
+        BatchState::Loading(wakers) => {
+            wakers.push(cx.waker().clone());
+            Poll::Pending
+        }
+        // The batch is dispatched above if it's still open and batches never reopen, so this
+        // can't happen. It's an error rather than a panic in case that changes.
+        BatchState::Open => Poll::Ready(Err(FieldError::from(
+            "The batch was waited on before being loaded",
+        ))),
+    })
+    .await
+}
+
+fn join_batch<K, V>(
+    loaders: &BatchLoaders,
+    id: BatchId,
+    arguments: BatchArguments,
+    key: K,
+) -> SharedBatch<K, V>
+where
+    K: Send + 'static,
+    V: Send + 'static,
+{
+    let mut batches = lock(&loaders.batches);
+    let batches = batches.entry(id).or_default();
+
+    let open = batches
+        .iter()
+        .filter(|(batch_arguments, _)| *batch_arguments == arguments)
+        .filter_map(|(_, batch)| batch.downcast_ref::<SharedBatch<K, V>>())
+        .find(|batch| matches!(lock(batch).state, BatchState::Open))
+        .cloned();
+
+    let batch = open.unwrap_or_else(|| {
+        let batch = Arc::new(Mutex::new(Batch {
+            keys: Vec::new(),
+            state: BatchState::Open,
+        }));
+        batches.retain(|(batch_arguments, _)| *batch_arguments != arguments);
+        batches.push((arguments, Box::new(Arc::clone(&batch))));
+        batch
+    });
+
+    lock(&batch).keys.push(key);
+    batch
+}
+
+// Makes the current task yield once, so its siblings get polled before it continues.
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+// Fails the batch if the task loading it is dropped, so the tasks waiting on it don't hang.
+struct CancelOnDrop<'a, K, V>(Option<&'a SharedBatch<K, V>>);
+
+impl<'a, K, V> CancelOnDrop<'a, K, V> {
+    fn disarm(mut self) {
+        self.0 = None;
+    }
+}
+
+impl<'a, K, V> Drop for CancelOnDrop<'a, K, V> {
+    fn drop(&mut self) {
+        if let Some(batch) = self.0 {
+            lock(batch).finish(Err(FieldError::from("Loading the batch was cancelled")));
+        }
+    }
+}
+
+/// A page of a connection, returned by the resolvers of fields marked with `@connection`.
+///
+/// It is converted into the generated `{Type}Connection` type. The start and end cursors of the
+/// page are the cursors of the first and last edges.
+///
+/// ```
+/// # use juniper_from_schema::Connection;
+/// let connection = Connection::new()
+///     .edge("cursor-1", "Alice")
+///     .edge("cursor-2", "Bob")
+///     .has_next_page(true);
+///
+/// assert_eq!(Some("cursor-1"), connection.start_cursor());
+/// assert_eq!(Some("cursor-2"), connection.end_cursor());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Connection<T> {
+    edges: Vec<Edge<T>>,
+    has_next_page: bool,
+    has_previous_page: bool,
+}
+
+impl<T> Connection<T> {
+    /// An empty connection without next or previous pages.
+    pub fn new() -> Self {
+        Connection {
+            edges: Vec::new(),
+            has_next_page: false,
+            has_previous_page: false,
+        }
+    }
+
+    /// Add an edge to `node`, identified by `cursor`.
+    pub fn edge<C: Into<String>>(mut self, cursor: C, node: T) -> Self {
+        self.edges.push(Edge {
+            cursor: cursor.into(),
+            node,
+        });
+        self
+    }
+
+    /// Add an edge for each `(cursor, node)` pair.
+    pub fn edges<I, C>(mut self, edges: I) -> Self
+    where
+        I: IntoIterator<Item = (C, T)>,
+        C: Into<String>,
+    {
+        self.edges
+            .extend(edges.into_iter().map(|(cursor, node)| Edge {
+                cursor: cursor.into(),
+                node,
+            }));
+        self
+    }
+
+    /// Set whether there are more edges after this page.
+    pub fn has_next_page(mut self, has_next_page: bool) -> Self {
+        self.has_next_page = has_next_page;
+        self
+    }
+
+    /// Set whether there are more edges before this page.
+    pub fn has_previous_page(mut self, has_previous_page: bool) -> Self {
+        self.has_previous_page = has_previous_page;
+        self
+    }
+
+    /// The cursor of the first edge.
+    pub fn start_cursor(&self) -> Option<&str> {
+        self.edges.first().map(Edge::cursor)
+    }
+
+    /// The cursor of the last edge.
+    pub fn end_cursor(&self) -> Option<&str> {
+        self.edges.last().map(Edge::cursor)
+    }
+
     #[doc(hidden)]
     #[allow(missing_docs)]
-    // This method is required by the macros but you shouldn't rely them. They might change
-    // without a major version increase.
-    pub fn new(lh: &'r LookAheadSelection<'r, DefaultScalarValue>) -> QueryTrail<'r, T, Walked> {
-        QueryTrail {
-            look_ahead: Some(lh),
+    // This method is required by the macros but you shouldn't rely on it. It might change without
+    // a major version increase.
+    //
+    // Builds the generated connection type from the constructors of the generated types.
+    pub fn convert<C, E, P>(
+        self,
+        mut edge: impl FnMut(T, String) -> E,
+        page_info: impl FnOnce(bool, bool, Option<String>, Option<String>) -> P,
+        connection: impl FnOnce(Vec<E>, P) -> C,
+    ) -> C {
+        let start_cursor = self.start_cursor().map(ToString::to_string);
+        let end_cursor = self.end_cursor().map(ToString::to_string);
+        let page_info = page_info(
+            self.has_next_page,
+            self.has_previous_page,
+            start_cursor,
+            end_cursor,
+        );
+
+        let edges = self
+            .edges
+            .into_iter()
+            .map(|Edge { cursor, node }| edge(node, cursor))
+            .collect();
+
+        connection(edges, page_info)
+    }
+}
+
+impl<T> Default for Connection<T> {
+    fn default() -> Self {
+        Connection::new()
+    }
+}
+
+/// An edge of a [`Connection`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edge<T> {
+    cursor: String,
+    node: T,
+}
+
+impl<T> Edge<T> {
+    /// The cursor that identifies the edge.
+    pub fn cursor(&self) -> &str {
+        &self.cursor
+    }
+
+    /// The item at the end of the edge.
+    pub fn node(&self) -> &T {
+        &self.node
+    }
+}
+
+/// An object type that implements the interface marked with `@juniper(node_interface: true)`.
+///
+/// Implemented by the generated code for each implementor of the interface.
+pub trait NodeType {
+    /// The name of the GraphQL type, which is encoded into the global ids of its objects.
+    const TYPE_NAME: &'static str;
+}
+
+/// The global id of an object of type `T`.
+///
+/// Following the Relay convention the id is encoded as the base64 encoding of `{Type}:{id}`, so
+/// it is unique across all the implementors of the node interface.
+///
+/// ```
+/// # use juniper_from_schema::{GlobalId, NodeType};
+/// struct User;
+///
+/// impl NodeType for User {
+///     const TYPE_NAME: &'static str = "User";
+/// }
+///
+/// let id = GlobalId::<User>::new("1").encode();
+/// assert_eq!("VXNlcjox", id.to_string());
+/// assert_eq!("1", GlobalId::<User>::decode(&id).unwrap().id());
+/// ```
+pub struct GlobalId<T> {
+    id: String,
+    node_type: PhantomData<fn() -> T>,
+}
+
+impl<T> GlobalId<T> {
+    /// Create the global id of the object of type `T` with the given id.
+    pub fn new<I: Into<String>>(id: I) -> Self {
+        Self {
+            id: id.into(),
             node_type: PhantomData,
-            walked: Walked,
         }
     }
+
+    /// The id of the object, without the type.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Convert into the id of the object, without the type.
+    pub fn into_id(self) -> String {
+        self.id
+    }
+}
+
+impl<T: NodeType> GlobalId<T> {
+    /// Encode the id into an opaque `ID`.
+    pub fn encode(&self) -> juniper::ID {
+        juniper::ID::new(base64::encode(format!("{}:{}", T::TYPE_NAME, self.id)))
+    }
+
+    /// Decode an `ID` created with [`GlobalId::encode`].
+    ///
+    /// Fails if the id is malformed or belongs to another type.
+    pub fn decode(id: &juniper::ID) -> Result<Self, GlobalIdError> {
+        let (type_name, id) = decode_global_id(id)?;
+
+        if type_name == T::TYPE_NAME {
+            Ok(Self::new(id))
+        } else {
+            Err(GlobalIdError::WrongType {
+                expected: T::TYPE_NAME,
+                found: type_name,
+            })
+        }
+    }
+}
+
+impl<T: NodeType> From<GlobalId<T>> for juniper::ID {
+    fn from(id: GlobalId<T>) -> Self {
+        id.encode()
+    }
+}
+
+impl<T> fmt::Debug for GlobalId<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("GlobalId").field(&self.id).finish()
+    }
+}
+
+impl<T> Clone for GlobalId<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.id.clone())
+    }
+}
+
+impl<T> PartialEq for GlobalId<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for GlobalId<T> {}
+
+impl<T> Hash for GlobalId<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
+/// The error returned when decoding a [`GlobalId`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GlobalIdError {
+    /// The id isn't the base64 encoding of `{Type}:{id}`.
+    Malformed(String),
+
+    /// The id belongs to another type.
+    WrongType {
+        /// The type the id was decoded as.
+        expected: &'static str,
+        /// The type encoded in the id.
+        found: String,
+    },
+}
+
+impl fmt::Display for GlobalIdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GlobalIdError::Malformed(id) => write!(f, "Invalid global id `{}`", id),
+            GlobalIdError::WrongType { expected, found } => write!(
+                f,
+                "Expected the global id of a `{}`, got the id of a `{}`",
+                expected, found
+            ),
+        }
+    }
+}
+
+impl Error for GlobalIdError {}
+
+#[doc(hidden)]
+#[allow(missing_docs)]
+// This function is required by the macros but you shouldn't rely on it. It might change without a
+// major version increase.
+//
+// Splits a global id into the type name and the id of the object.
+pub fn decode_global_id(id: &juniper::ID) -> Result<(String, String), GlobalIdError> {
+    let malformed = || GlobalIdError::Malformed(id.to_string());
+
+    let decoded = base64::decode(id.as_bytes()).map_err(|_| malformed())?;
+    let decoded = String::from_utf8(decoded).map_err(|_| malformed())?;
+    let separator = decoded.find(':').ok_or_else(malformed)?;
+
+    Ok((
+        decoded[..separator].to_string(),
+        decoded[separator + 1..].to_string(),
+    ))
+}
+
+/// The `_Service` type of Apollo Federation, returned by `Query._service`.
+///
+/// The generated code returns it with the SDL of the schema, so you don't need to construct it
+/// yourself.
+#[derive(Debug, Clone, PartialEq, Eq, juniper::GraphQLObject)]
+#[graphql(name = "_Service", scalar = DefaultScalarValue)]
+pub struct Service {
+    sdl: Option<String>,
+}
+
+impl Service {
+    /// Create a `Service` with the SDL of the subgraph.
+    pub fn new(sdl: &str) -> Self {
+        Self {
+            sdl: Some(sdl.to_string()),
+        }
+    }
+
+    /// The SDL the gateway composes the supergraph from.
+    pub fn sdl(&self) -> Option<&str> {
+        self.sdl.as_deref()
+    }
+}
+
+/// An entity representation, the `_Any` scalar of Apollo Federation.
+///
+/// The gateway sends a representation to `Query._entities` for each entity it needs from this
+/// service. It has the `__typename` of the entity, the fields of one of its `@key`s and the fields
+/// required by `@requires`.
+///
+/// ```
+/// # use juniper_from_schema::Representation;
+/// use juniper::{FromInputValue, InputValue, ID};
+///
+/// let representation = Representation::from_input_value(&InputValue::object(
+///     vec![
+///         ("__typename", InputValue::scalar("User")),
+///         ("id", InputValue::scalar("1")),
+///     ]
+///     .into_iter()
+///     .collect(),
+/// ))
+/// .unwrap();
+///
+/// assert_eq!("User", representation.typename());
+/// assert_eq!(Some(ID::new("1")), representation.get("id"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Representation {
+    typename: String,
+    value: juniper::InputValue,
+}
+
+impl Representation {
+    /// The GraphQL type of the entity.
+    pub fn typename(&self) -> &str {
+        &self.typename
+    }
+
+    /// The value of a field of the representation, if it is there and has the right type.
+    pub fn get<T: juniper::FromInputValue>(&self, field: &str) -> Option<T> {
+        self.field(field).and_then(T::from_input_value)
+    }
+
+    /// The raw value of a field of the representation.
+    pub fn field(&self, field: &str) -> Option<&juniper::InputValue> {
+        self.value.to_object_value()?.get(field).copied()
+    }
+}
+
+#[juniper::graphql_scalar(name = "_Any")]
+impl GraphQLScalar for Representation {
+    fn resolve(&self) -> juniper::Value {
+        input_value_to_value(&self.value)
+    }
+
+    fn from_input_value(value: &juniper::InputValue) -> Option<Representation> {
+        // Representations encoded by `prepare_federation_request`
+        if let Some(json) = value.as_string_value() {
+            let value = serde_json::from_str::<juniper::InputValue>(json).ok()?;
+            return Representation::from_input_value(&value);
+        }
+
+        let typename = value
+            .to_object_value()?
+            .get("__typename")?
+            .as_string_value()?
+            .to_string();
+
+        Some(Representation {
+            typename,
+            value: value.clone(),
+        })
+    }
+
+    // Representations are objects, which are never scalar tokens
+    fn from_str<'a>(
+        value: juniper::parser::ScalarToken<'a>,
+    ) -> juniper::ParseScalarResult<'a, DefaultScalarValue> {
+        Err(juniper::parser::ParseError::UnexpectedToken(
+            juniper::parser::Token::Scalar(value),
+        ))
+    }
+}
+
+/// Prepare a request to a federation subgraph for execution.
+///
+/// Apollo gateways send the representations of `Query._entities` as objects, but juniper only
+/// accepts scalar values for scalar types such as `_Any`. This encodes the representations in the
+/// variables of the request into strings, which [`Representation`] decodes again. Requests
+/// without representations are left as they are.
+///
+/// Fails if the request can't be converted to and from JSON.
+///
+/// ```
+/// # use juniper_from_schema::prepare_federation_request;
+/// # use juniper::http::GraphQLRequest;
+/// let request: GraphQLRequest = serde_json::from_str(r#"{
+///     "query": "query($representations: [_Any!]!) { _entities(representations: $representations) { __typename } }",
+///     "variables": { "representations": [{ "__typename": "User", "id": "1" }] }
+/// }"#).unwrap();
+///
+/// let request = prepare_federation_request(request).unwrap();
+/// ```
+pub fn prepare_federation_request(
+    request: GraphQLRequest,
+) -> Result<GraphQLRequest, serde_json::Error> {
+    let mut json = serde_json::to_value(&request)?;
+
+    match json.get_mut("variables") {
+        Some(variables) => encode_representations(variables),
+        None => return Ok(request),
+    }
+
+    serde_json::from_value(json)
+}
+
+// Input objects can't have fields starting with `__`, so objects with a `__typename` are
+// representations
+fn encode_representations(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(fields) if fields.contains_key("__typename") => {
+            *value = serde_json::Value::String(value.to_string());
+        }
+        serde_json::Value::Object(fields) => fields.values_mut().for_each(encode_representations),
+        serde_json::Value::Array(items) => items.iter_mut().for_each(encode_representations),
+        _ => {}
+    }
+}
+
+fn input_value_to_value(value: &juniper::InputValue) -> juniper::Value {
+    match value {
+        juniper::InputValue::Null | juniper::InputValue::Variable(_) => juniper::Value::null(),
+        juniper::InputValue::Scalar(scalar) => juniper::Value::scalar(scalar.clone()),
+        juniper::InputValue::Enum(name) => juniper::Value::scalar(name.clone()),
+        juniper::InputValue::List(items) => juniper::Value::list(
+            items
+                .iter()
+                .map(|item| input_value_to_value(&item.item))
+                .collect(),
+        ),
+        juniper::InputValue::Object(fields) => juniper::Value::object(
+            fields
+                .iter()
+                .map(|(key, value)| (key.item.as_str(), input_value_to_value(&value.item)))
+                .collect(),
+        ),
+    }
+}
+
+#[doc(hidden)]
+#[allow(missing_docs)]
+// This function is required by the macros but you shouldn't rely on it. It might change without a
+// major version increase.
+//
+// The name of the field `look_ahead` selects, ignoring its alias.
+pub fn look_ahead_name(look_ahead: &LookAheadSelection<'_, DefaultScalarValue>) -> String {
+    unaliased_name(look_ahead)
+}
+
+#[doc(hidden)]
+#[allow(missing_docs)]
+// This function is required by the macros but you shouldn't rely on it. It might change without a
+// major version increase.
+//
+// The children of `look_ahead` together with the names of the fields they select, in the order
+// they appear in the query.
+pub fn look_ahead_children<'r>(
+    look_ahead: Option<&'r LookAheadSelection<'r, DefaultScalarValue>>,
+) -> Vec<(String, &'r LookAheadSelection<'r, DefaultScalarValue>)> {
+    look_ahead
+        .map(|la| {
+            la.children()
+                .into_iter()
+                .map(|child| (unaliased_name(child), child))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[doc(hidden)]
+#[allow(missing_docs)]
+// This function is required by the macros but you shouldn't rely on it. It might change without a
+// major version increase.
+//
+// The children of `look_ahead` that select the field `name`, with or without an alias, in the
+// order they appear in the query.
+pub fn look_ahead_children_named<'r>(
+    look_ahead: Option<&'r LookAheadSelection<'r, DefaultScalarValue>>,
+    name: &str,
+) -> Vec<&'r LookAheadSelection<'r, DefaultScalarValue>> {
+    look_ahead_children(look_ahead)
+        .into_iter()
+        .filter(|(child_name, _)| child_name == name)
+        .map(|(_, child)| child)
+        .collect()
+}
+
+#[doc(hidden)]
+#[allow(missing_docs)]
+// This function is required by the macros but you shouldn't rely on it. It might change without a
+// major version increase.
+//
+// The field selected by `look_ahead`, together with the fields selected on it.
+pub fn look_ahead_selected_field(
+    look_ahead: &LookAheadSelection<'_, DefaultScalarValue>,
+) -> SelectedField {
+    selected_field(unaliased_name(look_ahead), look_ahead)
+}
+
+#[doc(hidden)]
+#[allow(missing_docs)]
+// This function is required by the macros but you shouldn't rely on it. It might change without a
+// major version increase.
+//
+// The fields selected on `look_ahead`.
+pub fn look_ahead_selected_fields(
+    look_ahead: Option<&LookAheadSelection<'_, DefaultScalarValue>>,
+) -> Vec<SelectedField> {
+    selected_fields(look_ahead)
+}
+
+#[doc(hidden)]
+#[allow(missing_docs)]
+// This function is required by the macros but you shouldn't rely on it. It might change without a
+// major version increase.
+//
+// The root fields selected by an operation of a parsed query document, read the same way juniper
+// builds look aheads: fragments are inlined, `@skip` and `@include` are applied, and variables are
+// substituted, falling back to the defaults declared by the operation.
+pub fn operation_selected_fields(
+    document: &Document<'_, DefaultScalarValue>,
+    operation_name: Option<&str>,
+    variables: &Variables,
+) -> Result<(OperationType, Vec<SelectedField>), GraphQLError<'static>> {
+    let operation = &get_operation(document, operation_name)?.item;
+
+    let mut variables = variables.clone();
+    for (name, definition) in operation
+        .variable_definitions
+        .iter()
+        .flat_map(|definitions| &definitions.item.items)
+    {
+        if let Some(default_value) = &definition.default_value {
+            variables
+                .entry(name.item.to_string())
+                .or_insert_with(|| default_value.item.clone());
+        }
+    }
+
+    let fragments = document
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Fragment(fragment) => Some((
+                fragment.item.name.item,
+                fragment.item.selection_set.as_slice(),
+            )),
+            Definition::Operation(_) => None,
+        })
+        .collect();
+
+    let mut selection_set = DocumentSelectionSet {
+        fragments: &fragments,
+        variables: &variables,
+        fragment_stack: Vec::new(),
+        fields: Vec::new(),
+    };
+    selection_set.collect(&operation.selection_set);
+
+    Ok((operation.operation_type.clone(), selection_set.fields))
+}
+
+// The fields selected in a selection set of a query document.
+struct DocumentSelectionSet<'a, 'doc> {
+    fragments: &'a HashMap<&'doc str, &'a [juniper::Selection<'doc, DefaultScalarValue>]>,
+    variables: &'a Variables,
+    // The fragments being spread, so fragments that spread themselves, which validation would
+    // reject, don't recurse forever
+    fragment_stack: Vec<&'doc str>,
+    fields: Vec<SelectedField>,
+}
+
+// `@skip` and `@include` apply to fields, fragment spreads, and inline fragments alike. Their
+// directives can't be named outside of juniper, so this is a macro rather than a function.
+macro_rules! is_included {
+    ($directives:expr, $variables:expr) => {
+        $directives.iter().flatten().all(|directive| {
+            let condition = directive
+                .item
+                .arguments
+                .iter()
+                .flat_map(|arguments| &arguments.item.items)
+                .find(|(name, _)| name.item == "if")
+                .and_then(|(_, value)| match &value.item {
+                    juniper::InputValue::Variable(name) => $variables.get(name),
+                    value => Some(value),
+                })
+                .and_then(|value| value.as_scalar_value::<bool>().copied());
+
+            match (directive.item.name.item, condition) {
+                ("skip", Some(skip)) => !skip,
+                ("include", Some(include)) => include,
+                _ => true,
+            }
+        })
+    };
+}
+
+impl<'a, 'doc> DocumentSelectionSet<'a, 'doc> {
+    fn collect(&mut self, selection_set: &'a [juniper::Selection<'doc, DefaultScalarValue>]) {
+        for selection in selection_set {
+            match selection {
+                juniper::Selection::Field(field) => {
+                    let field = &field.item;
+                    if !is_included!(field.directives, self.variables) {
+                        continue;
+                    }
+
+                    let mut children = DocumentSelectionSet {
+                        fragments: self.fragments,
+                        variables: self.variables,
+                        fragment_stack: self.fragment_stack.clone(),
+                        fields: Vec::new(),
+                    };
+                    children.collect(field.selection_set.as_deref().unwrap_or_default());
+
+                    self.fields.push(SelectedField {
+                        name: field.name.item.to_string(),
+                        alias: field.alias.as_ref().map(|alias| alias.item.to_string()),
+                        arguments: field
+                            .arguments
+                            .iter()
+                            .flat_map(|arguments| &arguments.item.items)
+                            .map(|(name, value)| {
+                                (
+                                    name.item.to_string(),
+                                    argument_value(&value.item, self.variables),
+                                )
+                            })
+                            .collect(),
+                        fields: children.fields,
+                    });
+                }
+                juniper::Selection::FragmentSpread(spread) => {
+                    let name = spread.item.name.item;
+                    if !is_included!(spread.item.directives, self.variables)
+                        || self.fragment_stack.contains(&name)
+                    {
+                        continue;
+                    }
+
+                    if let Some(fragment) = self.fragments.get(name) {
+                        self.fragment_stack.push(name);
+                        self.collect(fragment);
+                        self.fragment_stack.pop();
+                    }
+                }
+                juniper::Selection::InlineFragment(fragment) => {
+                    if is_included!(fragment.item.directives, self.variables) {
+                        self.collect(&fragment.item.selection_set);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Variables that aren't passed are `null`, like in juniper's look aheads.
+fn argument_value(value: &InputValue, variables: &Variables) -> ArgumentValue {
+    match value {
+        InputValue::Null => ArgumentValue::Null,
+        InputValue::Scalar(scalar) => ArgumentValue::Scalar(scalar.clone()),
+        InputValue::Enum(value) => ArgumentValue::Enum(value.clone()),
+        InputValue::Variable(name) => variables
+            .get(name)
+            .map(|value| argument_value(value, variables))
+            .unwrap_or(ArgumentValue::Null),
+        InputValue::List(values) => ArgumentValue::List(
+            values
+                .iter()
+                .map(|value| argument_value(&value.item, variables))
+                .collect(),
+        ),
+        InputValue::Object(fields) => ArgumentValue::Object(
+            fields
+                .iter()
+                .map(|(name, value)| (name.item.clone(), argument_value(&value.item, variables)))
+                .collect(),
+        ),
+    }
+}
+
+// juniper 0.15 only exposes the alias of a selection, through `field_name`, so the name of the
+// field itself is read from the `Debug` output, which starts with
+// `LookAheadSelection { name: "..."`. Formatting is stopped as soon as the name has been read so
+// the children aren't formatted.
+//
+// This is the only place that depends on the `Debug` layout, which is why juniper is pinned to
+// exactly `=0.15.12` and `test_unaliased_name` checks it. If the layout changes anyway this panics
+// rather than treating every field as unselected. Replace it with an accessor for the name once
+// juniper has one.
+fn unaliased_name(selection: &LookAheadSelection<'_, DefaultScalarValue>) -> String {
+    struct NameWriter(String);
+
+    impl NameWriter {
+        fn name(&self) -> Option<&str> {
+            let start = self.0.find("name: \"")? + "name: \"".len();
+            let len = self.0[start..].find('"')?;
+            Some(&self.0[start..start + len])
+        }
+    }
+
+    impl fmt::Write for NameWriter {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.0.push_str(s);
+            if self.name().is_some() {
+                Err(fmt::Error)
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    let mut writer = NameWriter(String::new());
+    let _ = fmt::write(&mut writer, format_args!("{:?}", selection));
+    match writer.name() {
+        Some(name) => name.to_string(),
+        None => panic!(
+            "failed to read the field name from the `Debug` output of a juniper `LookAheadSelection`: {:?}",
+            writer.0
+        ),
+    }
 }
 
 /// Include the code generated by "juniper-from-schema-build" in a "build.rs" file.
@@ -1811,6 +4312,12 @@ impl<'r, T, K> QueryTrail<'r, T, K> {
 /// ```rust,ignore
 /// juniper_from_schema::include_schema!();
 /// ```
+///
+/// If the schema was compiled with a custom output name, pass the same name:
+///
+/// ```rust,ignore
+/// juniper_from_schema::include_schema!("admin");
+/// ```
 #[macro_export]
 macro_rules! include_schema {
     () => {
@@ -1819,6 +4326,14 @@ macro_rules! include_schema {
             "/juniper_from_schema_graphql_schema.rs"
         ));
     };
+    ($name:literal) => {
+        std::include!(std::concat!(
+            std::env!("OUT_DIR"),
+            "/juniper_from_schema_",
+            $name,
+            ".rs"
+        ));
+    };
 }
 
 #[cfg(test)]
@@ -1838,6 +4353,86 @@ mod test {
         t.compile_fail("tests/compile_fail/*.rs");
     }
 
+    #[test]
+    fn test_unaliased_name() {
+        struct Query;
+
+        #[juniper::graphql_object(scalar = DefaultScalarValue)]
+        impl Query {
+            fn user(&self, executor: &Executor) -> Vec<String> {
+                let look_ahead = executor.look_ahead();
+                vec![
+                    look_ahead.field_name().to_string(),
+                    unaliased_name(&look_ahead),
+                ]
+            }
+        }
+
+        let (value, errors) = juniper::execute_sync(
+            "{ user other: user }",
+            None,
+            &juniper::RootNode::new(
+                Query,
+                juniper::EmptyMutation::<()>::new(),
+                juniper::EmptySubscription::<()>::new(),
+            ),
+            &juniper::Variables::new(),
+            &(),
+        )
+        .unwrap();
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        assert_eq!(
+            serde_json::json!({
+                "user": ["user", "user"],
+                "other": ["other", "user"],
+            }),
+            serde_json::to_value(&value).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_batches_are_split_by_arguments() {
+        let loaders = BatchLoaders::default();
+        let calls = Mutex::new(Vec::new());
+
+        let load = |first: i32, key: i32| {
+            let mut arguments = BTreeMap::new();
+            arguments.insert(
+                "first".to_string(),
+                ArgumentValue::Scalar(DefaultScalarValue::Int(first)),
+            );
+            let calls = &calls;
+
+            load_batched(
+                &loaders,
+                "User.posts",
+                0,
+                arguments,
+                key,
+                move |keys| async move {
+                    lock(calls).push((first, keys.clone()));
+                    Ok(keys.into_iter().map(|key| (key, key * first)).collect())
+                },
+            )
+        };
+
+        let values = futures::executor::block_on(futures::future::join_all(vec![
+            load(1, 1),
+            load(2, 1),
+            load(1, 2),
+        ]));
+        let values = values
+            .into_iter()
+            .map(|value| value.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![Some(1), Some(2), Some(2)], values);
+
+        let mut calls = calls.into_inner().unwrap();
+        calls.sort();
+        assert_eq!(vec![(1, vec![1, 2]), (2, vec![1])], calls);
+    }
+
     #[allow(dead_code)]
     fn setup_subscription_tests(outcome: &str, t: &TestCases) {
         for entry in std::fs::read_dir(format!("tests/subscriptions/{}", outcome)).unwrap() {
@@ -0,0 +1,162 @@
+#![allow(dead_code, unused_variables, unused_imports)]
+
+#[macro_use]
+extern crate juniper;
+
+use juniper::{EmptyMutation, EmptySubscription, Executor, FieldResult, Variables};
+use juniper_from_schema::graphql_schema;
+use serde_json::{json, Value};
+
+graphql_schema! {
+    schema {
+        query: Query
+    }
+
+    type Query {
+        user: User! @juniper(ownership: "owned")
+    }
+
+    type User {
+        summary: String! @juniper(ownership: "owned")
+        avatar(size: Int!): Image! @juniper(ownership: "owned")
+        avatarUrl(size: Int = 64): String! @juniper(ownership: "owned")
+    }
+
+    type Image {
+        url: String! @juniper(ownership: "owned")
+        width: Int! @juniper(ownership: "owned")
+    }
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_user(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<User, Walked>,
+    ) -> FieldResult<User> {
+        let mut summary = vec![];
+
+        for selection in trail.avatar_selections() {
+            summary.push(format!(
+                "{}/{:?}/{}/width={}",
+                selection.response_key(),
+                selection.alias(),
+                selection.args().size(),
+                selection.trail().width(),
+            ));
+        }
+
+        for selection in trail.avatar_url_selections() {
+            summary.push(format!(
+                "{}/{:?}/{}",
+                selection.response_key(),
+                selection.alias(),
+                selection.args().size(),
+            ));
+        }
+
+        let summaries = trail.summary_selections().collect::<Vec<_>>();
+        summary.push(format!("summary={}", summaries.len()));
+
+        Ok(User(summary.join(" ")))
+    }
+}
+
+pub struct User(String);
+
+impl UserFields for User {
+    fn field_summary(&self, executor: &Executor<Context>) -> FieldResult<String> {
+        Ok(self.0.clone())
+    }
+
+    fn field_avatar(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<Image, Walked>,
+        size: i32,
+    ) -> FieldResult<Image> {
+        Ok(Image(size))
+    }
+
+    fn field_avatar_url(&self, executor: &Executor<Context>, size: i32) -> FieldResult<String> {
+        Ok(format!("{}.png", size))
+    }
+}
+
+pub struct Image(i32);
+
+impl ImageFields for Image {
+    fn field_url(&self, executor: &Executor<Context>) -> FieldResult<String> {
+        Ok(format!("{}.png", self.0))
+    }
+
+    fn field_width(&self, executor: &Executor<Context>) -> FieldResult<i32> {
+        Ok(self.0)
+    }
+}
+
+#[test]
+fn test_selections_include_every_alias() {
+    let value = run_query(
+        r#"
+        query {
+            user {
+                summary
+                small: avatar(size: 32) { url }
+                big: avatar(size: 256) { url width }
+                avatarUrl
+                huge: avatarUrl(size: 1024)
+            }
+        }
+        "#,
+    );
+
+    assert_eq!(
+        json!(
+            "small/Some(\"small\")/32/width=false \
+             big/Some(\"big\")/256/width=true \
+             avatarUrl/None/64 \
+             huge/Some(\"huge\")/1024 \
+             summary=1"
+        ),
+        value["user"]["summary"]
+    );
+}
+
+#[test]
+fn test_walking_aliased_fields() {
+    let value = run_query(
+        r#"
+        query {
+            user {
+                info: summary
+                avatar: avatarUrl(size: 16)
+            }
+        }
+        "#,
+    );
+
+    // `avatar` is an alias for `avatarUrl`, not a selection of the `avatar` field
+    assert_eq!(
+        json!("avatar/Some(\"avatar\")/16 summary=1"),
+        value["user"]["info"]
+    );
+}
+
+type Context = ();
+
+fn run_query(query: &str) -> Value {
+    let (res, errors) = juniper::execute_sync(
+        query,
+        None,
+        &Schema::new(Query, EmptyMutation::new(), EmptySubscription::new()),
+        &Variables::new(),
+        &(),
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    serde_json::from_str(&serde_json::to_string(&res).unwrap()).unwrap()
+}
@@ -0,0 +1,277 @@
+#![allow(dead_code, unused_variables, unused_imports)]
+
+#[macro_use]
+extern crate juniper;
+
+use async_trait::async_trait;
+use futures::executor::block_on;
+use juniper::{EmptyMutation, EmptySubscription, Executor, FieldResult, Variables};
+use juniper_from_schema::{graphql_schema, BatchContext, BatchLoaders};
+use serde_json::{json, Value};
+use std::{collections::HashMap, sync::Mutex};
+
+graphql_schema! {
+    schema {
+        query: Query
+    }
+
+    type Query {
+        users: [User!]! @juniper(ownership: "owned")
+    }
+
+    type User {
+        id: Int! @juniper(ownership: "owned")
+        posts(first: Int = 10): [Post!]! @juniper(ownership: "owned", async: true, batch: true)
+        avatar: String @juniper(ownership: "owned", async: true, batch: true)
+        company: Company! @juniper(ownership: "owned", async: true, batch: true)
+    }
+
+    type Post {
+        title: String! @juniper(ownership: "owned")
+    }
+
+    type Company {
+        name: String! @juniper(ownership: "owned")
+    }
+}
+
+pub struct Context {
+    batch_loaders: BatchLoaders,
+    calls: Mutex<Vec<String>>,
+}
+
+impl juniper::Context for Context {}
+
+impl BatchContext for Context {
+    fn batch_loaders(&self) -> &BatchLoaders {
+        &self.batch_loaders
+    }
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<User, Walked>,
+    ) -> FieldResult<Vec<User>> {
+        Ok(vec![
+            User { id: 1 },
+            User { id: 2 },
+            User { id: 1 },
+            User { id: 3 },
+        ])
+    }
+}
+
+pub struct User {
+    id: i32,
+}
+
+impl UserFields for User {
+    fn field_id(&self, executor: &Executor<Context>) -> FieldResult<i32> {
+        Ok(self.id)
+    }
+}
+
+#[async_trait]
+impl UserPostsBatch for User {
+    type Key = i32;
+
+    fn batch_key(&self) -> i32 {
+        self.id
+    }
+
+    async fn load_many<'r, 'a>(
+        executor: &Executor<'r, 'a, Context>,
+        trail: &QueryTrail<'r, Post, Walked>,
+        keys: &[i32],
+        first: i32,
+    ) -> FieldResult<HashMap<i32, Vec<Post>>> {
+        log_call(executor, format!("posts({}) {:?}", first, keys));
+
+        Ok(keys
+            .iter()
+            .map(|id| {
+                let posts = (0..first)
+                    .map(|n| Post {
+                        title: format!("{}-{}", id, n),
+                    })
+                    .collect();
+                (*id, posts)
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl UserAvatarBatch for User {
+    type Key = i32;
+
+    fn batch_key(&self) -> i32 {
+        self.id
+    }
+
+    async fn load_many<'r, 'a>(
+        executor: &Executor<'r, 'a, Context>,
+        keys: &[i32],
+    ) -> FieldResult<HashMap<i32, String>> {
+        log_call(executor, format!("avatar {:?}", keys));
+
+        // Users without an avatar are left out
+        Ok(keys
+            .iter()
+            .filter(|id| **id != 2)
+            .map(|id| (*id, format!("{}.png", id)))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl UserCompanyBatch for User {
+    type Key = i32;
+
+    fn batch_key(&self) -> i32 {
+        self.id
+    }
+
+    async fn load_many<'r, 'a>(
+        executor: &Executor<'r, 'a, Context>,
+        trail: &QueryTrail<'r, Company, Walked>,
+        keys: &[i32],
+    ) -> FieldResult<HashMap<i32, Company>> {
+        log_call(executor, format!("company {:?}", keys));
+
+        Ok(keys
+            .iter()
+            .filter(|id| **id != 3)
+            .map(|id| {
+                let company = Company {
+                    name: format!("company {}", id),
+                };
+                (*id, company)
+            })
+            .collect())
+    }
+}
+
+fn log_call(executor: &Executor<Context>, call: String) {
+    executor.context().calls.lock().unwrap().push(call);
+}
+
+#[derive(Clone)]
+pub struct Post {
+    title: String,
+}
+
+impl PostFields for Post {
+    fn field_title(&self, executor: &Executor<Context>) -> FieldResult<String> {
+        Ok(self.title.clone())
+    }
+}
+
+#[derive(Clone)]
+pub struct Company {
+    name: String,
+}
+
+impl CompanyFields for Company {
+    fn field_name(&self, executor: &Executor<Context>) -> FieldResult<String> {
+        Ok(self.name.clone())
+    }
+}
+
+#[test]
+fn test_siblings_are_loaded_with_one_call() {
+    let (value, calls) = run_query(
+        r#"
+        query {
+            users {
+                id
+                posts(first: 1) { title }
+                avatar
+            }
+        }
+        "#,
+    );
+
+    assert_eq!(
+        json!([
+            { "id": 1, "posts": [{ "title": "1-0" }], "avatar": "1.png" },
+            { "id": 2, "posts": [{ "title": "2-0" }], "avatar": null },
+            { "id": 1, "posts": [{ "title": "1-0" }], "avatar": "1.png" },
+            { "id": 3, "posts": [{ "title": "3-0" }], "avatar": "3.png" },
+        ]),
+        value["users"]
+    );
+
+    assert_eq!(vec!["avatar [1, 2, 3]", "posts(1) [1, 2, 3]"], calls);
+}
+
+#[test]
+fn test_aliases_are_loaded_in_separate_batches() {
+    let (value, calls) = run_query(
+        r#"
+        query {
+            users {
+                latest: posts(first: 1) { title }
+                posts { title }
+            }
+        }
+        "#,
+    );
+
+    assert_eq!(json!([{ "title": "2-0" }]), value["users"][1]["latest"]);
+    assert_eq!(10, value["users"][1]["posts"].as_array().unwrap().len());
+    assert_eq!(vec!["posts(1) [1, 2, 3]", "posts(10) [1, 2, 3]"], calls);
+}
+
+#[test]
+fn test_missing_values_of_non_null_fields_are_errors() {
+    let context = context();
+    let (res, errors) = block_on(juniper::execute(
+        "query { users { company { name } } }",
+        None,
+        &Schema::new(Query, EmptyMutation::new(), EmptySubscription::new()),
+        &Variables::new(),
+        &context,
+    ))
+    .unwrap();
+
+    assert_eq!(1, errors.len());
+    assert_eq!(
+        "No value was loaded for `User.company`",
+        errors[0].error().message()
+    );
+    assert_eq!(vec!["company [1, 2, 3]"], sorted_calls(context));
+}
+
+fn context() -> Context {
+    Context {
+        batch_loaders: BatchLoaders::default(),
+        calls: Mutex::new(Vec::new()),
+    }
+}
+
+fn sorted_calls(context: Context) -> Vec<String> {
+    let mut calls = context.calls.into_inner().unwrap();
+    calls.sort();
+    calls
+}
+
+fn run_query(query: &str) -> (Value, Vec<String>) {
+    let context = context();
+    let (res, errors) = block_on(juniper::execute(
+        query,
+        None,
+        &Schema::new(Query, EmptyMutation::new(), EmptySubscription::new()),
+        &Variables::new(),
+        &context,
+    ))
+    .unwrap();
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    let value = serde_json::from_str(&serde_json::to_string(&res).unwrap()).unwrap();
+    (value, sorted_calls(context))
+}
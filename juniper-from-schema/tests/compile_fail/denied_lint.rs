@@ -0,0 +1,18 @@
+#![allow(dead_code, unused_variables, unused_must_use, unused_imports)]
+include!("../compile_pass/setup.rs");
+
+juniper_from_schema::graphql_schema! {
+    deny: [enum_value_case],
+
+    schema {
+        query: Query
+    }
+
+    type Query {
+        status: Status! @juniper(ownership: "owned")
+    }
+
+    enum Status {
+        Active
+    }
+}
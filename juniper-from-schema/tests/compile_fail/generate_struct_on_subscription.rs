@@ -0,0 +1,29 @@
+#![allow(dead_code, unused_variables, unused_must_use, unused_imports)]
+include!("../compile_pass/setup.rs");
+
+juniper_from_schema::graphql_schema! {
+    type Query {
+      ping: Boolean!
+    }
+
+    type Subscription @juniper(generate_struct: true) {
+      users: User! @juniper(infallible: true, ownership: "owned")
+    }
+
+    type User {
+      id: ID!
+    }
+
+    schema {
+      query: Query
+      subscription: Subscription
+    }
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_ping(&self, _: &Executor<Context>) -> FieldResult<&bool> {
+        todo!()
+    }
+}
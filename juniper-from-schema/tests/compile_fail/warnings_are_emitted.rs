@@ -0,0 +1,34 @@
+#![deny(deprecated)]
+#![allow(dead_code, unused_variables, unused_must_use, unused_imports)]
+
+include!("../compile_pass/setup.rs");
+
+juniper_from_schema::graphql_schema! {
+    schema @lints(warn: ["unused_type"]) {
+      query: Query
+    }
+
+    type Query {
+      ping: Boolean! @juniper(ownership: "owned")
+    }
+
+    type Orphan {
+      id: ID!
+    }
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_ping(&self, _: &Executor<Context>) -> FieldResult<bool> {
+        Ok(true)
+    }
+}
+
+impl OrphanFields for Orphan {
+    fn field_id(&self, _: &Executor<Context>) -> FieldResult<&ID> {
+        unimplemented!()
+    }
+}
+
+pub struct Orphan;
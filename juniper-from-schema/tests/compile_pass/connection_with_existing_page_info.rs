@@ -0,0 +1,49 @@
+#![allow(dead_code, unused_variables, unused_must_use, unused_imports)]
+include!("setup.rs");
+
+use juniper_from_schema::Connection;
+
+juniper_from_schema::graphql_schema! {
+    type Query {
+        users: [User!]! @connection
+    }
+
+    type User {
+        id: ID!
+    }
+
+    "Pagination, shared with the other services"
+    type PageInfo @juniper(generate_struct: true) {
+        hasNextPage: Boolean! @juniper(ownership: "owned")
+        hasPreviousPage: Boolean! @juniper(ownership: "owned")
+        startCursor: String @juniper(ownership: "as_ref")
+        endCursor: String @juniper(ownership: "as_ref")
+    }
+
+    schema { query: Query }
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<UserConnection, Walked>,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> FieldResult<Connection<User>> {
+        let _: bool = trail.page_info().walk().is_some();
+        unimplemented!()
+    }
+}
+
+pub struct User;
+
+impl UserFields for User {
+    fn field_id(&self, executor: &Executor<Context>) -> FieldResult<&ID> {
+        unimplemented!()
+    }
+}
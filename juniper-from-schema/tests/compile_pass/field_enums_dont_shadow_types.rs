@@ -0,0 +1,57 @@
+#![allow(dead_code, unused_variables, unused_must_use, unused_imports)]
+include!("setup.rs");
+
+juniper_from_schema::graphql_schema! {
+    type Query {
+        form: Form!
+    }
+
+    type Form {
+        fields: [FormField!]!
+    }
+
+    type FormField {
+        label: String!
+    }
+
+    schema { query: Query }
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_form(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<Form, Walked>,
+    ) -> FieldResult<&Form> {
+        for field in trail.selected_fields() {
+            match field {
+                fields::Form::Fields(trail) => {
+                    let _: QueryTrail<FormField, Walked> = trail;
+                }
+            }
+        }
+        unimplemented!()
+    }
+}
+
+pub struct Form;
+
+impl FormFields for Form {
+    fn field_fields(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<FormField, Walked>,
+    ) -> FieldResult<&Vec<FormField>> {
+        unimplemented!()
+    }
+}
+
+pub struct FormField;
+
+impl FormFieldFields for FormField {
+    fn field_label(&self, executor: &Executor<Context>) -> FieldResult<&String> {
+        unimplemented!()
+    }
+}
@@ -0,0 +1,43 @@
+#![allow(dead_code, unused_variables, unused_must_use, unused_imports)]
+include!("setup.rs");
+
+use juniper_from_schema::*;
+
+juniper_from_schema::graphql_schema! {
+    schema {
+        query: Query
+    }
+
+    type Query {
+        user: User! @juniper(ownership: "owned")
+    }
+
+    type User @juniper(generate_struct: true) {
+        id: ID!
+        name: String @juniper(ownership: "as_ref")
+        friends(first: Int!): [User!]! @juniper(ownership: "owned")
+    }
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_user(
+        &self,
+        _: &Executor<Context>,
+        _: &QueryTrail<'_, User, Walked>,
+    ) -> FieldResult<User> {
+        Ok(User::new(ID::new("1"), None))
+    }
+}
+
+impl UserResolvers for User {
+    fn field_friends<'s, 'r, 'a>(
+        &'s self,
+        _: &Executor<'r, 'a, Context>,
+        _: &QueryTrail<'r, User, Walked>,
+        first: i32,
+    ) -> FieldResult<Vec<User>> {
+        todo!()
+    }
+}
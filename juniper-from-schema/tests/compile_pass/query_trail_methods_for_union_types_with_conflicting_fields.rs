@@ -0,0 +1,100 @@
+#![allow(dead_code, unused_variables, unused_must_use, unused_imports)]
+include!("setup.rs");
+
+juniper_from_schema::graphql_schema! {
+    type Query {
+      entity: Entity! @juniper(ownership: "owned")
+    }
+
+    union Entity = User | Company
+
+    type User {
+      id: Int! @juniper(ownership: "owned")
+      country: Country! @juniper(ownership: "owned")
+    }
+
+    type Company {
+      id: Int! @juniper(ownership: "owned")
+      country: CountryOfOperation! @juniper(ownership: "owned")
+    }
+
+    type Country {
+      id: Int! @juniper(ownership: "owned")
+    }
+
+    type CountryOfOperation {
+      id: Int! @juniper(ownership: "owned")
+    }
+
+    schema {
+      query: Query
+    }
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_entity(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<Entity, Walked>,
+    ) -> FieldResult<Entity> {
+        let _: bool = trail.id();
+
+        // `country` has different types so it's only available after downcasting
+        let user_trail: QueryTrail<User, Walked> = trail.downcast();
+        let _: QueryTrail<Country, NotWalked> = user_trail.country();
+        let company_trail: QueryTrail<Company, Walked> = trail.downcast();
+        let _: QueryTrail<CountryOfOperation, NotWalked> = company_trail.country();
+
+        unimplemented!()
+    }
+}
+
+pub struct User;
+
+impl UserFields for User {
+    fn field_id(&self, executor: &Executor<Context>) -> FieldResult<i32> {
+        unimplemented!()
+    }
+
+    fn field_country(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<Country, Walked>,
+    ) -> FieldResult<Country> {
+        unimplemented!()
+    }
+}
+
+pub struct Company;
+
+impl CompanyFields for Company {
+    fn field_id(&self, executor: &Executor<Context>) -> FieldResult<i32> {
+        unimplemented!()
+    }
+
+    fn field_country(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<CountryOfOperation, Walked>,
+    ) -> FieldResult<CountryOfOperation> {
+        unimplemented!()
+    }
+}
+
+pub struct Country;
+
+impl CountryFields for Country {
+    fn field_id(&self, executor: &Executor<Context>) -> FieldResult<i32> {
+        unimplemented!()
+    }
+}
+
+pub struct CountryOfOperation;
+
+impl CountryOfOperationFields for CountryOfOperation {
+    fn field_id(&self, executor: &Executor<Context>) -> FieldResult<i32> {
+        unimplemented!()
+    }
+}
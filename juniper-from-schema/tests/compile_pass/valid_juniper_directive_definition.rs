@@ -14,8 +14,11 @@ juniper_from_schema::graphql_schema! {
         with_time_zone: Boolean = true,
         async: Boolean = false,
         stream_item_infallible: Boolean = true,
-        stream_type: String = null
-    ) on FIELD_DEFINITION | SCALAR
+        stream_type: String = null,
+        generate_struct: Boolean = false,
+        batch: Boolean = false,
+        node_interface: Boolean = false
+    ) on FIELD_DEFINITION | SCALAR | OBJECT | INTERFACE
 }
 
 pub struct Query;
@@ -0,0 +1,184 @@
+#![allow(dead_code, unused_variables, unused_imports)]
+
+#[macro_use]
+extern crate juniper;
+
+use juniper::{EmptyMutation, EmptySubscription, Executor, FieldResult, Variables};
+use juniper_from_schema::{graphql_schema, Connection};
+use serde_json::{json, Value};
+use std::sync::Mutex;
+
+graphql_schema! {
+    schema {
+        query: Query
+    }
+
+    type Query {
+        users: [User!]! @connection
+        admins: [User!] @connection @juniper(infallible: true)
+    }
+
+    type User {
+        id: Int! @juniper(ownership: "owned")
+        name: String!
+    }
+}
+
+pub struct Context {
+    node_trail_walked: Mutex<Option<bool>>,
+}
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<UserConnection, Walked>,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> FieldResult<Connection<User>> {
+        *executor.context().node_trail_walked.lock().unwrap() =
+            Some(trail.edges().node().walk().is_some());
+
+        let after = after.map(|cursor| cursor.parse::<i32>()).transpose()?;
+        let first = first.unwrap_or(10);
+
+        let users = all_users()
+            .into_iter()
+            .filter(|user| user.id > after.unwrap_or(0))
+            .collect::<Vec<_>>();
+        let has_next_page = users.len() > first as usize;
+
+        Ok(Connection::new()
+            .edges(
+                users
+                    .into_iter()
+                    .take(first as usize)
+                    .map(|user| (user.id.to_string(), user)),
+            )
+            .has_next_page(has_next_page)
+            .has_previous_page(after.is_some()))
+    }
+
+    fn field_admins(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<UserConnection, Walked>,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> Option<Connection<User>> {
+        None
+    }
+}
+
+pub struct User {
+    id: i32,
+    name: String,
+}
+
+impl UserFields for User {
+    fn field_id(&self, executor: &Executor<Context>) -> FieldResult<i32> {
+        Ok(self.id)
+    }
+
+    fn field_name(&self, executor: &Executor<Context>) -> FieldResult<&String> {
+        Ok(&self.name)
+    }
+}
+
+fn all_users() -> Vec<User> {
+    vec!["Alice", "Bob", "Carol"]
+        .into_iter()
+        .enumerate()
+        .map(|(idx, name)| User {
+            id: idx as i32 + 1,
+            name: name.to_string(),
+        })
+        .collect()
+}
+
+#[test]
+fn test_paginating_a_connection() {
+    let (value, node_trail_walked) = run_query(
+        r#"
+        query {
+            users(first: 1, after: "1") {
+                edges {
+                    cursor
+                    node { name }
+                }
+                pageInfo {
+                    hasNextPage
+                    hasPreviousPage
+                    startCursor
+                    endCursor
+                }
+            }
+        }
+        "#,
+    );
+
+    assert_eq!(
+        json!({
+            "edges": [{ "cursor": "2", "node": { "name": "Bob" } }],
+            "pageInfo": {
+                "hasNextPage": true,
+                "hasPreviousPage": true,
+                "startCursor": "2",
+                "endCursor": "2",
+            },
+        }),
+        value["users"]
+    );
+    assert_eq!(Some(true), node_trail_walked);
+}
+
+#[test]
+fn test_empty_connections_have_no_cursors() {
+    let (value, node_trail_walked) = run_query(
+        r#"
+        query {
+            users(after: "3") {
+                pageInfo { startCursor endCursor hasNextPage }
+            }
+            admins { edges { cursor } }
+        }
+        "#,
+    );
+
+    assert_eq!(
+        json!({
+            "users": {
+                "pageInfo": { "startCursor": null, "endCursor": null, "hasNextPage": false },
+            },
+            "admins": null,
+        }),
+        value
+    );
+    assert_eq!(Some(false), node_trail_walked);
+}
+
+fn run_query(query: &str) -> (Value, Option<bool>) {
+    let context = Context {
+        node_trail_walked: Mutex::new(None),
+    };
+    let (res, errors) = juniper::execute_sync(
+        query,
+        None,
+        &Schema::new(Query, EmptyMutation::new(), EmptySubscription::new()),
+        &Variables::new(),
+        &context,
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    let value = serde_json::from_str(&serde_json::to_string(&res).unwrap()).unwrap();
+    (value, context.node_trail_walked.into_inner().unwrap())
+}
@@ -0,0 +1,254 @@
+#![allow(dead_code, unused_variables, unused_imports)]
+
+#[macro_use]
+extern crate juniper;
+
+use futures::executor::block_on;
+use juniper::{
+    http::GraphQLRequest, EmptyMutation, EmptySubscription, Executor, FieldResult, FromInputValue,
+    InputValue, ID,
+};
+use juniper_from_schema::{graphql_schema, prepare_federation_request, Representation};
+use serde_json::{json, Value};
+
+graphql_schema! {
+    schema @lints(allow: ["missing_description"]) {
+        query: Query
+    }
+
+    type Query {
+        me: User! @juniper(ownership: "owned")
+    }
+
+    type User @key(fields: "id") {
+        id: ID! @juniper(ownership: "owned")
+        name: String! @juniper(ownership: "owned")
+    }
+
+    type Product @key(fields: "upc") @extends {
+        upc: String! @external @juniper(ownership: "owned")
+        reviews: [Review!]! @juniper(ownership: "owned")
+    }
+
+    type Review {
+        body: String! @juniper(ownership: "owned")
+        author: User! @provides(fields: "name") @juniper(ownership: "owned")
+    }
+}
+
+pub struct Context;
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_me(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<User, Walked>,
+    ) -> FieldResult<User> {
+        Ok(User { id: 1 })
+    }
+}
+
+pub struct User {
+    id: i32,
+}
+
+impl UserFields for User {
+    fn field_id(&self, executor: &Executor<Context>) -> FieldResult<ID> {
+        Ok(ID::new(self.id.to_string()))
+    }
+
+    fn field_name(&self, executor: &Executor<Context>) -> FieldResult<String> {
+        Ok(format!("User {}", self.id))
+    }
+}
+
+#[juniper_from_schema::juniper::async_trait]
+impl UserReferenceResolver for User {
+    async fn resolve_reference<'r, 'a>(
+        executor: &Executor<'r, 'a, Context>,
+        trail: &QueryTrail<'r, User, Walked>,
+        representation: &Representation,
+    ) -> FieldResult<Option<User>> {
+        let id = representation.get::<ID>("id").ok_or("Missing `id`")?;
+        let id = id.parse::<i32>()?;
+
+        // Only the first two users exist
+        if id > 2 {
+            return Ok(None);
+        }
+
+        Ok(Some(User { id }))
+    }
+}
+
+pub struct Product {
+    upc: String,
+}
+
+impl ProductFields for Product {
+    fn field_upc(&self, executor: &Executor<Context>) -> FieldResult<String> {
+        Ok(self.upc.clone())
+    }
+
+    fn field_reviews(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<Review, Walked>,
+    ) -> FieldResult<Vec<Review>> {
+        Ok(vec![Review {
+            body: format!("Great {}", self.upc),
+        }])
+    }
+}
+
+#[juniper_from_schema::juniper::async_trait]
+impl ProductReferenceResolver for Product {
+    async fn resolve_reference<'r, 'a>(
+        executor: &Executor<'r, 'a, Context>,
+        trail: &QueryTrail<'r, Product, Walked>,
+        representation: &Representation,
+    ) -> FieldResult<Option<Product>> {
+        let upc = representation.get::<String>("upc").ok_or("Missing `upc`")?;
+        Ok(Some(Product { upc }))
+    }
+}
+
+pub struct Review {
+    body: String,
+}
+
+impl ReviewFields for Review {
+    fn field_body(&self, executor: &Executor<Context>) -> FieldResult<String> {
+        Ok(self.body.clone())
+    }
+
+    fn field_author(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<User, Walked>,
+    ) -> FieldResult<User> {
+        Ok(User { id: 2 })
+    }
+}
+
+#[test]
+fn test_service_sdl() {
+    let value = run_query("query { _service { sdl } }", json!({})).unwrap();
+    let sdl = value["_service"]["sdl"].as_str().unwrap();
+
+    assert!(sdl.contains(r#"type User @key(fields: "id") {"#), "{}", sdl);
+    assert!(
+        sdl.contains(r#"type Product @key(fields: "upc") @extends {"#),
+        "{}",
+        sdl
+    );
+    assert!(sdl.contains("upc: String! @external\n"), "{}", sdl);
+    assert!(
+        sdl.contains(r#"author: User! @provides(fields: "name")"#),
+        "{}",
+        sdl
+    );
+    assert!(sdl.contains("schema {\n"), "{}", sdl);
+    assert!(!sdl.contains("@juniper"), "{}", sdl);
+    assert!(!sdl.contains("@lints"), "{}", sdl);
+    assert!(!sdl.contains("_entities"), "{}", sdl);
+}
+
+#[test]
+fn test_resolving_entities() {
+    let value = run_query(
+        r#"
+        query($representations: [_Any!]!) {
+            _entities(representations: $representations) {
+                ... on User { name }
+                ... on Product { reviews { body } }
+            }
+        }
+        "#,
+        json!({
+            "representations": [
+                { "__typename": "User", "id": "2" },
+                { "__typename": "Product", "upc": "chair" },
+                { "__typename": "User", "id": "3" },
+            ],
+        }),
+    )
+    .unwrap();
+
+    assert_eq!(
+        json!([
+            { "name": "User 2" },
+            { "reviews": [{ "body": "Great chair" }] },
+            null,
+        ]),
+        value["_entities"]
+    );
+}
+
+#[test]
+fn test_unknown_entity_types_are_errors() {
+    let errors = run_query(
+        r#"
+        query($representations: [_Any!]!) {
+            _entities(representations: $representations) { __typename }
+        }
+        "#,
+        json!({ "representations": [{ "__typename": "Review" }] }),
+    )
+    .unwrap_err();
+
+    assert_eq!(vec!["Unknown entity type `Review`"], errors);
+}
+
+#[test]
+fn test_representations() {
+    let representation = Representation::from_input_value(&InputValue::object(
+        vec![
+            ("__typename", InputValue::scalar("User")),
+            ("id", InputValue::scalar(1)),
+        ]
+        .into_iter()
+        .collect(),
+    ))
+    .unwrap();
+
+    assert_eq!("User", representation.typename());
+    assert_eq!(Some(1), representation.get::<i32>("id"));
+    assert_eq!(None, representation.get::<String>("id"));
+    assert_eq!(None, representation.get::<i32>("name"));
+
+    // `__typename` is required
+    assert_eq!(
+        None,
+        Representation::from_input_value(&InputValue::object(
+            vec![("id", InputValue::scalar(1))].into_iter().collect(),
+        ))
+    );
+}
+
+fn run_query(query: &str, variables: Value) -> Result<Value, Vec<String>> {
+    let request: GraphQLRequest = serde_json::from_value(json!({
+        "query": query,
+        "variables": variables,
+    }))
+    .unwrap();
+    let request = prepare_federation_request(request).unwrap();
+
+    let schema = Schema::new(Query, EmptyMutation::new(), EmptySubscription::new());
+    let response = block_on(request.execute(&schema, &Context));
+    let response = serde_json::to_value(&response).unwrap();
+
+    match response.get("errors") {
+        Some(errors) => Err(errors
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|error| error["message"].as_str().unwrap().to_string())
+            .collect()),
+        None => Ok(response["data"].clone()),
+    }
+}
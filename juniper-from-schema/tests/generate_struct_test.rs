@@ -0,0 +1,156 @@
+#![allow(dead_code, unused_variables, unused_imports)]
+
+#[macro_use]
+extern crate juniper;
+
+use assert_json_diff::assert_json_include;
+use juniper::{Executor, FieldResult, Variables, ID};
+use juniper_from_schema::graphql_schema;
+use serde_json::{self, json, Value};
+
+graphql_schema! {
+    schema {
+        query: Query
+    }
+
+    type Query {
+        user: User! @juniper(ownership: "owned")
+    }
+
+    "A user"
+    type User @juniper(generate_struct: true) {
+        id: ID! @juniper(infallible: true)
+        "The name of the user"
+        name: String!
+        nickname: String @juniper(ownership: "as_ref")
+        tags: [String!]! @juniper(ownership: "as_ref")
+        age: Int! @juniper(ownership: "owned", infallible: true)
+        address: Address!
+        posts(first: Int!): [Post!]! @juniper(ownership: "owned")
+        greeting(greeting: String!): String! @juniper(async: true, ownership: "owned")
+    }
+
+    type Address @juniper(generate_struct: true) {
+        city: String!
+    }
+
+    type Post @juniper(generate_struct: true) {
+        title: String!
+    }
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_user(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<User, Walked>,
+    ) -> FieldResult<User> {
+        Ok(User::new(
+            ID::new("1"),
+            "Bob".to_string(),
+            None,
+            vec!["admin".to_string()],
+            42,
+            Address::new("Copenhagen".to_string()),
+        ))
+    }
+}
+
+#[juniper_from_schema::juniper::async_trait]
+impl UserResolvers for User {
+    fn field_posts<'s, 'r, 'a>(
+        &'s self,
+        executor: &Executor<'r, 'a, Context>,
+        trail: &QueryTrail<'r, Post, Walked>,
+        first: i32,
+    ) -> FieldResult<Vec<Post>> {
+        Ok((0..first)
+            .map(|idx| Post::new(format!("Post {}", idx)))
+            .collect())
+    }
+
+    async fn field_greeting<'s, 'r, 'a>(
+        &'s self,
+        executor: &Executor<'r, 'a, Context>,
+        greeting: String,
+    ) -> FieldResult<String> {
+        Ok(format!("{}, {}", greeting, self.name))
+    }
+}
+
+#[test]
+fn generated_structs_resolve_their_fields() {
+    let value = run_query(
+        r#"query {
+            user {
+                id
+                name
+                nickname
+                tags
+                age
+                address { city }
+                posts(first: 2) { title }
+                greeting(greeting: "Hello")
+            }
+        }"#,
+    );
+
+    assert_json_include!(
+        actual: value,
+        expected: json!({
+            "user": {
+                "id": "1",
+                "name": "Bob",
+                "nickname": null,
+                "tags": ["admin"],
+                "age": 42,
+                "address": { "city": "Copenhagen" },
+                "posts": [{ "title": "Post 0" }, { "title": "Post 1" }],
+                "greeting": "Hello, Bob",
+            }
+        })
+    );
+}
+
+#[test]
+fn generated_struct_fields_are_public() {
+    let user = User {
+        id: ID::new("1"),
+        name: "Alice".to_string(),
+        nickname: Some("Al".to_string()),
+        tags: Vec::new(),
+        age: 30,
+        address: Address {
+            city: "Berlin".to_string(),
+        },
+    };
+
+    assert_eq!("Alice", user.name);
+    assert_eq!(Some("Al".to_string()), user.nickname);
+    assert_eq!("Berlin", user.address.city);
+}
+
+type Context = ();
+
+fn run_query(query: &str) -> Value {
+    let (res, _errors) = futures::executor::block_on(juniper::execute(
+        query,
+        None,
+        &Schema::new(
+            Query,
+            juniper::EmptyMutation::new(),
+            juniper::EmptySubscription::new(),
+        ),
+        &Variables::new(),
+        &(),
+    ))
+    .unwrap();
+
+    let json = serde_json::from_str(&serde_json::to_string(&res).unwrap()).unwrap();
+    println!("--- <json> -----------------");
+    println!("{}", serde_json::to_string_pretty(&json).unwrap());
+    println!("--- </json> -----------------");
+    json
+}
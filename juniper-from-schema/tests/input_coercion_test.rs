@@ -0,0 +1,173 @@
+#![allow(clippy::too_many_arguments)]
+#![allow(dead_code, unused_variables, unused_imports)]
+
+#[macro_use]
+extern crate juniper;
+
+use juniper::{EmptyMutation, EmptySubscription, Executor, FieldResult, InputValue, Variables, ID};
+use juniper_from_schema::graphql_schema;
+use serde_json::{json, Value};
+
+graphql_schema! {
+    schema {
+        query: Query
+    }
+
+    type Query {
+        a: A! @juniper(ownership: "owned")
+    }
+
+    type A {
+        field(
+            floatArg: Float!
+            ids: [ID!]!
+            colors: [Color!]
+            color: Color
+            nullableArg: String
+            defaultArg: Int = 10
+            input: InputObject
+        ): String! @juniper(ownership: "owned")
+    }
+
+    input InputObject {
+        firstName: String!
+        lastName: String
+    }
+
+    enum Color {
+        RED
+        BLUE
+    }
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_a(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<A, Walked>,
+    ) -> FieldResult<A> {
+        let args = trail.field_args();
+        let from_trail = format!(
+            "{:?}",
+            (
+                args.try_float_arg()?,
+                args.try_ids()?,
+                args.try_colors()?,
+                args.try_color()?,
+                args.try_nullable_arg()?,
+                args.try_default_arg()?,
+                args.try_input()?,
+            )
+        );
+        Ok(A { from_trail })
+    }
+}
+
+pub struct A {
+    from_trail: String,
+}
+
+impl AFields for A {
+    fn field_field(
+        &self,
+        executor: &Executor<Context>,
+        float_arg: f64,
+        ids: Vec<ID>,
+        colors: Option<Vec<Color>>,
+        color: Option<Color>,
+        nullable_arg: Option<String>,
+        default_arg: i32,
+        input: Option<InputObject>,
+    ) -> FieldResult<String> {
+        let from_resolver = format!(
+            "{:?}",
+            (
+                float_arg,
+                ids,
+                colors,
+                color,
+                nullable_arg,
+                default_arg,
+                input
+            )
+        );
+        assert_eq!(from_resolver, self.from_trail);
+        Ok(from_resolver)
+    }
+}
+
+#[test]
+fn test_literals_are_coerced_like_juniper_does() {
+    let value = run_query(
+        r#"
+        query {
+            a {
+                field(
+                    floatArg: 1,
+                    ids: 2,
+                    colors: RED,
+                    input: { firstName: "Alice" },
+                )
+            }
+        }
+        "#,
+        Variables::new(),
+    );
+
+    assert_eq!(
+        json!(
+            "(1.0, [ID(\"2\")], Some([Red]), None, None, 10, \
+             Some(InputObject { first_name: \"Alice\", last_name: None }))"
+        ),
+        value["a"]["field"]
+    );
+}
+
+#[test]
+fn test_variables_are_coerced_like_juniper_does() {
+    let mut variables = Variables::new();
+    variables.insert("floatArg".to_string(), InputValue::scalar(2));
+    variables.insert("ids".to_string(), InputValue::scalar("3"));
+    variables.insert("color".to_string(), InputValue::scalar("BLUE"));
+    variables.insert("nullableArg".to_string(), InputValue::null());
+
+    let value = run_query(
+        r#"
+        query($floatArg: Float!, $ids: [ID!]!, $color: Color, $nullableArg: String, $defaultArg: Int) {
+            a {
+                field(
+                    floatArg: $floatArg,
+                    ids: $ids,
+                    color: $color,
+                    nullableArg: $nullableArg,
+                    defaultArg: $defaultArg,
+                )
+            }
+        }
+        "#,
+        variables,
+    );
+
+    assert_eq!(
+        json!("(2.0, [ID(\"3\")], None, Some(Blue), None, 10, None)"),
+        value["a"]["field"]
+    );
+}
+
+type Context = ();
+
+fn run_query(query: &str, variables: Variables) -> Value {
+    let (res, errors) = juniper::execute_sync(
+        query,
+        None,
+        &Schema::new(Query, EmptyMutation::new(), EmptySubscription::new()),
+        &variables,
+        &(),
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    serde_json::from_str(&serde_json::to_string(&res).unwrap()).unwrap()
+}
@@ -0,0 +1,225 @@
+#![allow(dead_code, unused_variables, unused_imports)]
+
+#[macro_use]
+extern crate juniper;
+
+use futures::executor::block_on;
+use juniper::{EmptyMutation, EmptySubscription, Executor, FieldResult, Variables, ID};
+use juniper_from_schema::{graphql_schema, GlobalId, GlobalIdError, NodeType};
+use serde_json::{json, Value};
+
+graphql_schema! {
+    schema {
+        query: Query
+    }
+
+    type Query {
+        node(id: ID!): Node @juniper(async: true)
+        nodes(ids: [ID!]!): [Node]! @juniper(async: true)
+    }
+
+    interface Node @juniper(node_interface: true) {
+        id: ID! @juniper(ownership: "owned")
+    }
+
+    type User implements Node {
+        id: ID! @juniper(ownership: "owned")
+        name: String!
+    }
+
+    type BlogPost implements Node {
+        id: ID! @juniper(ownership: "owned")
+        title: String!
+    }
+}
+
+pub struct Context;
+
+impl juniper::Context for Context {}
+
+pub struct Query;
+
+#[juniper_from_schema::juniper::async_trait]
+impl NodeResolver for Query {
+    async fn resolve_user<'s, 'r, 'a>(
+        &'s self,
+        executor: &Executor<'r, 'a, Context>,
+        trail: &QueryTrail<'r, User, Walked>,
+        id: GlobalId<User>,
+    ) -> FieldResult<Option<User>> {
+        let id = id.id().parse::<i32>()?;
+
+        Ok(Some(User {
+            id,
+            name: format!("User {}", id),
+        }))
+    }
+
+    async fn resolve_blog_post<'s, 'r, 'a>(
+        &'s self,
+        executor: &Executor<'r, 'a, Context>,
+        trail: &QueryTrail<'r, BlogPost, Walked>,
+        id: GlobalId<BlogPost>,
+    ) -> FieldResult<Option<BlogPost>> {
+        // Only the first post exists
+        if id.id() != "1" {
+            return Ok(None);
+        }
+
+        Ok(Some(BlogPost {
+            id: 1,
+            title: "Hello".to_string(),
+        }))
+    }
+}
+
+pub struct User {
+    id: i32,
+    name: String,
+}
+
+impl UserFields for User {
+    fn field_id(&self, executor: &Executor<Context>) -> FieldResult<ID> {
+        Ok(GlobalId::<User>::new(self.id.to_string()).encode())
+    }
+
+    fn field_name(&self, executor: &Executor<Context>) -> FieldResult<&String> {
+        Ok(&self.name)
+    }
+}
+
+pub struct BlogPost {
+    id: i32,
+    title: String,
+}
+
+impl BlogPostFields for BlogPost {
+    fn field_id(&self, executor: &Executor<Context>) -> FieldResult<ID> {
+        Ok(GlobalId::<BlogPost>::new(self.id.to_string()).encode())
+    }
+
+    fn field_title(&self, executor: &Executor<Context>) -> FieldResult<&String> {
+        Ok(&self.title)
+    }
+}
+
+#[test]
+fn test_global_ids() {
+    let id = GlobalId::<BlogPost>::new("42").encode();
+    assert_eq!("BlogPost", BlogPost::TYPE_NAME);
+    assert_eq!(ID::new("QmxvZ1Bvc3Q6NDI="), id);
+    assert_eq!(
+        GlobalId::new("42"),
+        GlobalId::<BlogPost>::decode(&id).unwrap()
+    );
+
+    assert_eq!(
+        Err(GlobalIdError::WrongType {
+            expected: "User",
+            found: "BlogPost".to_string(),
+        }),
+        GlobalId::<User>::decode(&id)
+    );
+    assert_eq!(
+        Err(GlobalIdError::Malformed("42".to_string())),
+        GlobalId::<User>::decode(&ID::new("42"))
+    );
+}
+
+#[test]
+fn test_global_ids_round_trip() {
+    for id in &["", "1", "12", "123", "1234", "12345"] {
+        let global_id = GlobalId::<User>::new(*id);
+        assert_eq!(
+            global_id,
+            GlobalId::<User>::decode(&global_id.encode()).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_global_ids_with_bad_padding() {
+    for id in &["QmxvZ1Bvc3Q6NDI==", "QmxvZ1Bvc3Q6N=DI", "=QmxvZ1Bvc3Q6NDI"] {
+        assert_eq!(
+            Err(GlobalIdError::Malformed(id.to_string())),
+            GlobalId::<BlogPost>::decode(&ID::new(*id))
+        );
+    }
+}
+
+#[test]
+fn test_looking_up_a_node() {
+    let id = GlobalId::<User>::new("7").encode();
+    let value = run_query(&format!(
+        r#"
+        query {{
+            node(id: "{}") {{
+                id
+                ... on User {{ name }}
+            }}
+        }}
+        "#,
+        id
+    ))
+    .unwrap();
+
+    assert_eq!(
+        json!({ "id": id.to_string(), "name": "User 7" }),
+        value["node"]
+    );
+}
+
+#[test]
+fn test_looking_up_nodes_of_different_types() {
+    let ids = [
+        GlobalId::<BlogPost>::new("1").encode(),
+        GlobalId::<User>::new("2").encode(),
+        GlobalId::<BlogPost>::new("2").encode(),
+        // An unknown type
+        ID::new("Q29tbWVudDox"),
+    ];
+    let value = run_query(&format!(
+        r#"
+        query {{
+            nodes(ids: {:?}) {{
+                ... on User {{ name }}
+                ... on BlogPost {{ title }}
+            }}
+        }}
+        "#,
+        ids.iter().map(|id| id.to_string()).collect::<Vec<_>>()
+    ))
+    .unwrap();
+
+    assert_eq!(
+        json!([{ "title": "Hello" }, { "name": "User 2" }, null, null]),
+        value["nodes"]
+    );
+}
+
+#[test]
+fn test_malformed_ids_are_errors() {
+    let errors = run_query(r#"query { node(id: "not an id") { id } }"#).unwrap_err();
+
+    assert_eq!(vec!["Invalid global id `not an id`"], errors);
+}
+
+fn run_query(query: &str) -> Result<Value, Vec<String>> {
+    let (res, errors) = block_on(juniper::execute(
+        query,
+        None,
+        &Schema::new(Query, EmptyMutation::new(), EmptySubscription::new()),
+        &Variables::new(),
+        &Context,
+    ))
+    .unwrap();
+
+    if !errors.is_empty() {
+        return Err(errors
+            .iter()
+            .map(|error| error.error().message().to_string())
+            .collect());
+    }
+
+    Ok(serde_json::from_str(&serde_json::to_string(&res).unwrap()).unwrap())
+}
@@ -0,0 +1,254 @@
+#![allow(dead_code, unused_variables, unused_imports)]
+
+#[macro_use]
+extern crate juniper;
+
+use async_trait::async_trait;
+use futures::{executor::block_on, stream, Stream, StreamExt};
+use juniper::{Executor, FieldResult, Variables};
+use juniper_from_schema::graphql_schema;
+use serde_json::{json, Value};
+use std::pin::Pin;
+
+graphql_schema! {
+    schema {
+        query: Query
+        mutation: Mutation
+        subscription: Subscription
+    }
+
+    type Query {
+        ping: Boolean! @juniper(ownership: "owned")
+    }
+
+    type Mutation {
+        createUser(name: String!): CreateUserPayload! @juniper(ownership: "owned")
+    }
+
+    type Subscription {
+        userEvents(kind: String!): [UserEvent!]! @juniper(ownership: "owned", async: true)
+    }
+
+    type CreateUserPayload {
+        user: User! @juniper(ownership: "owned")
+        sources: [String!]! @juniper(ownership: "owned")
+    }
+
+    type UserEvent {
+        user: User! @juniper(ownership: "owned")
+        sources: [String!]! @juniper(ownership: "owned")
+    }
+
+    type User {
+        name: String! @juniper(ownership: "owned")
+        avatar(size: Int = 64): String! @juniper(ownership: "owned")
+    }
+}
+
+type Context = ();
+
+// The data sources a resolver would subscribe to or load from, based on what the client selected
+fn data_sources(trail: &QueryTrail<User, Walked>) -> Vec<String> {
+    let mut sources = vec!["users".to_string()];
+    if trail.avatar() {
+        sources.push(format!("avatars/{}", trail.avatar_args().size()));
+    }
+    sources
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_ping(&self, executor: &Executor<Context>) -> FieldResult<bool> {
+        Ok(true)
+    }
+}
+
+pub struct Mutation;
+
+impl MutationFields for Mutation {
+    fn field_create_user(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<CreateUserPayload, Walked>,
+        name: String,
+    ) -> FieldResult<CreateUserPayload> {
+        let sources = trail
+            .user()
+            .walk()
+            .map(|user| data_sources(&user))
+            .unwrap_or_default();
+
+        Ok(CreateUserPayload {
+            user: User { name },
+            sources,
+        })
+    }
+}
+
+pub struct Subscription;
+
+#[async_trait]
+impl SubscriptionFields for Subscription {
+    async fn field_user_events<'s, 'r, 'a>(
+        &'s self,
+        executor: &Executor<'r, 'a, Context>,
+        trail: &QueryTrail<'r, UserEvent, Walked>,
+        kind: String,
+    ) -> FieldResult<Pin<Box<dyn Stream<Item = Vec<UserEvent>> + Send>>> {
+        // The trail borrows from the executor so everything needed from it has to be read before
+        // the stream is returned
+        let sources = trail
+            .user()
+            .walk()
+            .map(|user| data_sources(&user))
+            .unwrap_or_default();
+
+        let events = (1..=2).map(move |n| {
+            vec![UserEvent {
+                user: User {
+                    name: format!("{} {}", kind, n),
+                },
+                sources: sources.clone(),
+            }]
+        });
+
+        Ok(Box::pin(stream::iter(events)))
+    }
+}
+
+pub struct CreateUserPayload {
+    user: User,
+    sources: Vec<String>,
+}
+
+impl CreateUserPayloadFields for CreateUserPayload {
+    fn field_user(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<User, Walked>,
+    ) -> FieldResult<User> {
+        Ok(User {
+            name: self.user.name.clone(),
+        })
+    }
+
+    fn field_sources(&self, executor: &Executor<Context>) -> FieldResult<Vec<String>> {
+        Ok(self.sources.clone())
+    }
+}
+
+pub struct UserEvent {
+    user: User,
+    sources: Vec<String>,
+}
+
+impl UserEventFields for UserEvent {
+    fn field_user(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<User, Walked>,
+    ) -> FieldResult<User> {
+        Ok(User {
+            name: self.user.name.clone(),
+        })
+    }
+
+    fn field_sources(&self, executor: &Executor<Context>) -> FieldResult<Vec<String>> {
+        Ok(self.sources.clone())
+    }
+}
+
+pub struct User {
+    name: String,
+}
+
+impl UserFields for User {
+    fn field_name(&self, executor: &Executor<Context>) -> FieldResult<String> {
+        Ok(self.name.clone())
+    }
+
+    fn field_avatar(&self, executor: &Executor<Context>, size: i32) -> FieldResult<String> {
+        Ok(format!("{}.png", size))
+    }
+}
+
+fn schema() -> Schema {
+    Schema::new(Query, Mutation, Subscription)
+}
+
+#[test]
+fn test_query_trail_for_mutation_payload() {
+    let (res, errors) = juniper::execute_sync(
+        r#"
+        mutation {
+            createUser(name: "Alice") {
+                sources
+                user { avatar(size: 32) }
+            }
+        }
+        "#,
+        None,
+        &schema(),
+        &Variables::new(),
+        &(),
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    let value: Value = serde_json::from_str(&serde_json::to_string(&res).unwrap()).unwrap();
+    assert_eq!(
+        json!(["users", "avatars/32"]),
+        value["createUser"]["sources"]
+    );
+}
+
+#[test]
+fn test_query_trail_for_subscription_stream_items() {
+    let schema = schema();
+    let variables = Variables::new();
+
+    let values = block_on(async {
+        let (res, errors) = juniper::resolve_into_stream(
+            r#"
+            subscription {
+                userEvents(kind: "signup") {
+                    sources
+                    user { name avatar }
+                }
+            }
+            "#,
+            None,
+            &schema,
+            &variables,
+            &(),
+        )
+        .await
+        .unwrap();
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let mut values = vec![];
+        let mut stream = match res {
+            juniper::Value::Object(object) => match object.into_iter().next() {
+                Some((_, juniper::Value::Scalar(stream))) => stream,
+                _ => panic!("expected a stream"),
+            },
+            _ => panic!("expected an object"),
+        };
+
+        while let Some(item) = stream.next().await {
+            let item = item.unwrap();
+            values.push(serde_json::to_value(&item).unwrap());
+        }
+
+        values
+    });
+
+    assert_eq!(
+        vec![
+            json!([{ "sources": ["users", "avatars/64"], "user": { "name": "signup 1", "avatar": "64.png" } }]),
+            json!([{ "sources": ["users", "avatars/64"], "user": { "name": "signup 2", "avatar": "64.png" } }]),
+        ],
+        values
+    );
+}
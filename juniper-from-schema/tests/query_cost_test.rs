@@ -0,0 +1,298 @@
+#![allow(dead_code, unused_variables, unused_imports)]
+
+#[macro_use]
+extern crate juniper;
+
+use juniper::{
+    EmptyMutation, EmptySubscription, Executor, FieldError, FieldResult, GraphQLError, InputValue,
+    Variables,
+};
+use juniper_from_schema::graphql_schema;
+use serde_json::{json, Value};
+
+graphql_schema! {
+    schema {
+        query: Query
+    }
+
+    type Query {
+        users(first: Int = 10): [User!]!
+            @juniper(ownership: "owned")
+            @cost(complexity: 2, multipliers: ["first"])
+        entity: Entity! @juniper(ownership: "owned")
+    }
+
+    type User @cost(complexity: 3) {
+        cost: String! @juniper(ownership: "owned")
+        name: String! @juniper(ownership: "owned")
+        friends(first: Int, last: Int): [User!]!
+            @juniper(ownership: "owned")
+            @cost(multipliers: ["first", "last"])
+        bestFriend: User! @juniper(ownership: "owned")
+    }
+
+    type Company {
+        cost: String! @juniper(ownership: "owned")
+        employees(first: Int!): [User!]!
+            @juniper(ownership: "owned")
+            @cost(complexity: 0, multipliers: ["first"])
+    }
+
+    union Entity = User | Company
+
+    directive @cost(complexity: Int, multipliers: [String!]) on FIELD_DEFINITION | OBJECT
+}
+
+const MAX_COST: u64 = 1000;
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_users(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<User, Walked>,
+        first: i32,
+    ) -> FieldResult<Vec<User>> {
+        let cost = query_cost(&executor.look_ahead());
+        if cost.cost() > MAX_COST {
+            return Err(FieldError::from(format!(
+                "Query is too expensive: {}",
+                cost.cost()
+            )));
+        }
+
+        let children = trail.query_cost();
+        Ok(vec![User(format!(
+            "{}/{} children={}/{}",
+            cost.cost(),
+            cost.depth(),
+            children.cost(),
+            children.depth(),
+        ))])
+    }
+
+    fn field_entity(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<Entity, Walked>,
+    ) -> FieldResult<Entity> {
+        let cost = query_cost(&executor.look_ahead());
+        Ok(Entity::Company(Company(format!(
+            "{}/{}",
+            cost.cost(),
+            cost.depth()
+        ))))
+    }
+}
+
+pub struct User(String);
+
+impl UserFields for User {
+    fn field_cost(&self, executor: &Executor<Context>) -> FieldResult<String> {
+        Ok(self.0.clone())
+    }
+
+    fn field_name(&self, executor: &Executor<Context>) -> FieldResult<String> {
+        Ok(String::new())
+    }
+
+    fn field_friends(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<User, Walked>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> FieldResult<Vec<User>> {
+        Ok(vec![])
+    }
+
+    fn field_best_friend(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<User, Walked>,
+    ) -> FieldResult<User> {
+        Ok(User(String::new()))
+    }
+}
+
+pub struct Company(String);
+
+impl CompanyFields for Company {
+    fn field_cost(&self, executor: &Executor<Context>) -> FieldResult<String> {
+        Ok(self.0.clone())
+    }
+
+    fn field_employees(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<User, Walked>,
+        first: i32,
+    ) -> FieldResult<Vec<User>> {
+        Ok(vec![])
+    }
+}
+
+#[test]
+fn test_cost_of_query() {
+    let value = run_query(
+        r#"
+        query {
+            users(first: 2) {
+                cost
+                friends(first: 3) { name }
+                bestFriend { name }
+            }
+        }
+        "#,
+        Variables::new(),
+    );
+
+    // `cost` costs 1, `friends` costs (3 + 1) * 3 as `User` has complexity 3, and `bestFriend`
+    // costs 3 + 1. `users` then costs (2 + 1 + 12 + 4) * 2.
+    assert_eq!(json!("38/3 children=17/2"), value["users"][0]["cost"]);
+}
+
+#[test]
+fn test_cost_of_aliases_and_variables() {
+    let mut variables = Variables::new();
+    variables.insert("first".to_string(), InputValue::scalar(4));
+
+    let value = run_query(
+        r#"
+        query($first: Int, $last: Int) {
+            users {
+                cost
+                a: friends(first: $first, last: 2) { name }
+                b: friends(last: $last) { name }
+            }
+        }
+        "#,
+        variables,
+    );
+
+    // `a` costs (3 + 1) * 4 * 2 and `b` costs 3 + 1, since `$last` isn't passed. `users` uses the
+    // default value of `first`.
+    assert_eq!(json!("390/3 children=37/2"), value["users"][0]["cost"]);
+}
+
+#[test]
+fn test_cost_of_union_fragments() {
+    let value = run_query(
+        r#"
+        query {
+            entity {
+                __typename
+                ... on Company {
+                    cost
+                    employees(first: 5) { name }
+                }
+            }
+        }
+        "#,
+        Variables::new(),
+    );
+
+    // `employees` costs (0 + 1) * 5
+    assert_eq!(json!("7/3"), value["entity"]["cost"]);
+}
+
+#[test]
+fn test_rejecting_expensive_queries() {
+    let (res, errors) = juniper::execute_sync(
+        r#"
+        query {
+            users(first: 100) {
+                friends(first: 100) { name }
+            }
+        }
+        "#,
+        None,
+        &Schema::new(Query, EmptyMutation::new(), EmptySubscription::new()),
+        &Variables::new(),
+        &(),
+    )
+    .unwrap();
+
+    assert_eq!(1, errors.len());
+    assert_eq!("Query is too expensive: 40200", errors[0].error().message());
+}
+
+#[test]
+fn test_cost_of_whole_queries() {
+    let schema = Schema::new(Query, EmptyMutation::new(), EmptySubscription::new());
+    let document = juniper::parser::parse_document_source(
+        r#"
+        query Other {
+            users { name }
+        }
+
+        query Expensive($first: Int = 30) {
+            a: users(first: $first) { name }
+            b: users(first: $first) { ...Friends }
+            entity {
+                ... on Company { employees(first: 5) { name } }
+            }
+        }
+
+        fragment Friends on User {
+            friends(first: 2) @skip(if: false) { name }
+            bestFriend @include(if: false) { name }
+        }
+        "#,
+        &schema.schema,
+    )
+    .unwrap();
+
+    let cost = schema
+        .query_cost(&document, Some("Expensive"), &Variables::new())
+        .unwrap();
+
+    // `a` costs (2 + 1) * 30, `b` costs (2 + (3 + 1) * 2) * 30, and `entity` costs 1 + (0 + 1) * 5
+    assert_eq!(396, cost.cost());
+    assert_eq!(3, cost.depth());
+
+    assert_eq!(
+        Err(GraphQLError::UnknownOperationName),
+        schema.query_cost(&document, Some("Unknown"), &Variables::new())
+    );
+}
+
+#[test]
+fn test_rejecting_expensive_queries_with_several_root_fields() {
+    let query = r#"
+        query {
+            a: users(first: 40) { friends(first: 2) { name } }
+            b: users(first: 40) { friends(first: 2) { name } }
+            c: users(first: 40) { friends(first: 2) { name } }
+        }
+    "#;
+    let schema = Schema::new(Query, EmptyMutation::new(), EmptySubscription::new());
+
+    // Every field costs 400 on its own, so checking them in their resolvers lets the query through
+    let (_, errors) = juniper::execute_sync(query, None, &schema, &Variables::new(), &()).unwrap();
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    let document = juniper::parser::parse_document_source(query, &schema.schema).unwrap();
+    let cost = schema
+        .query_cost(&document, None, &Variables::new())
+        .unwrap();
+    assert_eq!(1200, cost.cost());
+    assert!(cost.cost() > MAX_COST);
+}
+
+type Context = ();
+
+fn run_query(query: &str, variables: Variables) -> Value {
+    let (res, errors) = juniper::execute_sync(
+        query,
+        None,
+        &Schema::new(Query, EmptyMutation::new(), EmptySubscription::new()),
+        &variables,
+        &(),
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    serde_json::from_str(&serde_json::to_string(&res).unwrap()).unwrap()
+}
@@ -6,10 +6,10 @@ extern crate juniper;
 
 use assert_json_diff::assert_json_include;
 use chrono::prelude::*;
-use juniper::{Executor, FieldResult, Variables, ID};
-use juniper_from_schema::{graphql_schema, graphql_schema_from_file};
+use juniper::{EmptyMutation, EmptySubscription, Executor, FieldResult, Variables, ID};
+use juniper_from_schema::{graphql_schema, graphql_schema_from_file, QueryTrailError};
 use serde_json::{self, json, Value};
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Mutex};
 use url::Url;
 use uuid::Uuid;
 
@@ -20,6 +20,7 @@ graphql_schema! {
 
     type Query {
         a: A! @juniper(ownership: "owned")
+        unvalidated: C @juniper(ownership: "owned")
     }
 
     type A {
@@ -85,6 +86,20 @@ impl QueryFields for Query {
         trail: &QueryTrail<A, Walked>,
     ) -> FieldResult<A> {
         if let Some(c) = trail.b().c().walk() {
+            if !c.field_with_arg() {
+                assert_eq!(
+                    Err(QueryTrailError::FieldNotSelected(
+                        "fieldWithArg".to_string()
+                    )),
+                    c.field_with_arg_args().try_string_arg()
+                );
+                assert_eq!(
+                    Ok("qux".to_string()),
+                    c.field_with_arg_returning_type_args().try_string_arg()
+                );
+                return Ok(A);
+            }
+
             assert_eq!("foo".to_string(), c.field_with_arg_args().string_arg());
             assert_eq!(None, c.field_with_arg_args().nullable_arg());
             assert_eq!(
@@ -137,6 +152,23 @@ impl QueryFields for Query {
 
         Ok(A)
     }
+
+    fn field_unvalidated(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<C, Walked>,
+    ) -> FieldResult<Option<C>> {
+        let args = trail.field_with_arg_args();
+        let mut errors = executor.context().errors.lock().unwrap();
+        errors.extend(args.try_string_arg().err());
+        errors.extend(args.try_int_arg().err());
+        errors.extend(args.try_enum_arg().err());
+        errors.extend(args.try_object_arg().err());
+        errors.extend(args.try_url_arg().err());
+
+        // Returning `null` means juniper never converts the invalid arguments itself
+        Ok(None)
+    }
 }
 
 pub struct A;
@@ -251,7 +283,117 @@ fn scalar_values() {
     );
 }
 
-type Context = ();
+#[test]
+fn fields_not_selected() {
+    let value = run_query(
+        r#"query {
+        a {
+            b {
+                c {
+                    fieldWithArgReturningType(stringArg: "qux") {
+                      value
+                    }
+                }
+            }
+        }
+    }"#,
+    );
+    assert_json_include!(
+        actual: value,
+        expected: json!({
+            "a": { "b": { "c": {} } }
+        })
+    );
+}
+
+const VALID_ARGS: &str = r#"
+    stringArg: "foo",
+    intArg: 1,
+    floatArg: 2.5,
+    boolArg: false,
+    listArg: [1, 2, 3],
+    enumArg: RED,
+    objectArg: { value: "baz" },
+    cursorArg: "cursor-value",
+    idArg: "id-value",
+    urlArg: "https://example.net",
+    uuidArg: "46ebd0ee-0e6d-43c9-b90d-ccc35a913f3e",
+    dateArg: "2019-01-01",
+    dateTimeArg: "1996-12-19T16:39:57-08:00",
+"#;
+
+#[test]
+fn valid_arguments_have_no_errors() {
+    assert_eq!(Vec::<QueryTrailError>::new(), try_args_errors(VALID_ARGS));
+}
+
+#[test]
+fn missing_argument() {
+    assert_eq!(
+        vec![QueryTrailError::MissingArgument("intArg".to_string())],
+        try_args_errors(&VALID_ARGS.replace("intArg: 1,", "")),
+    );
+}
+
+#[test]
+fn unexpected_value() {
+    assert_eq!(
+        vec![QueryTrailError::UnexpectedValue {
+            expected: "`i32`".to_string(),
+            found: "String".to_string(),
+        }],
+        try_args_errors(&VALID_ARGS.replace("intArg: 1,", r#"intArg: "one","#)),
+    );
+}
+
+#[test]
+fn unknown_enum_value() {
+    assert_eq!(
+        vec![QueryTrailError::UnknownEnumValue("GREEN".to_string())],
+        try_args_errors(&VALID_ARGS.replace("enumArg: RED,", "enumArg: GREEN,")),
+    );
+}
+
+#[test]
+fn unknown_input_object_key() {
+    assert_eq!(
+        vec![QueryTrailError::UnknownInputObjectKey("other".to_string())],
+        try_args_errors(&VALID_ARGS.replace(
+            r#"objectArg: { value: "baz" },"#,
+            r#"objectArg: { value: "baz", other: "qux" },"#,
+        )),
+    );
+}
+
+#[test]
+fn missing_input_object_field() {
+    assert_eq!(
+        vec![QueryTrailError::MissingInputObjectField(
+            "value".to_string()
+        )],
+        try_args_errors(&VALID_ARGS.replace(r#"objectArg: { value: "baz" },"#, "objectArg: {},")),
+    );
+}
+
+#[test]
+fn invalid_value() {
+    assert_eq!(
+        vec![QueryTrailError::InvalidValue(
+            "Error parsing URL: relative URL without a base".to_string()
+        )],
+        try_args_errors(&VALID_ARGS.replace(
+            r#"urlArg: "https://example.net","#,
+            r#"urlArg: "not a url","#,
+        )),
+    );
+}
+
+#[derive(Default)]
+pub struct Context {
+    errors: Mutex<Vec<QueryTrailError>>,
+}
+
+impl juniper::Context for Context {}
 
 fn run_query(query: &str) -> Value {
     let (res, _errors) = juniper::execute_sync(
@@ -263,7 +405,7 @@ fn run_query(query: &str) -> Value {
             juniper::EmptySubscription::new(),
         ),
         &Variables::new(),
-        &(),
+        &Context::default(),
     )
     .unwrap();
 
@@ -273,3 +415,25 @@ fn run_query(query: &str) -> Value {
     println!("--- </json> -----------------");
     json
 }
+
+// Runs the query without juniper's validation, so the look ahead contains argument values that
+// validation would otherwise reject, and returns the errors of the `try_*` accessors.
+fn try_args_errors(args: &str) -> Vec<QueryTrailError> {
+    let schema = Schema::new(Query, EmptyMutation::new(), EmptySubscription::new());
+    let query = format!("query {{ unvalidated {{ fieldWithArg({}) }} }}", args);
+    let document = juniper::parser::parse_document_source(&query, &schema.schema).unwrap();
+    let operation = juniper::executor::get_operation(&document, None).unwrap();
+    let context = Context::default();
+
+    let (_, errors) = juniper::executor::execute_validated_query(
+        &document,
+        operation,
+        &schema,
+        &Variables::new(),
+        &context,
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    context.errors.into_inner().unwrap()
+}
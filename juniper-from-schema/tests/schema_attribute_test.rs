@@ -0,0 +1,108 @@
+#![allow(dead_code, unused_variables, unused_imports)]
+
+use assert_json_diff::assert_json_include;
+use juniper::{Executor, FieldError, FieldResult, Variables, ID};
+use serde_json::{self, json, Value};
+
+pub struct MyContext;
+
+impl juniper::Context for MyContext {}
+
+#[juniper_from_schema::schema(
+    file = "tests/schemas/schema_attribute.graphql",
+    context_type = MyContext,
+    visibility = pub(crate),
+    deny = [input_type_suffix, list_nesting_depth],
+)]
+mod api {
+    pub(crate) use super::resolvers::Query;
+}
+
+mod resolvers {
+    use super::{api, MyContext};
+    use juniper::{Executor, FieldResult, ID};
+
+    pub struct Query;
+
+    impl api::QueryFields for Query {
+        fn field_user(
+            &self,
+            executor: &Executor<MyContext>,
+            trail: &api::QueryTrail<api::User, api::Walked>,
+            id: ID,
+        ) -> FieldResult<api::User> {
+            Ok(api::User::new(id, "Bob".to_string()))
+        }
+    }
+}
+
+// A second schema in the same crate doesn't clash with the first
+juniper_from_schema::graphql_schema! {
+    context_type: MyContext,
+    error_type: FieldError,
+    deny: [enum_value_case],
+    max_list_nesting_depth: 2,
+
+    schema {
+        query: Query
+    }
+
+    type Query {
+        ping: Boolean! @juniper(ownership: "owned")
+    }
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_ping(&self, executor: &Executor<MyContext>) -> FieldResult<bool> {
+        Ok(true)
+    }
+}
+
+#[test]
+fn generates_code_inside_the_module() {
+    let schema = api::Schema::new(
+        resolvers::Query,
+        juniper::EmptyMutation::new(),
+        juniper::EmptySubscription::new(),
+    );
+
+    let (res, _errors) = futures::executor::block_on(juniper::execute(
+        r#"query { user(id: "1") { id name } }"#,
+        None,
+        &schema,
+        &Variables::new(),
+        &MyContext,
+    ))
+    .unwrap();
+
+    let json: Value = serde_json::from_str(&serde_json::to_string(&res).unwrap()).unwrap();
+
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "user": { "id": "1", "name": "Bob" } })
+    );
+}
+
+#[test]
+fn literal_schema_accepts_context_type() {
+    let schema = Schema::new(
+        Query,
+        juniper::EmptyMutation::new(),
+        juniper::EmptySubscription::new(),
+    );
+
+    let (res, _errors) = futures::executor::block_on(juniper::execute(
+        "query { ping }",
+        None,
+        &schema,
+        &Variables::new(),
+        &MyContext,
+    ))
+    .unwrap();
+
+    let json: Value = serde_json::from_str(&serde_json::to_string(&res).unwrap()).unwrap();
+
+    assert_json_include!(actual: json, expected: json!({ "ping": true }));
+}
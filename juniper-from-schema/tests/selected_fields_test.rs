@@ -0,0 +1,135 @@
+#![allow(dead_code, unused_variables, unused_imports)]
+
+#[macro_use]
+extern crate juniper;
+
+use juniper::{EmptyMutation, EmptySubscription, Executor, FieldResult, Variables};
+use juniper_from_schema::graphql_schema;
+use serde_json::{json, Value};
+
+graphql_schema! {
+    schema {
+        query: Query
+    }
+
+    type Query {
+        user: User! @juniper(ownership: "owned")
+    }
+
+    type User {
+        columns: [String!]! @juniper(ownership: "owned")
+        name: String! @juniper(ownership: "owned")
+        email: String @juniper(ownership: "owned")
+        posts: [Post!]! @juniper(ownership: "owned")
+    }
+
+    type Post {
+        title: String! @juniper(ownership: "owned")
+        body: String! @juniper(ownership: "owned")
+    }
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_user(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<User, Walked>,
+    ) -> FieldResult<User> {
+        let mut columns = vec![];
+
+        for field in trail.selected_fields() {
+            match field {
+                fields::User::Columns => {}
+                fields::User::Name => columns.push("users.name".to_string()),
+                fields::User::Email => columns.push("users.email".to_string()),
+                fields::User::Posts(posts) => {
+                    for field in posts.selected_fields() {
+                        match field {
+                            fields::Post::Title => columns.push("posts.title".to_string()),
+                            fields::Post::Body => columns.push("posts.body".to_string()),
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(User(columns))
+    }
+}
+
+pub struct User(Vec<String>);
+
+impl UserFields for User {
+    fn field_columns(&self, executor: &Executor<Context>) -> FieldResult<Vec<String>> {
+        Ok(self.0.clone())
+    }
+
+    fn field_name(&self, executor: &Executor<Context>) -> FieldResult<String> {
+        Ok(String::new())
+    }
+
+    fn field_email(&self, executor: &Executor<Context>) -> FieldResult<Option<String>> {
+        Ok(None)
+    }
+
+    fn field_posts(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<Post, Walked>,
+    ) -> FieldResult<Vec<Post>> {
+        Ok(vec![])
+    }
+}
+
+pub struct Post;
+
+impl PostFields for Post {
+    fn field_title(&self, executor: &Executor<Context>) -> FieldResult<String> {
+        Ok(String::new())
+    }
+
+    fn field_body(&self, executor: &Executor<Context>) -> FieldResult<String> {
+        Ok(String::new())
+    }
+}
+
+#[test]
+fn test_selected_fields() {
+    let value = run_query(
+        r#"
+        query {
+            user {
+                columns
+                email
+                __typename
+                posts { body }
+                name
+                alsoEmail: email
+            }
+        }
+        "#,
+    );
+
+    assert_eq!(
+        json!(["users.email", "posts.body", "users.name"]),
+        value["user"]["columns"]
+    );
+}
+
+type Context = ();
+
+fn run_query(query: &str) -> Value {
+    let (res, errors) = juniper::execute_sync(
+        query,
+        None,
+        &Schema::new(Query, EmptyMutation::new(), EmptySubscription::new()),
+        &Variables::new(),
+        &(),
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    serde_json::from_str(&serde_json::to_string(&res).unwrap()).unwrap()
+}
@@ -0,0 +1,206 @@
+#![allow(dead_code, unused_variables, unused_imports)]
+
+#[macro_use]
+extern crate juniper;
+
+use juniper::{EmptyMutation, EmptySubscription, Executor, FieldResult, InputValue, Variables};
+use juniper_from_schema::{graphql_schema, ArgumentValue, SelectionTree};
+use serde_json::{json, Value};
+
+graphql_schema! {
+    schema {
+        query: Query
+    }
+
+    type Query {
+        user: User! @juniper(ownership: "owned")
+    }
+
+    type User {
+        summary: String! @juniper(ownership: "owned")
+        name: String! @juniper(ownership: "owned")
+        avatar(size: Int = 64, format: ImageFormat): String! @juniper(ownership: "owned")
+        posts(first: Int!): [Post!]! @juniper(ownership: "owned")
+        entity: Entity! @juniper(ownership: "owned")
+    }
+
+    type Post {
+        title: String! @juniper(ownership: "owned")
+    }
+
+    union Entity = User | Post
+
+    enum ImageFormat {
+        PNG
+        JPEG
+    }
+}
+
+pub struct Query;
+
+impl QueryFields for Query {
+    fn field_user(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<User, Walked>,
+    ) -> FieldResult<User> {
+        let request = serde_json::to_string(&trail.to_selection_tree())?;
+        Ok(User(backend(&request)))
+    }
+}
+
+// Stands in for a service on the other side of an RPC boundary
+fn backend(request: &str) -> String {
+    let tree: SelectionTree<User> = serde_json::from_str(request).unwrap();
+    let mut summary = vec![];
+
+    summary.push(format!("name={}", tree.name()));
+    summary.push(format!(
+        "avatar={:?}/{:?}",
+        tree.avatar_args().size(),
+        tree.avatar_args().format(),
+    ));
+
+    if let Some(posts) = tree.posts() {
+        summary.push(format!(
+            "posts={}/title={}",
+            tree.posts_args().first(),
+            posts.title()
+        ));
+    }
+
+    if let Some(entity) = tree.entity() {
+        let post: SelectionTree<Post> = (&entity).downcast();
+        summary.push(format!("entity.title={}", post.title()));
+    }
+
+    let response_keys = tree
+        .iter()
+        .map(|field| field.response_key())
+        .collect::<Vec<_>>();
+    summary.push(response_keys.join(","));
+
+    summary.join(" ")
+}
+
+pub struct User(String);
+
+impl UserFields for User {
+    fn field_summary(&self, executor: &Executor<Context>) -> FieldResult<String> {
+        Ok(self.0.clone())
+    }
+
+    fn field_name(&self, executor: &Executor<Context>) -> FieldResult<String> {
+        Ok(String::new())
+    }
+
+    fn field_avatar(
+        &self,
+        executor: &Executor<Context>,
+        size: i32,
+        format: Option<ImageFormat>,
+    ) -> FieldResult<String> {
+        Ok(String::new())
+    }
+
+    fn field_posts(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<Post, Walked>,
+        first: i32,
+    ) -> FieldResult<Vec<Post>> {
+        Ok(vec![])
+    }
+
+    fn field_entity(
+        &self,
+        executor: &Executor<Context>,
+        trail: &QueryTrail<Entity, Walked>,
+    ) -> FieldResult<Entity> {
+        Ok(Entity::Post(Post))
+    }
+}
+
+pub struct Post;
+
+impl PostFields for Post {
+    fn field_title(&self, executor: &Executor<Context>) -> FieldResult<String> {
+        Ok(String::new())
+    }
+}
+
+#[test]
+fn test_selection_tree_can_be_sent_to_another_service() {
+    let mut variables = Variables::new();
+    variables.insert("format".to_string(), InputValue::enum_value("JPEG"));
+
+    let value = run_query(
+        r#"
+        query($format: ImageFormat) {
+            user {
+                summary
+                avatar(format: $format)
+                posts(first: 3) { title }
+                entity { ... on Post { title } }
+                big: avatar(size: 256)
+            }
+        }
+        "#,
+        variables,
+    );
+
+    assert_eq!(
+        json!(
+            "name=false avatar=64/Some(Jpeg) posts=3/title=true entity.title=true \
+             summary,avatar,posts,entity,big"
+        ),
+        value["user"]["summary"]
+    );
+}
+
+#[test]
+fn test_serialized_selection_tree() {
+    let tree: SelectionTree<User> = serde_json::from_value(json!({
+        "fields": [
+            { "name": "name", "alias": "fullName", "arguments": {}, "fields": [] },
+            {
+                "name": "posts",
+                "alias": null,
+                "arguments": { "first": { "Scalar": { "Int": 3 } } },
+                "fields": [{ "name": "title", "alias": null, "arguments": {}, "fields": [] }]
+            }
+        ]
+    }))
+    .unwrap();
+
+    assert!(tree.name());
+    assert!(!tree.summary());
+    assert_eq!(3, tree.posts_args().first());
+    assert!(tree.posts().unwrap().title());
+
+    let name = tree.iter().next().unwrap();
+    assert_eq!("fullName", name.response_key());
+    assert_eq!(Some("fullName"), name.alias());
+
+    let posts = tree.iter().nth(1).unwrap();
+    assert_eq!(
+        Some(&ArgumentValue::Scalar(juniper::DefaultScalarValue::Int(3))),
+        posts.arguments().get("first")
+    );
+}
+
+type Context = ();
+
+fn run_query(query: &str, variables: Variables) -> Value {
+    let (res, errors) = juniper::execute_sync(
+        query,
+        None,
+        &Schema::new(Query, EmptyMutation::new(), EmptySubscription::new()),
+        &variables,
+        &(),
+    )
+    .unwrap();
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    serde_json::from_str(&serde_json::to_string(&res).unwrap()).unwrap()
+}